@@ -34,6 +34,7 @@ use runtime_primitives::traits::{
 };
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::ApplyError;
+use runtime_primitives::transaction_validity::OPERATIONAL_TRANSACTION_PRIORITY;
 use transaction_pool::txpool::{self, Pool as TransactionPool};
 use inherents::{InherentData, pool::InherentsPool};
 use substrate_telemetry::{telemetry, CONSENSUS_INFO};
@@ -217,6 +218,8 @@ impl<Block, C, A> Proposer<Block, C, A>	where
 				// proceed with transactions
 				let mut is_first = true;
 				let mut skipped = 0;
+				// set once the share of the block available to normal transactions is used up.
+				let mut only_operational = false;
 				let mut unqueue_invalid = Vec::new();
 				let pending_iterator = self.transaction_pool.ready();
 
@@ -227,6 +230,10 @@ impl<Block, C, A> Proposer<Block, C, A>	where
 						break;
 					}
 
+					if only_operational && pending.priority < OPERATIONAL_TRANSACTION_PRIORITY {
+						continue;
+					}
+
 					trace!("[{:?}] Pushing to the block.", pending.hash);
 					match block_builder.push_extrinsic(pending.data.clone()) {
 						Ok(()) => {
@@ -242,6 +249,10 @@ impl<Block, C, A> Proposer<Block, C, A>	where
 									"Block seems full, but will try {} more transactions before quitting.",
 									MAX_SKIPPED_TRANSACTIONS - skipped
 								);
+							} else if !only_operational {
+								debug!("Block is full for normal transactions, only trying operational ones.");
+								only_operational = true;
+								skipped = 0;
 							} else {
 								debug!("Block is full, proceed with proposing.");
 								break;
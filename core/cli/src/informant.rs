@@ -26,6 +26,7 @@ use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
 use network::{SyncState, SyncProvider};
 use client::{backend::Backend, BlockchainEvents};
 use substrate_telemetry::{telemetry, SUBSTRATE_INFO};
+use substrate_metrics as metrics;
 use log::{info, warn};
 
 use runtime_primitives::generic::BlockId;
@@ -44,6 +45,15 @@ pub fn start<C>(service: &Service<C>, exit: ::exit_future::Exit, handle: TaskExe
 	let mut sys = System::new();
 	let self_pid = get_current_pid();
 
+	let best_height = metrics::gauge("substrate_block_height_best", "Number of the best block");
+	let finalized_height = metrics::gauge("substrate_block_height_finalized", "Number of the last finalized block");
+	let peers = metrics::gauge("substrate_sync_peers", "Number of connected peers");
+	let download = metrics::gauge("substrate_network_download_bytes_per_second", "Average download bandwidth");
+	let upload = metrics::gauge("substrate_network_upload_bytes_per_second", "Average upload bandwidth");
+	let ready_transactions = metrics::gauge("substrate_txpool_ready", "Number of ready transactions in the pool");
+	let future_transactions = metrics::gauge("substrate_txpool_future", "Number of future transactions in the pool");
+	let txpool_metrics = (ready_transactions.clone(), future_transactions.clone());
+
 	let display_notifications = network.status().for_each(move |sync_status| {
 
 		if let Ok(info) = client.info() {
@@ -88,6 +98,14 @@ pub fn start<C>(service: &Service<C>, exit: ::exit_future::Exit, handle: TaskExe
 				(proc.cpu_usage(), proc.memory())
 			} else { (0.0, 0) };
 
+			best_height.set(best_number as i64);
+			finalized_height.set(finalized_number as i64);
+			peers.set(num_peers as i64);
+			download.set(bandwidth_download as i64);
+			upload.set(bandwidth_upload as i64);
+			ready_transactions.set(txpool_status.ready as i64);
+			future_transactions.set(txpool_status.future as i64);
+
 			let network_state = network.network_state();
 
 			telemetry!(
@@ -150,8 +168,11 @@ pub fn start<C>(service: &Service<C>, exit: ::exit_future::Exit, handle: TaskExe
 	});
 
 	let txpool = service.transaction_pool();
+	let (ready_transactions, future_transactions) = txpool_metrics;
 	let display_txpool_import = txpool.import_notification_stream().for_each(move |_| {
 		let status = txpool.status();
+		ready_transactions.set(status.ready as i64);
+		future_transactions.set(status.future as i64);
 		telemetry!(SUBSTRATE_INFO; "txpool.import"; "ready" => status.ready, "future" => status.future);
 		Ok(())
 	});
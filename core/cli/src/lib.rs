@@ -401,6 +401,15 @@ where
 		.unwrap_or_else(|| keystore_path(&base_path, config.chain_spec.id()))
 		.to_string_lossy()
 		.into();
+	config.keystore_password = match cli.password_filename {
+		Some(path) => {
+			let password = fs::read_to_string(&path).map_err(|e| input_err(
+				format!("Unable to read password file {}: {}", path.display(), e)
+			))?;
+			Some(password.trim_end_matches(|c| c == '\r' || c == '\n').to_owned())
+		},
+		None => cli.password,
+	};
 
 	config.database_path =
 		db_path(&base_path, config.chain_spec.id()).to_string_lossy().into();
@@ -480,6 +489,7 @@ where
 	config.rpc_ws = Some(
 		parse_address(&format!("{}:{}", ws_interface, 9944), cli.ws_port)?
 	);
+	config.rpc_methods = cli.rpc_methods.into();
 	config.rpc_cors = cli.rpc_cors.unwrap_or_else(|| if is_dev {
 		log::warn!("Running in --dev mode, RPC CORS has been disabled.");
 		None
@@ -494,6 +504,13 @@ where
 		])
 	});
 
+	if let Some(port) = cli.prometheus_port {
+		let prometheus_interface: &str = if cli.prometheus_external { "0.0.0.0" } else { "127.0.0.1" };
+		config.prometheus_endpoint = Some(
+			parse_address(&format!("{}:{}", prometheus_interface, 9615), Some(port))?
+		);
+	}
+
 	// Override telemetry
 	if cli.no_telemetry {
 		config.telemetry_endpoints = None;
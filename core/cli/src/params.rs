@@ -65,6 +65,26 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// Which RPC methods to expose
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	pub enum RpcMethods {
+		Auto,
+		Safe,
+		Unsafe,
+	}
+}
+
+impl Into<service::config::RpcMethods> for RpcMethods {
+	fn into(self) -> service::config::RpcMethods {
+		match self {
+			RpcMethods::Auto => service::config::RpcMethods::Auto,
+			RpcMethods::Safe => service::config::RpcMethods::Safe,
+			RpcMethods::Unsafe => service::config::RpcMethods::Unsafe,
+		}
+	}
+}
+
 /// Shared parameters used by all `CoreParams`.
 #[derive(Debug, StructOpt, Clone)]
 pub struct SharedParams {
@@ -297,6 +317,14 @@ pub struct RunCmd {
 	#[structopt(long = "key", value_name = "STRING")]
 	pub key: Option<String>,
 
+	/// Password used to encrypt and decrypt the keys of the keystore
+	#[structopt(long = "password", value_name = "PASSWORD", conflicts_with = "password_filename")]
+	pub password: Option<String>,
+
+	/// File holding the password used to encrypt and decrypt the keys of the keystore
+	#[structopt(long = "password-filename", value_name = "PATH", parse(from_os_str))]
+	pub password_filename: Option<PathBuf>,
+
 	/// Enable validator mode
 	#[structopt(long = "validator")]
 	pub validator: bool,
@@ -341,6 +369,29 @@ pub struct RunCmd {
 	#[structopt(long = "rpc-cors", value_name = "ORIGINS", parse(try_from_str = "parse_cors"))]
 	pub rpc_cors: Option<Option<Vec<String>>>,
 
+	/// RPC methods to expose.
+	/// `Unsafe` exposes every method, `Safe` only the methods which are safe to be called by anyone.
+	/// `Auto` exposes every method on servers listening on a local interface and only safe methods otherwise.
+	#[structopt(
+		long = "rpc-methods",
+		value_name = "METHOD SET",
+		raw(
+			possible_values = "&RpcMethods::variants()",
+			case_insensitive = "true",
+			default_value = r#""Auto""#
+		)
+	)]
+	pub rpc_methods: RpcMethods,
+
+	/// Expose Prometheus metrics on `/metrics` of an HTTP server listening on given TCP port.
+	/// Metrics are not exported unless this is set.
+	#[structopt(long = "prometheus-port", value_name = "PORT")]
+	pub prometheus_port: Option<u16>,
+
+	/// Listen to all Prometheus interfaces (default is local)
+	#[structopt(long = "prometheus-external")]
+	pub prometheus_external: bool,
+
 	/// Specify the pruning mode, a number of blocks to keep or 'archive'. Default is 256.
 	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
 	pub pruning: Option<String>,
@@ -356,6 +407,8 @@ pub struct RunCmd {
 	/// The URL of the telemetry server to connect to. This flag can be passed multiple times
 	/// as a mean to specify multiple telemetry endpoints. Verbosity levels range from 0-9, with
 	/// 0 denoting the least verbosity. If no verbosity level is specified the default is 0.
+	/// Records are written locally as newline-delimited JSON to `file:///path/to/file` and
+	/// `stdout://` URLs.
 	#[structopt(long = "telemetry-url", value_name = "URL VERBOSITY", parse(try_from_str = "parse_telemetry_endpoints"))]
 	pub telemetry_endpoints: Vec<(String, u8)>,
 
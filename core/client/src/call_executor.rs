@@ -21,7 +21,7 @@ use runtime_primitives::{
 };
 use state_machine::{
 	self, OverlayedChanges, Ext, CodeExecutor, ExecutionManager,
-	ExecutionStrategy, NeverOffchainExt, backend::Backend as _,
+	ExecutionStrategy, NeverOffchainExt, StorageAccess, TracingExt, backend::Backend as _,
 };
 use executor::{RuntimeVersion, RuntimeInfo, NativeVersion};
 use hash_db::Hasher;
@@ -143,6 +143,18 @@ where
 		call_data: &[u8]
 	) -> Result<(Vec<u8>, Vec<Vec<u8>>), error::Error>;
 
+	/// Execute a call to a contract on top of given state, recording every storage
+	/// access made by the call.
+	///
+	/// Changes made by the call are kept in the overlay.
+	fn trace_at_state<S: state_machine::Backend<H>>(
+		&self,
+		state: &S,
+		overlay: &mut OverlayedChanges,
+		method: &str,
+		call_data: &[u8],
+	) -> Result<(Vec<u8>, Vec<StorageAccess>), error::Error>;
+
 	/// Get runtime version if supported.
 	fn native_runtime_version(&self) -> Option<&NativeVersion>;
 }
@@ -355,6 +367,27 @@ where
 		.map_err(Into::into)
 	}
 
+	fn trace_at_state<S: state_machine::Backend<Blake2Hasher>>(
+		&self,
+		state: &S,
+		overlay: &mut OverlayedChanges,
+		method: &str,
+		call_data: &[u8],
+	) -> error::Result<(Vec<u8>, Vec<StorageAccess>)> {
+		let mut ext = Ext::new(overlay, state, self.backend.changes_trie_storage(), NeverOffchainExt::new());
+		let mut tracing = TracingExt::new(&mut ext);
+		let (result, _) = self.executor.call::<_, NeverNativeValue, fn() -> _>(
+			&mut tracing,
+			method,
+			call_data,
+			false,
+			None,
+		);
+		let accesses = tracing.into_accesses();
+		let result = result.map_err(|e| Box::new(e) as Box<state_machine::Error>)?;
+		Ok((result.into_encoded(), accesses))
+	}
+
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		Some(self.executor.native_version())
 	}
@@ -17,7 +17,7 @@
 //! Substrate Client
 
 use std::{
-	marker::PhantomData, collections::{HashSet, BTreeMap, HashMap}, sync::Arc,
+	marker::PhantomData, collections::{HashSet, BTreeMap, BTreeSet, HashMap}, sync::Arc,
 	panic::UnwindSafe, result, cell::RefCell, rc::Rc,
 };
 use crate::error::Error;
@@ -52,9 +52,10 @@ use primitives::storage::well_known_keys;
 use parity_codec::{Encode, Decode};
 use state_machine::{
 	DBValue, Backend as StateBackend, CodeExecutor, ChangesTrieAnchorBlockId,
-	ExecutionStrategy, ExecutionManager, prove_read, prove_child_read,
+	ExecutionStrategy, ExecutionManager, prove_read, prove_read_keys, prove_child_read,
 	ChangesTrieRootsStorage, ChangesTrieStorage,
-	key_changes, key_changes_proof, OverlayedChanges, NeverOffchainExt,
+	key_changes, key_changes_proof, OverlayedChanges, NeverOffchainExt, StorageAccess,
+	changed_keys_in_changes_tries,
 };
 use hash_db::Hasher;
 
@@ -203,6 +204,28 @@ pub struct FinalityNotification<Block: BlockT> {
 	pub header: Block::Header,
 }
 
+/// Storage accesses made while re-executing a block.
+#[derive(Clone, Debug)]
+pub struct BlockTrace {
+	/// Accesses made while initializing the block.
+	pub initialization: Vec<StorageAccess>,
+	/// Traces of the block's extrinsics, in order.
+	pub extrinsics: Vec<ExtrinsicTrace>,
+	/// Accesses made while finalizing the block.
+	pub finalization: Vec<StorageAccess>,
+}
+
+/// Storage accesses made while applying a single extrinsic.
+#[derive(Clone, Debug)]
+pub struct ExtrinsicTrace {
+	/// Encoded extrinsic.
+	pub extrinsic: Vec<u8>,
+	/// Encoded result of the `apply_extrinsic` runtime call.
+	pub result: Vec<u8>,
+	/// Storage accesses, in order.
+	pub storage: Vec<StorageAccess>,
+}
+
 // used in importing a block, where additional changes are made after the runtime
 // executed.
 enum PrePostHeader<H> {
@@ -343,6 +366,83 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		Ok(keys)
 	}
 
+	/// Return up to `count` storage entry keys with given prefix in state in a block of given hash,
+	/// in lexicographic order and following `start_key` if given.
+	pub fn storage_keys_paged(
+		&self,
+		id: &BlockId<Block>,
+		key_prefix: &StorageKey,
+		start_key: Option<&StorageKey>,
+		count: usize,
+	) -> error::Result<Vec<StorageKey>> {
+		Ok(self.storage_pairs_paged(id, key_prefix, start_key, count)?
+			.into_iter()
+			.map(|(key, _)| key)
+			.collect())
+	}
+
+	/// Return up to `count` storage entries with given key prefix in state in a block of given hash,
+	/// in lexicographic order of keys and following `start_key` if given.
+	pub fn storage_pairs_paged(
+		&self,
+		id: &BlockId<Block>,
+		key_prefix: &StorageKey,
+		start_key: Option<&StorageKey>,
+		count: usize,
+	) -> error::Result<Vec<(StorageKey, StorageData)>> {
+		let mut pairs = Vec::new();
+		if count == 0 {
+			return Ok(pairs);
+		}
+
+		let start_at = start_key.map(|key| &key.0[..]);
+		self.state_at(id)?.for_key_values_with_prefix(&key_prefix.0, start_at, |key, value| {
+			if start_at != Some(key) {
+				pairs.push((StorageKey(key.to_vec()), StorageData(value.to_vec())));
+			}
+			pairs.len() < count
+		});
+		Ok(pairs)
+	}
+
+	/// Return storage entries with given key prefix whose value differs between the states
+	/// of two blocks, along with their value at `to` (`None` if the entry has been removed).
+	///
+	/// When `from` is an ancestor of `to` and changes tries are available for the blocks in
+	/// between, only the keys changed at these blocks are compared. Otherwise both state tries
+	/// are walked, skipping the subtrees they have in common.
+	pub fn storage_diff(
+		&self,
+		from: &BlockId<Block>,
+		to: &BlockId<Block>,
+		key_prefix: &StorageKey,
+	) -> error::Result<Vec<(StorageKey, Option<StorageData>)>> {
+		let changed_keys = self.changed_keys_between(from, to, key_prefix)?;
+		let from_state = self.state_at(from)?;
+		let to_state = self.state_at(to)?;
+
+		let diff = match changed_keys {
+			Some(keys) => {
+				let mut diff = Vec::new();
+				for key in keys {
+					let old = from_state.storage(&key).map_err(|e| error::Error::from_state(Box::new(e)))?;
+					let new = to_state.storage(&key).map_err(|e| error::Error::from_state(Box::new(e)))?;
+					if old != new {
+						diff.push((key, new));
+					}
+				}
+				diff
+			},
+			None => {
+				let from_trie = from_state.try_into_trie_backend().ok_or(error::Error::NotAvailableOnLightClient)?;
+				let to_trie = to_state.try_into_trie_backend().ok_or(error::Error::NotAvailableOnLightClient)?;
+				state_machine::storage_diff(&from_trie, &to_trie, &key_prefix.0)
+					.map_err(|e| error::Error::from_state(Box::new(e)))?
+			},
+		};
+		Ok(diff.into_iter().map(|(key, value)| (StorageKey(key), value.map(StorageData))).collect())
+	}
+
 	/// Return single storage entry of contract under given address in state in a block of given hash.
 	pub fn storage(&self, id: &BlockId<Block>, key: &StorageKey) -> error::Result<Option<StorageData>> {
 		Ok(self.state_at(id)?
@@ -374,6 +474,14 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 				.map_err(Into::into))
 	}
 
+	/// Reads storage values at a given block + keys, returning a single read proof
+	/// of all of them.
+	pub fn read_proof_of_keys(&self, id: &BlockId<Block>, keys: &[Vec<u8>]) -> error::Result<Vec<Vec<u8>>> {
+		self.state_at(id)
+			.and_then(|state| prove_read_keys(state, keys)
+				.map_err(Into::into))
+	}
+
 	/// Reads child storage value at a given block + storage_key + key, returning
 	/// read proof.
 	pub fn read_child_proof(
@@ -398,6 +506,55 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		prove_execution(state, header, &self.executor, method, call_data)
 	}
 
+	/// Re-execute a block on top of its parent's state, recording the storage accesses
+	/// made while initializing it, by each extrinsic and while finalizing it.
+	///
+	/// No changes are made.
+	pub fn trace_block(&self, id: &BlockId<Block>) -> error::Result<BlockTrace> {
+		let unknown_block = || error::Error::UnknownBlock(format!("{}", id));
+		let header = self.header(id)?.ok_or_else(unknown_block)?;
+		let extrinsics = self.body(id)?.ok_or_else(unknown_block)?;
+		let state = self.state_at(&BlockId::Hash(*header.parent_hash()))?;
+		let mut overlay = OverlayedChanges::default();
+
+		// the stored header is sealed, while the runtime is given the header without its
+		// digest, as when the block is built: the seal is added afterwards and the runtime
+		// deposits the other digest items itself
+		let unsealed_header = <<Block as BlockT>::Header as HeaderT>::new(
+			*header.number(),
+			*header.extrinsics_root(),
+			*header.state_root(),
+			*header.parent_hash(),
+			Default::default(),
+		);
+		let (_, initialization) = self.executor.trace_at_state(
+			&state,
+			&mut overlay,
+			"Core_initialize_block",
+			&unsealed_header.encode(),
+		)?;
+		let extrinsics = extrinsics.into_iter()
+			.map(|extrinsic| {
+				let extrinsic = extrinsic.encode();
+				let (result, storage) = self.executor.trace_at_state(
+					&state,
+					&mut overlay,
+					"BlockBuilder_apply_extrinsic",
+					&extrinsic,
+				)?;
+				Ok(ExtrinsicTrace { extrinsic, result, storage })
+			})
+			.collect::<error::Result<_>>()?;
+		let (_, finalization) = self.executor.trace_at_state(
+			&state,
+			&mut overlay,
+			"BlockBuilder_finalize_block",
+			&[],
+		)?;
+
+		Ok(BlockTrace { initialization, extrinsics, finalization })
+	}
+
 	/// Reads given header and generates CHT-based header proof.
 	pub fn header_proof(&self, id: &BlockId<Block>) -> error::Result<(Block::Header, Vec<Vec<u8>>)> {
 		self.header_proof_with_cht_size(id, cht::SIZE)
@@ -607,6 +764,51 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		Ok(proof)
 	}
 
+	/// Returns the keys with given prefix changed at the blocks following `from` up to `to`,
+	/// read from changes tries.
+	///
+	/// Returns `None` if `from` is not an ancestor of `to` or if the changes tries of these
+	/// blocks are not available.
+	fn changed_keys_between(
+		&self,
+		from: &BlockId<Block>,
+		to: &BlockId<Block>,
+		key_prefix: &StorageKey,
+	) -> error::Result<Option<BTreeSet<Vec<u8>>>> {
+		let (config, storage) = match self.require_changes_trie() {
+			Ok((config, storage)) => (config, storage),
+			Err(error::Error::ChangesTriesNotSupported) => return Ok(None),
+			Err(err) => return Err(err),
+		};
+		let from_number = self.backend.blockchain().expect_block_number_from_id(from)?.as_();
+		let to_number = self.backend.blockchain().expect_block_number_from_id(to)?.as_();
+		let to_hash = self.backend.blockchain().expect_block_hash_from_id(to)?;
+		if from_number >= to_number {
+			return Ok(None);
+		}
+
+		let route = blockchain::tree_route(self.backend.blockchain(), from.clone(), to.clone())?;
+		if !route.retracted().is_empty() {
+			return Ok(None);
+		}
+		let finalized_number = self.backend.blockchain().info()?.finalized_number;
+		if storage.oldest_changes_trie_block(&config, finalized_number.as_()) > from_number + 1 {
+			return Ok(None);
+		}
+
+		changed_keys_in_changes_tries::<_, Blake2Hasher>(
+			&*storage,
+			from_number + 1,
+			&ChangesTrieAnchorBlockId {
+				hash: convert_hash(&to_hash),
+				number: to_number,
+			},
+			&key_prefix.0,
+		)
+		.map(Some)
+		.map_err(|err| error::Error::ChangesTrieAccessFailed(err))
+	}
+
 	/// Returns changes trie configuration and storage or an error if it is not supported.
 	fn require_changes_trie(&self) -> error::Result<(ChangesTrieConfiguration, &B::ChangesTrieStorage)> {
 		let config = self.changes_trie_config()?;
@@ -2324,6 +2526,36 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn storage_diff_works() {
+		let (client, _, _) = prepare_client_with_key_changes();
+		let balance_key = |account: AccountKeyring| blake2_256(&runtime::system::balance_of_key(account.into())).to_vec();
+		let from = BlockId::Number(1);
+		let to = BlockId::Number(4);
+
+		let diff = client.storage_diff(&from, &to, &StorageKey(Vec::new())).unwrap();
+		let changed_keys = diff.iter().map(|(key, _)| key.0.clone()).collect::<Vec<_>>();
+		assert!(changed_keys.contains(&balance_key(AccountKeyring::Alice)));
+		assert!(changed_keys.contains(&balance_key(AccountKeyring::Eve)));
+		assert!(!changed_keys.contains(&balance_key(AccountKeyring::Bob)));
+
+		// keys read from the changes tries give the same diff as walking both states
+		let from_trie = client.state_at(&from).unwrap().try_into_trie_backend().unwrap();
+		let to_trie = client.state_at(&to).unwrap().try_into_trie_backend().unwrap();
+		let expected = state_machine::storage_diff(&from_trie, &to_trie, &[]).unwrap();
+		assert_eq!(
+			diff,
+			expected.into_iter().map(|(key, value)| (StorageKey(key), value.map(StorageData))).collect::<Vec<_>>(),
+		);
+
+		let eve_key = StorageKey(balance_key(AccountKeyring::Eve));
+		assert_eq!(
+			client.storage_diff(&from, &to, &eve_key).unwrap(),
+			vec![(eve_key.clone(), client.storage(&to, &eve_key).unwrap())],
+		);
+		assert!(client.storage_diff(&to, &to, &StorageKey(Vec::new())).unwrap().is_empty());
+	}
+
 	#[test]
 	fn import_with_justification() {
 		use test_client::blockchain::Backend;
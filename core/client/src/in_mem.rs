@@ -36,6 +36,8 @@ use crate::light;
 use crate::leaves::LeafSet;
 use crate::blockchain::{self, BlockStatus, HeaderBackend};
 
+pub use primitives::offchain::InMemOffchainStorage as OffchainStorage;
+
 struct PendingBlock<B: BlockT> {
 	block: StoredBlock<B>,
 	state: NewBlockState,
@@ -531,6 +533,7 @@ where
 	states: RwLock<HashMap<Block::Hash, InMemory<H>>>,
 	changes_trie_storage: ChangesTrieStorage<H>,
 	blockchain: Blockchain<Block>,
+	offchain_storage: OffchainStorage,
 }
 
 impl<Block, H> Backend<Block, H>
@@ -545,6 +548,7 @@ where
 			states: RwLock::new(HashMap::new()),
 			changes_trie_storage: ChangesTrieStorage(InMemoryChangesTrieStorage::new()),
 			blockchain: Blockchain::new(),
+			offchain_storage: Default::default(),
 		}
 	}
 }
@@ -580,6 +584,7 @@ where
 	type Blockchain = Blockchain<Block>;
 	type State = InMemory<H>;
 	type ChangesTrieStorage = ChangesTrieStorage<H>;
+	type OffchainStorage = OffchainStorage;
 
 	fn begin_operation(&self) -> error::Result<Self::BlockImportOperation> {
 		let old_state = self.state_at(BlockId::Hash(Default::default()))?;
@@ -653,6 +658,10 @@ where
 		Some(&self.changes_trie_storage)
 	}
 
+	fn offchain_storage(&self) -> Option<Self::OffchainStorage> {
+		Some(self.offchain_storage.clone())
+	}
+
 	fn state_at(&self, block: BlockId<Block>) -> error::Result<Self::State> {
 		match block {
 			BlockId::Hash(h) if h == Default::default() => {
@@ -113,6 +113,7 @@ impl<S, F, Block, H> ClientBackend<Block, H> for Backend<S, F, H> where
 	type Blockchain = Blockchain<S, F>;
 	type State = OnDemandOrGenesisState<Block, S, F, H>;
 	type ChangesTrieStorage = in_mem::ChangesTrieStorage<H>;
+	type OffchainStorage = in_mem::OffchainStorage;
 
 	fn begin_operation(&self) -> ClientResult<Self::BlockImportOperation> {
 		Ok(ImportOperation {
@@ -190,6 +191,10 @@ impl<S, F, Block, H> ClientBackend<Block, H> for Backend<S, F, H> where
 		None
 	}
 
+	fn offchain_storage(&self) -> Option<Self::OffchainStorage> {
+		None
+	}
+
 	fn state_at(&self, block: BlockId<Block>) -> ClientResult<Self::State> {
 		let block_number = self.blockchain.expect_block_number_from_id(&block)?;
 
@@ -354,6 +359,15 @@ where
 		// whole state is not available on light node
 	}
 
+	fn for_key_values_with_prefix<A: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		_prefix: &[u8],
+		_start_at: Option<&[u8]>,
+		_action: A,
+	) {
+		// whole state is not available on light node
+	}
+
 	fn storage_root<I>(&self, _delta: I) -> (H::Out, Self::Transaction)
 	where
 		I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)>
@@ -429,6 +443,20 @@ where
 		}
 	}
 
+	fn for_key_values_with_prefix<A: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		action: A,
+	) {
+		match *self {
+			OnDemandOrGenesisState::OnDemand(ref state) =>
+				StateBackend::<H>::for_key_values_with_prefix(state, prefix, start_at, action),
+			OnDemandOrGenesisState::Genesis(ref state) =>
+				state.for_key_values_with_prefix(prefix, start_at, action),
+		}
+	}
+
 	fn storage_root<I>(&self, delta: I) -> (H::Out, Self::Transaction)
 	where
 		I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)>
@@ -30,7 +30,7 @@ use runtime_primitives::traits::{As, Block as BlockT, Header as HeaderT};
 use state_machine::{
 	self, Backend as StateBackend, CodeExecutor, OverlayedChanges,
 	ExecutionStrategy, create_proof_check_backend,
-	execution_proof_check_on_trie_backend, ExecutionManager, NeverOffchainExt
+	execution_proof_check_on_trie_backend, ExecutionManager, NeverOffchainExt, StorageAccess,
 };
 use hash_db::Hasher;
 
@@ -184,6 +184,16 @@ where
 		Err(ClientError::NotAvailableOnLightClient.into())
 	}
 
+	fn trace_at_state<S: state_machine::Backend<Blake2Hasher>>(
+		&self,
+		_state: &S,
+		_changes: &mut OverlayedChanges,
+		_method: &str,
+		_call_data: &[u8]
+	) -> ClientResult<(Vec<u8>, Vec<StorageAccess>)> {
+		Err(ClientError::NotAvailableOnLightClient.into())
+	}
+
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		None
 	}
@@ -378,6 +388,16 @@ impl<Block, B, Remote, Local> CallExecutor<Block, Blake2Hasher> for
 		self.remote.prove_at_trie_state(state, changes, method, call_data)
 	}
 
+	fn trace_at_state<S: state_machine::Backend<Blake2Hasher>>(
+		&self,
+		state: &S,
+		changes: &mut OverlayedChanges,
+		method: &str,
+		call_data: &[u8]
+	) -> ClientResult<(Vec<u8>, Vec<StorageAccess>)> {
+		self.remote.trace_at_state(state, changes, method, call_data)
+	}
+
 	fn native_runtime_version(&self) -> Option<&NativeVersion> {
 		None
 	}
@@ -44,6 +44,7 @@ pub use parity_codec::{Encode, Decode};
 use crate::error;
 use sr_api_macros::decl_runtime_apis;
 use primitives::OpaqueMetadata;
+use runtime_primitives::ApplyResult;
 #[cfg(feature = "std")]
 use std::{panic::UnwindSafe, cell::RefCell, rc::Rc};
 use rstd::vec::Vec;
@@ -156,6 +157,17 @@ pub trait CallRuntimeAt<Block: BlockT> {
 	fn runtime_version_at(&self, at: &BlockId<Block>) -> error::Result<RuntimeVersion>;
 }
 
+/// An event deposited in a block, as returned by the `EventsApi`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BlockEvent {
+	/// Index of the extrinsic which deposited the event, `None` if it was deposited while
+	/// finalizing the block.
+	pub extrinsic_index: Option<u32>,
+	/// The encoded event, starting with its module index and event index.
+	pub event: Vec<u8>,
+}
+
 decl_runtime_apis! {
 	/// The `Core` api trait that is mandatory for each runtime.
 	#[core_trait]
@@ -187,5 +199,34 @@ decl_runtime_apis! {
 		/// Validate the given transaction.
 		fn validate_transaction(tx: <Block as BlockT>::Extrinsic) -> TransactionValidity;
 	}
+
+	/// The `DryRunApi` api trait for applying an extrinsic without keeping its changes.
+	pub trait DryRunApi {
+		/// Apply the given extrinsic, returning the dispatch result and the encoded events
+		/// deposited while applying it.
+		fn dry_run(extrinsic: <Block as BlockT>::Extrinsic) -> (ApplyResult, Vec<u8>);
+	}
+
+	/// The `TransactionPaymentApi` api trait for querying the fees paid by extrinsics.
+	pub trait TransactionPaymentApi<Balance> where Balance: Encode + Decode {
+		/// Returns the fee the given extrinsic would pay for being included in a block.
+		fn query_fee(extrinsic: <Block as BlockT>::Extrinsic) -> Balance;
+	}
+
+	/// The `AccountNonceApi` api trait for querying the on-chain nonces of accounts.
+	pub trait AccountNonceApi<AccountId, Index> where
+		AccountId: Encode + Decode,
+		Index: Encode + Decode,
+	{
+		/// Returns the nonce the next extrinsic signed by the given account must have.
+		fn account_nonce(account: AccountId) -> Index;
+	}
+
+	/// The `EventsApi` api trait for querying the events deposited in a block.
+	pub trait EventsApi {
+		/// Returns the events deposited while executing the block, in order.
+		#[skip_initialize_block]
+		fn block_events() -> Vec<BlockEvent>;
+	}
 }
 
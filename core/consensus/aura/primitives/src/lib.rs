@@ -20,10 +20,47 @@
 
 use substrate_client::decl_runtime_apis;
 use runtime_primitives::ConsensusEngineId;
+use runtime_primitives::traits::Verify;
+use parity_codec::{Encode, Decode};
 
 /// The `ConsensusEngineId` of AuRa.
 pub const AURA_ENGINE_ID: ConsensusEngineId = [b'a', b'u', b'r', b'a'];
 
+/// Proof that an authority sealed two different headers for the same slot.
+///
+/// An Aura seal is the signature of `(slot, pre_hash)`, where `pre_hash` is the
+/// hash of the header without the seal, so the pre-seal hashes and signatures
+/// are all that is needed to check the proof.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EquivocationProof<Hash, AuthorityId, Signature> {
+	/// The slot both headers were sealed for.
+	pub slot: u64,
+	/// The authority which sealed both headers.
+	pub offender: AuthorityId,
+	/// Pre-seal hash and seal signature of the first header.
+	pub first_header: (Hash, Signature),
+	/// Pre-seal hash and seal signature of the second header.
+	pub second_header: (Hash, Signature),
+}
+
+impl<Hash, AuthorityId, Signature> EquivocationProof<Hash, AuthorityId, Signature> where
+	Hash: Encode + PartialEq,
+	Signature: Verify<Signer=AuthorityId>,
+{
+	/// Returns true if the headers are different and both are sealed by the offender
+	/// for the slot.
+	pub fn is_valid(&self) -> bool {
+		let sealed = |(hash, signature): &(Hash, Signature)| {
+			signature.verify(&(self.slot, hash).encode()[..], &self.offender)
+		};
+
+		self.first_header.0 != self.second_header.0
+			&& sealed(&self.first_header)
+			&& sealed(&self.second_header)
+	}
+}
+
 decl_runtime_apis! {
 	/// API necessary for block authorship with aura.
 	pub trait AuraApi {
@@ -31,7 +31,7 @@ use std::{sync::Arc, time::Duration, thread, marker::PhantomData, hash::Hash, fm
 use parity_codec::{Encode, Decode};
 use consensus_common::{self, Authorities, BlockImport, Environment, Proposer,
 	ForkChoiceStrategy, ImportBlock, BlockOrigin, Error as ConsensusError,
-	SelectChain, well_known_cache_keys
+	SelectChain, SharedKey, well_known_cache_keys
 };
 use consensus_common::import_queue::{
 	Verifier, BasicQueue, SharedBlockImport, SharedJustificationImport, SharedFinalityProofImport,
@@ -63,10 +63,10 @@ use srml_aura::{
 };
 use substrate_telemetry::{telemetry, CONSENSUS_TRACE, CONSENSUS_DEBUG, CONSENSUS_WARN, CONSENSUS_INFO};
 
-use slots::{CheckedHeader, SlotWorker, SlotInfo, SlotCompatible, slot_now};
+use slots::{CheckedHeader, SlotWorker, SlotInfo, SlotCompatible, SlotHeaders, slot_now};
 
 pub use aura_primitives::*;
-pub use consensus_common::{SyncOracle, ExtraVerification};
+pub use consensus_common::{SyncOracle, ExtraVerification, ReportMisbehavior};
 
 type AuthorityId<P> = <P as Pair>::Public;
 type Signature<P> = <P as Pair>::Signature;
@@ -185,7 +185,7 @@ impl SlotCompatible for AuraSlotCompatible {
 #[deprecated(since = "1.1", note = "Please spawn a thread manually")]
 pub fn start_aura_thread<B, C, SC, E, I, P, SO, Error, OnExit>(
 	slot_duration: SlotDuration,
-	local_key: Arc<P>,
+	local_key: SharedKey<P>,
 	client: Arc<C>,
 	select_chain: SC,
 	block_import: Arc<I>,
@@ -236,7 +236,7 @@ pub fn start_aura_thread<B, C, SC, E, I, P, SO, Error, OnExit>(
 /// Start the aura worker. The returned future should be run in a tokio runtime.
 pub fn start_aura<B, C, SC, E, I, P, SO, Error, OnExit>(
 	slot_duration: SlotDuration,
-	local_key: Arc<P>,
+	local_key: SharedKey<P>,
 	client: Arc<C>,
 	select_chain: SC,
 	block_import: Arc<I>,
@@ -285,7 +285,7 @@ struct AuraWorker<C, E, I, P, SO> {
 	client: Arc<C>,
 	block_import: Arc<I>,
 	env: Arc<E>,
-	local_key: Arc<P>,
+	local_key: SharedKey<P>,
 	sync_oracle: SO,
 	inherent_data_providers: InherentDataProviders,
 	force_authoring: bool,
@@ -319,8 +319,11 @@ impl<B: Block, C, E, I, P, Error, SO> SlotWorker<B> for AuraWorker<C, E, I, P, S
 		chain_head: B::Header,
 		slot_info: SlotInfo,
 	) -> Self::OnSlot {
-		let pair = self.local_key.clone();
-		let public_key = self.local_key.public();
+		let pair = match self.local_key.get() {
+			Some(pair) => pair,
+			None => return Box::new(future::ok(())),
+		};
+		let public_key = pair.public();
 		let client = self.client.clone();
 		let block_import = self.block_import.clone();
 		let env = self.env.clone();
@@ -457,8 +460,6 @@ impl<B: Block, C, E, I, P, Error, SO> SlotWorker<B> for AuraWorker<C, E, I, P, S
 /// if it's successful, returns the pre-header and the digest item containing the seal.
 ///
 /// This digest item will always return `Some` when used with `as_aura_seal`.
-//
-// FIXME #1018 needs misbehavior types
 fn check_header<B: Block, P: Pair>(
 	slot_now: u64,
 	mut header: B::Header,
@@ -509,18 +510,22 @@ fn check_header<B: Block, P: Pair>(
 }
 
 /// A verifier for Aura blocks.
-pub struct AuraVerifier<C, E, P> {
+///
+/// Authors sealing two different headers for the same slot are reported to `R`.
+pub struct AuraVerifier<B: Block, C, E, P: Pair, R> {
 	client: Arc<C>,
 	extra: E,
 	phantom: PhantomData<P>,
 	inherent_data_providers: inherents::InherentDataProviders,
 	allow_old_seals: bool,
+	slot_headers: SlotHeaders<AuthorityId<P>, B::Hash, Signature<P>>,
+	report: R,
 }
 
-impl<C, E, P> AuraVerifier<C, E, P>
+impl<B: Block, C, E, P: Pair, R> AuraVerifier<B, C, E, P, R>
 	where P: Send + Sync + 'static
 {
-	fn check_inherents<B: Block>(
+	fn check_inherents(
 		&self,
 		block: B,
 		block_id: BlockId<B>,
@@ -582,14 +587,15 @@ impl<B: Block> ExtraVerification<B> for NothingExtra {
 }
 
 #[forbid(deprecated)]
-impl<B: Block, C, E, P> Verifier<B> for AuraVerifier<C, E, P> where
+impl<B: Block, C, E, P, R> Verifier<B> for AuraVerifier<B, C, E, P, R> where
 	C: ProvideRuntimeApi + Send + Sync,
 	C::Api: BlockBuilderApi<B>,
 	DigestItemFor<B>: CompatibleDigestItem<P> + DigestItem<AuthorityId=AuthorityId<P>>,
 	E: ExtraVerification<B>,
 	P: Pair + Send + Sync + 'static,
 	P::Public: Send + Sync + Hash + Eq + Clone + Decode + Encode + Debug + AsRef<P::Public> + 'static,
-	P::Signature: Encode + Decode,
+	P::Signature: Clone + Send + Sync + Encode + Decode,
+	R: ReportMisbehavior<EquivocationProof<B::Hash, AuthorityId<P>, P::Signature>>,
 	Self: Authorities<B>,
 {
 	fn verify(
@@ -623,9 +629,31 @@ impl<B: Block, C, E, P> Verifier<B> for AuraVerifier<C, E, P> where
 		)?;
 		match checked_header {
 			CheckedHeader::Checked(pre_header, seal) => {
-				let (slot_num, _) = seal.as_aura_seal()
+				let (slot_num, signature) = seal.as_aura_seal()
 					.expect("check_header always returns a seal digest item; qed");
 
+				let author = slot_author::<P>(slot_num, &authorities)
+					.expect("check_header only checks headers sealed by a slot author; qed")
+					.clone();
+				let pre_hash = pre_header.hash();
+				if let Some(first_header) = self.slot_headers.note(
+					slot_num,
+					author.clone(),
+					pre_hash,
+					signature.clone(),
+				) {
+					warn!(target: "aura", "Slot author {:?} is equivocating at slot {}", author, slot_num);
+					telemetry!(CONSENSUS_WARN; "aura.equivocation";
+						"author" => ?author, "slot" => slot_num, "hash" => ?hash
+					);
+					self.report.report_misbehavior(EquivocationProof {
+						slot: slot_num,
+						offender: author,
+						first_header,
+						second_header: (pre_hash, signature),
+					});
+				}
+
 				// if the body is passed through, we need to use the runtime
 				// to check that the internally-set timestamp in the inherents
 				// actually matches the slot set in the seal.
@@ -684,8 +712,9 @@ impl<B: Block, C, E, P> Verifier<B> for AuraVerifier<C, E, P> where
 	}
 }
 
-impl<B, C, E, P> Authorities<B> for AuraVerifier<C, E, P> where
+impl<B, C, E, P, R> Authorities<B> for AuraVerifier<B, C, E, P, R> where
 	B: Block,
+	P: Pair,
 	C: ProvideRuntimeApi + ProvideCache<B>,
 	C::Api: AuthoritiesApi<B>,
 {
@@ -765,7 +794,7 @@ fn register_aura_inherent_data_provider(
 }
 
 /// Start an import queue for the Aura consensus algorithm.
-pub fn import_queue<B, C, E, P>(
+pub fn import_queue<B, C, E, P, R>(
 	slot_duration: SlotDuration,
 	block_import: SharedBlockImport<B>,
 	justification_import: Option<SharedJustificationImport<B>>,
@@ -774,6 +803,7 @@ pub fn import_queue<B, C, E, P>(
 	client: Arc<C>,
 	extra: E,
 	inherent_data_providers: InherentDataProviders,
+	report: R,
 ) -> Result<AuraImportQueue<B>, consensus_common::Error> where
 	B: Block,
 	C: 'static + ProvideRuntimeApi + ProvideCache<B> + Send + Sync,
@@ -782,7 +812,8 @@ pub fn import_queue<B, C, E, P>(
 	E: 'static + ExtraVerification<B>,
 	P: Pair + Send + Sync + 'static,
 	P::Public: Clone + Eq + Send + Sync + Hash + Debug + Encode + Decode + AsRef<P::Public>,
-	P::Signature: Encode + Decode,
+	P::Signature: Clone + Send + Sync + Encode + Decode,
+	R: 'static + ReportMisbehavior<EquivocationProof<B::Hash, AuthorityId<P>, P::Signature>>,
 {
 	register_aura_inherent_data_provider(&inherent_data_providers, slot_duration.get())?;
 	initialize_authorities_cache(&*client)?;
@@ -794,6 +825,8 @@ pub fn import_queue<B, C, E, P>(
 			inherent_data_providers,
 			phantom: PhantomData,
 			allow_old_seals: false,
+			slot_headers: SlotHeaders::default(),
+			report,
 		}
 	);
 	Ok(BasicQueue::new(
@@ -810,7 +843,7 @@ pub fn import_queue<B, C, E, P>(
 	since = "1.0.1",
 	note = "should not be used unless backwards compatibility with an older chain is needed.",
 )]
-pub fn import_queue_accept_old_seals<B, C, E, P>(
+pub fn import_queue_accept_old_seals<B, C, E, P, R>(
 	slot_duration: SlotDuration,
 	block_import: SharedBlockImport<B>,
 	justification_import: Option<SharedJustificationImport<B>>,
@@ -819,6 +852,7 @@ pub fn import_queue_accept_old_seals<B, C, E, P>(
 	client: Arc<C>,
 	extra: E,
 	inherent_data_providers: InherentDataProviders,
+	report: R,
 ) -> Result<AuraImportQueue<B>, consensus_common::Error> where
 	B: Block,
 	C: 'static + ProvideRuntimeApi + ProvideCache<B> + Send + Sync,
@@ -827,7 +861,8 @@ pub fn import_queue_accept_old_seals<B, C, E, P>(
 	E: 'static + ExtraVerification<B>,
 	P: Pair + Send + Sync + 'static,
 	P::Public: Clone + Eq + Send + Sync + Hash + Debug + Encode + Decode + AsRef<P::Public>,
-	P::Signature: Encode + Decode,
+	P::Signature: Clone + Send + Sync + Encode + Decode,
+	R: 'static + ReportMisbehavior<EquivocationProof<B::Hash, AuthorityId<P>, P::Signature>>,
 {
 	register_aura_inherent_data_provider(&inherent_data_providers, slot_duration.get())?;
 	initialize_authorities_cache(&*client)?;
@@ -839,6 +874,8 @@ pub fn import_queue_accept_old_seals<B, C, E, P>(
 			inherent_data_providers,
 			phantom: PhantomData,
 			allow_old_seals: true,
+			slot_headers: SlotHeaders::default(),
+			report,
 		}
 	);
 	Ok(BasicQueue::new(
@@ -902,7 +939,7 @@ mod tests {
 
 	impl TestNetFactory for AuraTestNet {
 		type Specialization = DummySpecialization;
-		type Verifier = AuraVerifier<PeersFullClient, NothingExtra, sr25519::Pair>;
+		type Verifier = AuraVerifier<TestBlock, PeersFullClient, NothingExtra, sr25519::Pair, ()>;
 		type PeerData = ();
 
 		/// Create new test network with peers and given config.
@@ -933,6 +970,8 @@ mod tests {
 						inherent_data_providers,
 						phantom: Default::default(),
 						allow_old_seals: false,
+						slot_headers: Default::default(),
+						report: (),
 					})
 				},
 				PeersClient::Light(_) => unreachable!("No (yet) tests for light client + Aura"),
@@ -1000,7 +1039,7 @@ mod tests {
 
 			let aura = start_aura::<_, _, _, _, _, sr25519::Pair, _, _, _>(
 				slot_duration,
-				Arc::new(key.clone().into()),
+				SharedKey::new(Some(Arc::new(key.clone().into()))),
 				client.clone(),
 				select_chain,
 				client,
@@ -19,13 +19,63 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use runtime_primitives::ConsensusEngineId;
+use runtime_primitives::traits::Verify;
 use substrate_client::decl_runtime_apis;
 
 use parity_codec::{Encode, Decode};
+use rstd::vec::Vec;
 
 /// The `ConsensusEngineId` of BABE.
 pub const BABE_ENGINE_ID: ConsensusEngineId = [b'b', b'a', b'b', b'e'];
 
+/// Seal of a header: its pre-seal hash, the VRF proof of the slot claim and the
+/// signature of both.
+pub type SealedHeader<Hash, Signature> = (Hash, Vec<u8>, Signature);
+
+/// Proof that an authority sealed two different headers for the same slot.
+///
+/// A BABE seal signs the slot, the hash of the header without the seal and the
+/// VRF proof, so the proof carries these for both headers.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EquivocationProof<Hash, AuthorityId, Signature> {
+	/// The slot both headers were sealed for.
+	pub slot: u64,
+	/// The authority which sealed both headers.
+	pub offender: AuthorityId,
+	/// The seal of the first header.
+	pub first_header: SealedHeader<Hash, Signature>,
+	/// The seal of the second header.
+	pub second_header: SealedHeader<Hash, Signature>,
+}
+
+impl<Hash, AuthorityId, Signature> EquivocationProof<Hash, AuthorityId, Signature> where
+	Hash: Encode + PartialEq,
+	Signature: Verify<Signer=AuthorityId>,
+{
+	/// Returns true if the headers are different and both are sealed by the offender
+	/// for the slot.
+	pub fn is_valid(&self) -> bool {
+		let sealed = |(hash, vrf_proof, signature): &SealedHeader<Hash, Signature>| {
+			signature.verify(&seal_payload(self.slot, hash, vrf_proof)[..], &self.offender)
+		};
+
+		self.first_header.0 != self.second_header.0
+			&& sealed(&self.first_header)
+			&& sealed(&self.second_header)
+	}
+}
+
+/// The payload signed by a BABE seal.
+///
+/// The VRF proof is a fixed-size array when sealing, so it is appended without
+/// any length prefix.
+pub fn seal_payload<Hash: Encode>(slot: u64, pre_hash: &Hash, vrf_proof: &[u8]) -> Vec<u8> {
+	let mut payload = (slot, pre_hash).encode();
+	payload.extend_from_slice(vrf_proof);
+	payload
+}
+
 /// Configuration data used by the BABE consensus engine.
 #[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Encode, Decode)]
 pub struct BabeConfiguration {
@@ -28,7 +28,7 @@
 extern crate core;
 pub use babe_primitives::*;
 pub use consensus_common::SyncOracle;
-use consensus_common::ExtraVerification;
+use consensus_common::{ExtraVerification, ReportMisbehavior};
 use runtime_primitives::{generic, generic::BlockId, Justification};
 use runtime_primitives::traits::{
 	Block, Header, Digest, DigestItemFor, DigestItem, ProvideRuntimeApi, AuthorityIdFor,
@@ -52,7 +52,7 @@ use schnorrkel::{
 };
 use authorities::AuthoritiesApi;
 use consensus_common::{self, Authorities, BlockImport, Environment, Proposer,
-	ForkChoiceStrategy, ImportBlock, BlockOrigin, Error as ConsensusError,
+	ForkChoiceStrategy, ImportBlock, BlockOrigin, Error as ConsensusError, SharedKey,
 };
 use srml_babe::{
 	BabeInherentData,
@@ -72,7 +72,7 @@ use futures::{Future, IntoFuture, future};
 use tokio::timer::Timeout;
 use log::{error, warn, debug, info, trace};
 
-use slots::{SlotWorker, SlotInfo, SlotCompatible, slot_now};
+use slots::{SlotWorker, SlotInfo, SlotCompatible, SlotHeaders, slot_now};
 
 /// A BABE seal.  It includes:
 ///
@@ -255,7 +255,7 @@ pub struct BabeParams<C, E, I, SO, SC, OnExit> {
 	pub config: Config,
 
 	/// The key of the node we are running on.
-	pub local_key: Arc<sr25519::Pair>,
+	pub local_key: SharedKey<sr25519::Pair>,
 
 	/// The client to use
 	pub client: Arc<C>,
@@ -335,7 +335,7 @@ struct BabeWorker<C, E, I, SO> {
 	client: Arc<C>,
 	block_import: Arc<I>,
 	env: Arc<E>,
-	local_key: Arc<sr25519::Pair>,
+	local_key: SharedKey<sr25519::Pair>,
 	sync_oracle: SO,
 	inherent_data_providers: InherentDataProviders,
 	force_authoring: bool,
@@ -367,7 +367,10 @@ impl<B: Block, C, E, I, Error, SO> SlotWorker<B> for BabeWorker<C, E, I, SO> whe
 		chain_head: B::Header,
 		slot_info: SlotInfo,
 	) -> Self::OnSlot {
-		let pair = self.local_key.clone();
+		let pair = match self.local_key.get() {
+			Some(pair) => pair,
+			None => return Box::new(future::ok(())),
+		};
 		let ref client = self.client;
 		let block_import = self.block_import.clone();
 		let ref env = self.env;
@@ -531,8 +534,6 @@ impl<B: Block, C, E, I, Error, SO> SlotWorker<B> for BabeWorker<C, E, I, SO> whe
 /// and the digest item containing the seal.
 ///
 /// This digest item will always return `Some` when used with `as_babe_seal`.
-//
-// FIXME #1018 needs misbehavior types
 #[forbid(warnings)]
 fn check_header<B: Block + Sized>(
 	slot_now: u64,
@@ -598,15 +599,19 @@ fn check_header<B: Block + Sized>(
 }
 
 /// A verifier for Babe blocks.
-pub struct BabeVerifier<C, E> {
+///
+/// Authors sealing two different headers for the same slot are reported to `R`.
+pub struct BabeVerifier<B: Block, C, E, R> {
 	client: Arc<C>,
 	extra: E,
 	inherent_data_providers: inherents::InherentDataProviders,
 	threshold: u64,
+	slot_headers: SlotHeaders<Public, B::Hash, (Vec<u8>, Signature)>,
+	report: R,
 }
 
-impl<C, E> BabeVerifier<C, E> {
-	fn check_inherents<B: Block>(
+impl<B: Block, C, E, R> BabeVerifier<B, C, E, R> {
+	fn check_inherents(
 		&self,
 		block: B,
 		block_id: BlockId<B>,
@@ -642,11 +647,12 @@ impl<B: Block> ExtraVerification<B> for NothingExtra {
 	}
 }
 
-impl<B: Block, C, E> Verifier<B> for BabeVerifier<C, E> where
+impl<B: Block, C, E, R> Verifier<B> for BabeVerifier<B, C, E, R> where
 	C: ProvideRuntimeApi + Send + Sync,
 	C::Api: BlockBuilderApi<B>,
 	DigestItemFor<B>: CompatibleDigestItem + DigestItem<AuthorityId=Public>,
 	E: ExtraVerification<B>,
+	R: ReportMisbehavior<EquivocationProof<B::Hash, Public, Signature>>,
 	Self: Authorities<B>,
 {
 	fn verify(
@@ -692,9 +698,35 @@ impl<B: Block, C, E> Verifier<B> for BabeVerifier<C, E> where
 		)?;
 		match checked_header {
 			CheckedHeader::Checked(pre_header, seal) => {
-				let BabeSeal { slot_num, .. } = seal.as_babe_seal()
+				let BabeSeal {
+					slot_num,
+					signature: LocalizedSignature { signer, signature },
+					proof,
+					..
+				} = seal.as_babe_seal()
 					.expect("check_header always returns a seal digest item; qed");
 
+				let pre_hash = pre_header.hash();
+				let sealed = (proof.to_bytes().to_vec(), signature);
+				if let Some((first_hash, (first_proof, first_signature))) = self.slot_headers.note(
+					slot_num,
+					signer.clone(),
+					pre_hash,
+					sealed.clone(),
+				) {
+					warn!(target: "babe", "Slot author {:?} is equivocating at slot {}", signer, slot_num);
+					telemetry!(CONSENSUS_WARN; "babe.equivocation";
+						"author" => ?signer, "slot" => slot_num, "hash" => ?hash
+					);
+					let (proof, signature) = sealed;
+					self.report.report_misbehavior(EquivocationProof {
+						slot: slot_num,
+						offender: signer,
+						first_header: (first_hash, first_proof, first_signature),
+						second_header: (pre_hash, proof, signature),
+					});
+				}
+
 				// if the body is passed through, we need to use the runtime
 				// to check that the internally-set timestamp in the inherents
 				// actually matches the slot set in the seal.
@@ -745,7 +777,7 @@ impl<B: Block, C, E> Verifier<B> for BabeVerifier<C, E> where
 	}
 }
 
-impl<B, C, E> Authorities<B> for BabeVerifier<C, E> where
+impl<B, C, E, R> Authorities<B> for BabeVerifier<B, C, E, R> where
 	B: Block,
 	C: ProvideRuntimeApi + ProvideCache<B>,
 	C::Api: AuthoritiesApi<B>,
@@ -914,7 +946,7 @@ mod tests {
 
 	impl TestNetFactory for BabeTestNet {
 		type Specialization = DummySpecialization;
-		type Verifier = BabeVerifier<PeersFullClient, NothingExtra>;
+		type Verifier = BabeVerifier<TestBlock, PeersFullClient, NothingExtra, ()>;
 		type PeerData = ();
 
 		/// Create new test network with peers and given config.
@@ -946,6 +978,8 @@ mod tests {
 				extra: NothingExtra,
 				inherent_data_providers,
 				threshold: config.threshold(),
+				slot_headers: Default::default(),
+				report: (),
 			})
 		}
 
@@ -1020,7 +1054,7 @@ mod tests {
 
 			let babe = start_babe(BabeParams {
 				config,
-				local_key: Arc::new(key.clone().into()),
+				local_key: SharedKey::new(Some(Arc::new(key.clone().into()))),
 				block_import: client.clone(),
 				select_chain: LongestChain::new(client.backend().clone(), client.import_lock().clone()),
 				client,
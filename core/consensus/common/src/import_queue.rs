@@ -30,10 +30,12 @@ use crate::block_import::{
 	FinalityProofImport, FinalityProofRequestBuilder,
 };
 use crossbeam_channel::{self as channel, Receiver, Sender};
+use metrics::{Gauge, Histogram};
 use parity_codec::Encode;
 
 use std::sync::Arc;
 use std::thread;
+use std::time::Instant;
 
 use runtime_primitives::traits::{
 	AuthorityIdFor, Block as BlockT, Header as HeaderT, NumberFor
@@ -167,12 +169,17 @@ impl<B: BlockT> BasicQueue<B> {
 		finality_proof_import: Option<SharedFinalityProofImport<B>>,
 		finality_proof_request_builder: Option<SharedFinalityProofRequestBuilder<B>>,
 	) -> Self {
+		let queued_blocks = metrics::gauge(
+			"substrate_import_queue_blocks",
+			"Number of blocks waiting in the import queue",
+		);
 		let (result_sender, result_port) = channel::unbounded();
 		let worker_sender = BlockImportWorker::new(
 			result_sender,
 			verifier.clone(),
 			block_import,
 			finality_proof_import.clone(),
+			queued_blocks.clone(),
 		);
 		let importer_sender = BlockImporter::new(
 			result_port,
@@ -181,6 +188,7 @@ impl<B: BlockT> BasicQueue<B> {
 			justification_import,
 			finality_proof_import,
 			finality_proof_request_builder,
+			queued_blocks,
 		);
 
 		Self {
@@ -282,6 +290,7 @@ struct BlockImporter<B: BlockT> {
 	justification_import: Option<SharedJustificationImport<B>>,
 	finality_proof_import: Option<SharedFinalityProofImport<B>>,
 	finality_proof_request_builder: Option<SharedFinalityProofRequestBuilder<B>>,
+	queued_blocks: Arc<Gauge>,
 }
 
 impl<B: BlockT> BlockImporter<B> {
@@ -292,6 +301,7 @@ impl<B: BlockT> BlockImporter<B> {
 		justification_import: Option<SharedJustificationImport<B>>,
 		finality_proof_import: Option<SharedFinalityProofImport<B>>,
 		finality_proof_request_builder: Option<SharedFinalityProofRequestBuilder<B>>,
+		queued_blocks: Arc<Gauge>,
 	) -> Sender<BlockImportMsg<B>> {
 		trace!(target: "block_import", "Creating new Block Importer!");
 		let (sender, port) = channel::bounded(4);
@@ -307,6 +317,7 @@ impl<B: BlockT> BlockImporter<B> {
 					justification_import,
 					finality_proof_import,
 					finality_proof_request_builder,
+					queued_blocks,
 				};
 				while importer.run() {
 					// Importing until all senders have been dropped...
@@ -495,6 +506,7 @@ impl<B: BlockT> BlockImporter<B> {
 
 	fn handle_import_blocks(&mut self, origin: BlockOrigin, blocks: Vec<IncomingBlock<B>>) {
 		trace!(target: "sync", "Scheduling {} blocks for import", blocks.len());
+		self.queued_blocks.add(blocks.len() as i64);
 		self.worker_sender
 			.send(BlockImportWorkerMsg::ImportBlocks(origin, blocks))
 			.expect("1. This is holding a sender to the worker, 2. the worker should not quit while a sender is still held; qed");
@@ -506,6 +518,8 @@ struct BlockImportWorker<B: BlockT, V: Verifier<B>> {
 	block_import: SharedBlockImport<B>,
 	finality_proof_import: Option<SharedFinalityProofImport<B>>,
 	verifier: Arc<V>,
+	queued_blocks: Arc<Gauge>,
+	import_time: Arc<Histogram>,
 }
 
 impl<B: BlockT, V: 'static + Verifier<B>> BlockImportWorker<B, V> {
@@ -514,7 +528,13 @@ impl<B: BlockT, V: 'static + Verifier<B>> BlockImportWorker<B, V> {
 		verifier: Arc<V>,
 		block_import: SharedBlockImport<B>,
 		finality_proof_import: Option<SharedFinalityProofImport<B>>,
+		queued_blocks: Arc<Gauge>,
 	) -> Sender<BlockImportWorkerMsg<B>> {
+		let import_time = metrics::histogram(
+			"substrate_block_import_time_seconds",
+			"Time spent verifying and importing a single block",
+			metrics::DEFAULT_BUCKETS,
+		);
 		let (sender, port) = channel::bounded(4);
 		let _ = thread::Builder::new()
 			.name("ImportQueueWorker".into())
@@ -524,6 +544,8 @@ impl<B: BlockT, V: 'static + Verifier<B>> BlockImportWorker<B, V> {
 					verifier,
 					block_import,
 					finality_proof_import,
+					queued_blocks,
+					import_time,
 				};
 				for msg in port.iter() {
 					// Working until all senders have been dropped...
@@ -573,13 +595,17 @@ impl<B: BlockT, V: 'static + Verifier<B>> BlockImportWorker<B, V> {
 			let import_result = if has_error {
 				Err(BlockImportError::Error)
 			} else {
-				import_single_block(
+				let started = Instant::now();
+				let import_result = import_single_block(
 					&*self.block_import,
 					origin.clone(),
 					block.clone(),
 					self.verifier.clone(),
-				)
+				);
+				self.import_time.observe_duration(started.elapsed());
+				import_result
 			};
+			self.queued_blocks.sub(1);
 			let was_ok = import_result.is_ok();
 			results.push((import_result, block.hash));
 			if was_ok {
@@ -41,6 +41,7 @@ pub mod offline_tracker;
 pub mod error;
 mod block_import;
 mod select_chain;
+mod shared_key;
 pub mod import_queue;
 pub mod evaluation;
 
@@ -53,6 +54,7 @@ pub use block_import::{
 	JustificationImport, FinalityProofImport, FinalityProofRequestBuilder,
 };
 pub use select_chain::SelectChain;
+pub use shared_key::SharedKey;
 
 /// Trait for getting the authorities at a given block.
 pub trait Authorities<B: Block> {
@@ -135,6 +137,25 @@ pub trait ExtraVerification<B: Block>: Send + Sync {
 	) -> Self::Verified;
 }
 
+/// Something that reports misbehavior of authorities, proven by `P`, to the chain.
+///
+/// Consensus engines call this with the proofs they find while importing or
+/// finalizing blocks. Reporting is best-effort: implementations should not block.
+pub trait ReportMisbehavior<P>: Send + Sync {
+	/// Report the misbehavior proven by `proof`.
+	fn report_misbehavior(&self, proof: P);
+}
+
+impl<P> ReportMisbehavior<P> for () {
+	fn report_misbehavior(&self, _proof: P) { }
+}
+
+impl<P, T: ReportMisbehavior<P> + ?Sized> ReportMisbehavior<P> for Arc<T> {
+	fn report_misbehavior(&self, proof: P) {
+		(**self).report_misbehavior(proof)
+	}
+}
+
 /// A list of all well known keys in the cache.
 pub mod well_known_cache_keys {
 	/// The type representing cache keys.
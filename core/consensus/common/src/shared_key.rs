@@ -0,0 +1,69 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate Consensus Common.
+
+// Substrate Demo is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate Consensus Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate Consensus Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, RwLock};
+
+/// A handle to the local authority key that can be replaced while the node is running.
+///
+/// Every clone refers to the same key. Consensus engines read it each time they need
+/// to sign, so a key rotated through the RPC is picked up without a restart.
+pub struct SharedKey<P>(Arc<RwLock<Option<Arc<P>>>>);
+
+impl<P> SharedKey<P> {
+	/// Create a new handle holding the given key.
+	pub fn new(key: Option<Arc<P>>) -> Self {
+		SharedKey(Arc::new(RwLock::new(key)))
+	}
+
+	/// The key currently in use, if any.
+	pub fn get(&self) -> Option<Arc<P>> {
+		self.0.read().expect("writers never panic while holding the lock; qed").clone()
+	}
+
+	/// Replace the key used by every holder of this handle.
+	pub fn set(&self, key: Option<Arc<P>>) {
+		*self.0.write().expect("writers never panic while holding the lock; qed") = key;
+	}
+}
+
+impl<P> Clone for SharedKey<P> {
+	fn clone(&self) -> Self {
+		SharedKey(self.0.clone())
+	}
+}
+
+impl<P> Default for SharedKey<P> {
+	fn default() -> Self {
+		SharedKey::new(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clones_observe_updates() {
+		let key = SharedKey::new(Some(Arc::new(1u32)));
+		let other = key.clone();
+
+		key.set(Some(Arc::new(2)));
+		assert_eq!(other.get().map(|k| *k), Some(2));
+
+		other.set(None);
+		assert!(key.get().is_none());
+	}
+}
@@ -0,0 +1,97 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of authors sealing more than one header in the same slot.
+
+use std::collections::BTreeMap;
+use parking_lot::Mutex;
+
+/// Number of slots before the latest noted one for which sealed headers are kept.
+pub const MAX_SLOT_CAPACITY: u64 = 1000;
+
+/// Headers recently sealed by every author, by slot.
+///
+/// Sealing two different headers in the same slot is an equivocation, this keeps
+/// enough of the first header (its hash and seal) to prove it once a second one
+/// is imported.
+pub struct SlotHeaders<A, H, S> {
+	slots: Mutex<BTreeMap<u64, Vec<(A, H, S)>>>,
+}
+
+impl<A, H, S> Default for SlotHeaders<A, H, S> {
+	fn default() -> Self {
+		SlotHeaders {
+			slots: Mutex::new(BTreeMap::new()),
+		}
+	}
+}
+
+impl<A: PartialEq, H: PartialEq + Clone, S: Clone> SlotHeaders<A, H, S> {
+	/// Note that `author` sealed the header with pre-seal hash `hash` in `slot`.
+	///
+	/// Returns the hash and seal of a different header sealed by the same author
+	/// in the same slot, if one has been noted before.
+	pub fn note(&self, slot: u64, author: A, hash: H, seal: S) -> Option<(H, S)> {
+		let mut slots = self.slots.lock();
+		let latest = slots.keys().next_back().cloned().unwrap_or(0);
+		if slot.saturating_add(MAX_SLOT_CAPACITY) < latest {
+			return None;
+		}
+
+		let headers = slots.entry(slot).or_insert_with(Vec::new);
+		if let Some((_, first_hash, first_seal)) = headers.iter().find(|(a, _, _)| *a == author) {
+			return if *first_hash == hash {
+				None
+			} else {
+				Some((first_hash.clone(), first_seal.clone()))
+			};
+		}
+		headers.push((author, hash, seal));
+
+		if slot > latest {
+			let oldest = slot.saturating_sub(MAX_SLOT_CAPACITY);
+			*slots = slots.split_off(&oldest);
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn second_header_in_slot_is_equivocation() {
+		let headers = SlotHeaders::default();
+
+		assert_eq!(headers.note(1, 'a', 10, "first"), None);
+		assert_eq!(headers.note(1, 'b', 11, "other author"), None);
+		assert_eq!(headers.note(2, 'a', 12, "other slot"), None);
+		// importing the same header again is fine.
+		assert_eq!(headers.note(1, 'a', 10, "first"), None);
+		assert_eq!(headers.note(1, 'a', 13, "second"), Some((10, "first")));
+	}
+
+	#[test]
+	fn old_slots_are_pruned() {
+		let headers = SlotHeaders::default();
+
+		assert_eq!(headers.note(1, 'a', 10, ()), None);
+		assert_eq!(headers.note(MAX_SLOT_CAPACITY + 2, 'a', 11, ()), None);
+		assert_eq!(headers.note(1, 'a', 12, ()), None);
+		assert!(headers.slots.lock().get(&1).is_none());
+	}
+}
@@ -23,8 +23,10 @@
 #![forbid(warnings, unsafe_code, missing_docs)]
 
 mod slots;
+mod equivocation;
 
 pub use slots::{slot_now, SlotInfo, Slots};
+pub use equivocation::{SlotHeaders, MAX_SLOT_CAPACITY};
 
 use codec::{Decode, Encode};
 use consensus_common::{SyncOracle, SelectChain};
@@ -24,14 +24,14 @@ use wasmi::{
 	Module, ModuleInstance, MemoryInstance, MemoryRef, TableRef, ImportsBuilder, ModuleRef,
 };
 use wasmi::RuntimeValue::{I32, I64, self};
-use wasmi::memory_units::{Pages};
+use wasmi::memory_units::{Bytes, Pages};
 use state_machine::{Externalities, ChildStorageKey};
 use crate::error::{Error, ErrorKind, Result};
 use crate::wasm_utils::UserError;
 use primitives::{blake2_128, blake2_256, twox_64, twox_128, twox_256, ed25519, sr25519, Pair};
 use primitives::hexdisplay::HexDisplay;
 use primitives::sandbox as sandbox_primitives;
-use primitives::{H256, Blake2Hasher};
+use primitives::{H256, Blake2Hasher, offchain};
 use trie::ordered_trie_root;
 use crate::sandbox;
 use crate::allocator;
@@ -113,6 +113,23 @@ impl ReadPrimitive<u32> for MemoryInstance {
 	}
 }
 
+fn deadline_to_timestamp(has_deadline: u32, deadline: u64) -> Option<offchain::Timestamp> {
+	if has_deadline == 0 {
+		None
+	} else {
+		Some(offchain::Timestamp::from_unix_millis(deadline))
+	}
+}
+
+/// Request ids are `u16`, larger ids passed by the runtime are rejected instead of truncated.
+fn http_request_id(request_id: u32) -> Option<offchain::HttpRequestId> {
+	if request_id > u32::from(u16::max_value()) {
+		None
+	} else {
+		Some(offchain::HttpRequestId(request_id as u16))
+	}
+}
+
 impl_function_executor!(this: FunctionExecutor<'e, E>,
 	ext_print_utf8(utf8_data: *const u8, utf8_len: u32) => {
 		if let Ok(utf8) = this.memory.get(utf8_data, utf8_len as usize) {
@@ -598,6 +615,336 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 
 		Ok(())
 	},
+	ext_local_storage_set(key: *const u8, key_len: u32, value: *const u8, value_len: u32) => {
+		let key = this.memory.get(key, key_len as usize)
+			.map_err(|_| UserError("OOB while ext_local_storage_set: wasm"))?;
+		let value = this.memory.get(value, value_len as usize)
+			.map_err(|_| UserError("OOB while ext_local_storage_set: wasm"))?;
+
+		this.ext.offchain()
+			.map(|api| api.local_storage_set(&key, &value))
+			.ok_or_else(|| UserError("Calling unavailable API ext_local_storage_set: wasm"))?;
+
+		Ok(())
+	},
+	ext_local_storage_get(key: *const u8, key_len: u32, value_len: *mut u32) -> *mut u8 => {
+		let key = this.memory.get(key, key_len as usize)
+			.map_err(|_| UserError("OOB while ext_local_storage_get: wasm"))?;
+
+		let maybe_value = this.ext.offchain()
+			.map(|api| api.local_storage_get(&key))
+			.ok_or_else(|| UserError("Calling unavailable API ext_local_storage_get: wasm"))?;
+
+		let (offset, len) = if let Some(value) = maybe_value {
+			let offset = this.heap.allocate(value.len() as u32)? as u32;
+			this.memory.set(offset, &value)
+				.map_err(|_| UserError("Invalid attempt to set memory in ext_local_storage_get"))?;
+			(offset, value.len() as u32)
+		} else {
+			(0, u32::max_value())
+		};
+
+		this.memory.write_primitive(value_len, len)
+			.map_err(|_| UserError("Invalid attempt to write value_len in ext_local_storage_get"))?;
+
+		Ok(offset)
+	},
+	ext_local_storage_compare_and_set(
+		key: *const u8,
+		key_len: u32,
+		old_value: *const u8,
+		old_value_len: u32,
+		new_value: *const u8,
+		new_value_len: u32
+	) -> u32 => {
+		let key = this.memory.get(key, key_len as usize)
+			.map_err(|_| UserError("OOB while ext_local_storage_compare_and_set: wasm"))?;
+		let new_value = this.memory.get(new_value, new_value_len as usize)
+			.map_err(|_| UserError("OOB while ext_local_storage_compare_and_set: wasm"))?;
+
+		let res = {
+			if old_value_len == u32::max_value() {
+				this.ext.offchain()
+					.map(|api| api.local_storage_compare_and_set(&key, None, &new_value))
+					.ok_or_else(|| UserError("Calling unavailable API ext_local_storage_compare_and_set: wasm"))?
+			} else {
+				let v = this.memory.get(old_value, old_value_len as usize)
+					.map_err(|_| UserError("OOB while ext_local_storage_compare_and_set: wasm"))?;
+				this.ext.offchain()
+					.map(|api| api.local_storage_compare_and_set(&key, Some(v.as_slice()), &new_value))
+					.ok_or_else(|| UserError("Calling unavailable API ext_local_storage_compare_and_set: wasm"))?
+			}
+		};
+
+		Ok(if res { 0 } else { 1 })
+	},
+	ext_http_request_start(
+		method: *const u8,
+		method_len: u32,
+		url: *const u8,
+		url_len: u32,
+		meta: *const u8,
+		meta_len: u32
+	) -> u32 => {
+		let method = this.memory.get(method, method_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_start: wasm"))?;
+		let url = this.memory.get(url, url_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_start: wasm"))?;
+		let meta = this.memory.get(meta, meta_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_start: wasm"))?;
+
+		let method_str = ::std::str::from_utf8(&method)
+			.map_err(|_| UserError("Invalid str while ext_http_request_start: wasm"))?;
+		let url_str = ::std::str::from_utf8(&url)
+			.map_err(|_| UserError("Invalid str while ext_http_request_start: wasm"))?;
+
+		let id = this.ext.offchain()
+			.map(|api| api.http_request_start(method_str, url_str, &*meta))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_request_start: wasm"))?;
+
+		if let Ok(id) = id {
+			Ok(id.0 as u32)
+		} else {
+			Ok(u32::max_value())
+		}
+	},
+	ext_http_request_add_header(
+		request_id: u32,
+		name: *const u8,
+		name_len: u32,
+		value: *const u8,
+		value_len: u32
+	) -> u32 => {
+		let name = this.memory.get(name, name_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_add_header: wasm"))?;
+		let value = this.memory.get(value, value_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_add_header: wasm"))?;
+
+		let name_str = ::std::str::from_utf8(&name)
+			.map_err(|_| UserError("Invalid str while ext_http_request_add_header: wasm"))?;
+		let value_str = ::std::str::from_utf8(&value)
+			.map_err(|_| UserError("Invalid str while ext_http_request_add_header: wasm"))?;
+
+		let request_id = http_request_id(request_id)
+			.ok_or_else(|| UserError("Invalid request id in ext_http_request_add_header: wasm"))?;
+		let res = this.ext.offchain()
+			.map(|api| api.http_request_add_header(
+				request_id,
+				name_str,
+				value_str,
+			))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_request_add_header: wasm"))?;
+
+		Ok(if res.is_ok() { 0 } else { 1 })
+	},
+	ext_http_request_write_body(
+		request_id: u32,
+		chunk: *const u8,
+		chunk_len: u32,
+		has_deadline: u32,
+		deadline: u64
+	) -> u32 => {
+		let request_id = http_request_id(request_id)
+			.ok_or_else(|| UserError("Invalid request id in ext_http_request_write_body: wasm"))?;
+		let chunk = this.memory.get(chunk, chunk_len as usize)
+			.map_err(|_| UserError("OOB while ext_http_request_write_body: wasm"))?;
+
+		let res = this.ext.offchain()
+			.map(|api| api.http_request_write_body(
+				request_id,
+				&chunk,
+				deadline_to_timestamp(has_deadline, deadline)
+			))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_request_write_body: wasm"))?;
+
+		Ok(match res {
+			Ok(()) => 0,
+			Err(e) => u32::from(e),
+		})
+	},
+	ext_http_response_wait(
+		ids: *const u32,
+		ids_len: u32,
+		statuses: *mut u32,
+		has_deadline: u32,
+		deadline: u64
+	) => {
+		let offset = |ptr: u32, i: u32| i.checked_mul(4).and_then(|delta| ptr.checked_add(delta))
+			.ok_or_else(|| UserError("Arithmetic overflow in ext_http_response_wait: wasm"));
+		let ids = (0..ids_len)
+			.map(|i| {
+				let id: u32 = this.memory.read_primitive(offset(ids, i)?)
+					.map_err(|_| UserError("OOB while ext_http_response_wait: wasm"))?;
+				http_request_id(id).ok_or_else(|| UserError("Invalid request id in ext_http_response_wait: wasm"))
+			})
+			.collect::<::std::result::Result<Vec<_>, _>>()?;
+
+		let res = this.ext.offchain()
+			.map(|api| api.http_response_wait(&ids, deadline_to_timestamp(has_deadline, deadline)))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_response_wait: wasm"))?;
+		for (i, status) in res.into_iter().enumerate() {
+			this.memory.write_primitive(offset(statuses, i as u32)?, u32::from(status))
+				.map_err(|_| UserError("Invalid attempt to set memory in ext_http_response_wait"))?;
+		}
+
+		Ok(())
+	},
+	ext_http_response_headers(
+		request_id: u32,
+		written_out: *mut u32
+	) -> *mut u8 => {
+		use parity_codec::Encode;
+
+		let request_id = http_request_id(request_id)
+			.ok_or_else(|| UserError("Invalid request id in ext_http_response_headers: wasm"))?;
+		let headers = this.ext.offchain()
+			.map(|api| api.http_response_headers(request_id))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_response_headers: wasm"))?;
+
+		let encoded = headers.encode();
+		let len = encoded.len() as u32;
+		let offset = this.heap.allocate(len)? as u32;
+		this.memory.set(offset, &encoded)
+			.map_err(|_| UserError("Invalid attempt to set memory in ext_http_response_headers"))?;
+		this.memory.write_primitive(written_out, len)
+			.map_err(|_| UserError("Invalid attempt to write written_out in ext_http_response_headers"))?;
+
+		Ok(offset)
+	},
+	ext_http_response_read_body(
+		request_id: u32,
+		buffer: *mut u8,
+		buffer_len: u32,
+		has_deadline: u32,
+		deadline: u64
+	) -> u32 => {
+		let request_id = http_request_id(request_id)
+			.ok_or_else(|| UserError("Invalid request id in ext_http_response_read_body: wasm"))?;
+		// the buffer is only allocated once it is known to fit into the memory of the runtime.
+		let buffer_end = buffer.checked_add(buffer_len).map(|end| end as usize);
+		if buffer_end.map_or(true, |end| end > Bytes::from(this.memory.current_size()).0) {
+			return Err(UserError("OOB while ext_http_response_read_body: wasm").into());
+		}
+		let mut internal_buffer = vec![0; buffer_len as usize];
+
+		let res = this.ext.offchain()
+			.map(|api| api.http_response_read_body(
+				request_id,
+				&mut internal_buffer,
+				deadline_to_timestamp(has_deadline, deadline),
+			))
+			.ok_or_else(|| UserError("Calling unavailable API ext_http_response_read_body: wasm"))?;
+
+		Ok(match res {
+			Ok(read) => {
+				this.memory.set(buffer, &internal_buffer[..read])
+					.map_err(|_| UserError("Invalid attempt to set memory in ext_http_response_read_body"))?;
+
+				read as u32
+			},
+			Err(err) => {
+				u32::max_value() - u32::from(err) + 1
+			}
+		})
+	},
+	ext_authority_pubkeys(kind: u32, written_out: *mut u32) -> *mut u8 => {
+		use parity_codec::Encode;
+
+		let kind = offchain::CryptoKind::from_code(kind)
+			.ok_or_else(|| UserError("Invalid crypto kind in ext_authority_pubkeys"))?;
+		let keys = this.ext.offchain()
+			.map(|api| api.authority_pubkeys(kind))
+			.ok_or_else(|| UserError("Calling unavailable API ext_authority_pubkeys: wasm"))?;
+
+		let encoded = keys.encode();
+		let len = encoded.len() as u32;
+		let offset = this.heap.allocate(len)? as u32;
+		this.memory.set(offset, &encoded)
+			.map_err(|_| UserError("Invalid attempt to set memory in ext_authority_pubkeys"))?;
+		this.memory.write_primitive(written_out, len)
+			.map_err(|_| UserError("Invalid attempt to write written_out in ext_authority_pubkeys"))?;
+
+		Ok(offset)
+	},
+	ext_sign(
+		kind: u32,
+		public: *const u8,
+		public_len: u32,
+		data: *const u8,
+		data_len: u32,
+		signature_len: *mut u32
+	) -> *mut u8 => {
+		let kind = offchain::CryptoKind::from_code(kind)
+			.ok_or_else(|| UserError("Invalid crypto kind in ext_sign"))?;
+		let public = this.memory.get(public, public_len as usize)
+			.map_err(|_| UserError("OOB while ext_sign: wasm"))?;
+		let data = this.memory.get(data, data_len as usize)
+			.map_err(|_| UserError("OOB while ext_sign: wasm"))?;
+
+		let signature = this.ext.offchain()
+			.map(|api| api.sign(kind, &public, &data))
+			.ok_or_else(|| UserError("Calling unavailable API ext_sign: wasm"))?;
+
+		let (offset, len) = if let Some(signature) = signature {
+			let offset = this.heap.allocate(signature.len() as u32)? as u32;
+			this.memory.set(offset, &signature)
+				.map_err(|_| UserError("Invalid attempt to set memory in ext_sign"))?;
+			(offset, signature.len() as u32)
+		} else {
+			(0, u32::max_value())
+		};
+
+		this.memory.write_primitive(signature_len, len)
+			.map_err(|_| UserError("Invalid attempt to write signature_len in ext_sign"))?;
+
+		Ok(offset)
+	},
+	ext_verify(
+		kind: u32,
+		public: *const u8,
+		public_len: u32,
+		msg: *const u8,
+		msg_len: u32,
+		signature: *const u8,
+		signature_len: u32
+	) -> u32 => {
+		let kind = offchain::CryptoKind::from_code(kind)
+			.ok_or_else(|| UserError("Invalid crypto kind in ext_verify"))?;
+		let public = this.memory.get(public, public_len as usize)
+			.map_err(|_| UserError("OOB while ext_verify: wasm"))?;
+		let msg = this.memory.get(msg, msg_len as usize)
+			.map_err(|_| UserError("OOB while ext_verify: wasm"))?;
+		let signature = this.memory.get(signature, signature_len as usize)
+			.map_err(|_| UserError("OOB while ext_verify: wasm"))?;
+
+		let is_valid = this.ext.offchain()
+			.map(|api| api.verify(kind, &public, &msg, &signature))
+			.ok_or_else(|| UserError("Calling unavailable API ext_verify: wasm"))?;
+
+		Ok(if is_valid { 0 } else { 1 })
+	},
+	ext_timestamp() -> u64 => {
+		let timestamp = this.ext.offchain()
+			.map(|api| api.timestamp())
+			.ok_or_else(|| UserError("Calling unavailable API ext_timestamp: wasm"))?;
+		Ok(timestamp.unix_millis())
+	},
+	ext_sleep_until(deadline: u64) => {
+		this.ext.offchain()
+			.map(|api| api.sleep_until(offchain::Timestamp::from_unix_millis(deadline)))
+			.ok_or_else(|| UserError("Calling unavailable API ext_sleep_until: wasm"))?;
+		Ok(())
+	},
+	ext_random_seed(seed_data: *mut u8) => {
+		// NOTE the runtime assumes the seed is 32 bytes long.
+		let seed: [u8; 32] = this.ext.offchain()
+			.map(|api| api.random_seed())
+			.ok_or_else(|| UserError("Calling unavailable API ext_random_seed: wasm"))?;
+
+		this.memory.set(seed_data, &seed)
+			.map_err(|_| UserError("Invalid attempt to set value in ext_random_seed"))?;
+		Ok(())
+	},
 	ext_sandbox_instantiate(
 		dispatch_thunk_idx: usize,
 		wasm_ptr: *const u8,
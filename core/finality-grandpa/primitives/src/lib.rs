@@ -23,11 +23,12 @@ extern crate alloc;
 
 use parity_codec::{Encode, Decode};
 use substrate_primitives::ed25519;
-use sr_primitives::traits::{DigestFor, NumberFor};
+use sr_primitives::traits::{DigestFor, NumberFor, Verify};
 use client::decl_runtime_apis;
 use rstd::vec::Vec;
 
 use ed25519::Public as AuthorityId;
+use ed25519::Signature as AuthoritySignature;
 
 /// A scheduled change of authority set.
 #[cfg_attr(feature = "std", derive(Debug, PartialEq))]
@@ -39,6 +40,57 @@ pub struct ScheduledChange<N> {
 	pub delay: N,
 }
 
+/// The kind of a GRANDPA vote.
+///
+/// Encoded as the index of the matching variant of the voter's message enum,
+/// which is how the kind is committed to by vote signatures.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum VoteKind {
+	/// A prevote.
+	Prevote,
+	/// A precommit.
+	Precommit,
+}
+
+/// The target of a vote, its block hash and number, with the vote's signature.
+pub type SignedVote<H, N> = ((H, N), AuthoritySignature);
+
+/// Proof that an authority cast two votes of the same kind for different blocks
+/// in the same round.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+pub struct EquivocationProof<H, N> {
+	/// The id of the authority set the votes were cast in.
+	pub set_id: u64,
+	/// The round of the votes.
+	pub round: u64,
+	/// The kind of both votes.
+	pub kind: VoteKind,
+	/// The authority which cast both votes.
+	pub offender: AuthorityId,
+	/// The first vote.
+	pub first_vote: SignedVote<H, N>,
+	/// The second vote.
+	pub second_vote: SignedVote<H, N>,
+}
+
+impl<H: Encode + PartialEq, N: Encode + PartialEq> EquivocationProof<H, N> {
+	/// Returns true if the votes are for different blocks and both are signed by
+	/// the offender for the round.
+	pub fn is_valid(&self) -> bool {
+		let signed = |(target, signature): &SignedVote<H, N>| {
+			let message = (self.kind, &target.0, &target.1);
+			let payload = (message, self.round, self.set_id).encode();
+			signature.verify(&payload[..], &self.offender)
+		};
+
+		self.first_vote.0 != self.second_vote.0
+			&& signed(&self.first_vote)
+			&& signed(&self.second_vote)
+	}
+}
+
 /// WASM function call to check for pending changes.
 pub const PENDING_CHANGE_CALL: &str = "grandpa_pending_change";
 /// WASM function call to get current GRANDPA authorities.
@@ -853,7 +853,7 @@ mod tests {
 		crate::Config {
 			gossip_duration: Duration::from_millis(10),
 			justification_period: 256,
-			local_key: None,
+			local_key: Default::default(),
 			name: None,
 		}
 	}
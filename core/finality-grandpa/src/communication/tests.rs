@@ -111,7 +111,7 @@ fn config() -> crate::Config {
 	crate::Config {
 		gossip_duration: std::time::Duration::from_millis(10),
 		justification_period: 256,
-		local_key: None,
+		local_key: Default::default(),
 		name: None,
 	}
 }
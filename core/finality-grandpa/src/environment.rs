@@ -41,8 +41,9 @@ use substrate_telemetry::{telemetry, CONSENSUS_INFO};
 
 use crate::{
 	CommandOrError, Commit, Config, Error, Network, Precommit, Prevote,
-	PrimaryPropose, SignedMessage, NewAuthoritySet, VoterCommand,
+	PrimaryPropose, SignedMessage, NewAuthoritySet, VoterCommand, MisbehaviorReporter,
 };
+use fg_primitives::{EquivocationProof, VoteKind};
 
 use consensus_common::SelectChain;
 
@@ -274,9 +275,32 @@ pub(crate) struct Environment<B, E, Block: BlockT, N: Network<Block>, RA, SC> {
 	pub(crate) network: crate::communication::NetworkBridge<Block, N>,
 	pub(crate) set_id: u64,
 	pub(crate) voter_set_state: SharedVoterSetState<Block>,
+	pub(crate) misbehavior_reporter: Option<MisbehaviorReporter<Block>>,
+	pub(crate) round_gauge: Arc<metrics::Gauge>,
 }
 
 impl<B, E, Block: BlockT, N: Network<Block>, RA, SC> Environment<B, E, Block, N, RA, SC> {
+	/// Report an equivocation of a voter of the current set, if there is a reporter.
+	fn report_equivocation(
+		&self,
+		round: u64,
+		kind: VoteKind,
+		offender: AuthorityId,
+		first_vote: ((Block::Hash, NumberFor<Block>), ed25519::Signature),
+		second_vote: ((Block::Hash, NumberFor<Block>), ed25519::Signature),
+	) {
+		if let Some(ref reporter) = self.misbehavior_reporter {
+			reporter.report_misbehavior(EquivocationProof {
+				set_id: self.set_id,
+				round,
+				kind,
+				offender,
+				first_vote,
+				second_vote,
+			});
+		}
+	}
+
 	/// Updates the voter set state using the given closure. The write lock is
 	/// held during evaluation of the closure and the environment's voter set
 	/// state is set to its result if successful.
@@ -452,18 +476,23 @@ where
 		&self,
 		round: u64
 	) -> voter::RoundData<Self::Id, Self::Timer, Self::In, Self::Out> {
+		self.round_gauge.set(round as i64);
+
 		let now = Instant::now();
 		let prevote_timer = Delay::new(now + self.config.gossip_duration * 2);
 		let precommit_timer = Delay::new(now + self.config.gossip_duration * 4);
 
-		let local_key = self.config.local_key.as_ref()
+		// read the key once so that a rotation can't split a round between two keys.
+		let local_key = self.config.local_key.get();
+		let voter_id = local_key.as_ref().map(|pair| pair.public());
+		let local_key = local_key
 			.filter(|pair| self.voters.contains_key(&pair.public().into()));
 
 		let (incoming, outgoing) = self.network.round_communication(
 			crate::communication::Round(round),
 			crate::communication::SetId(self.set_id),
 			self.voters.clone(),
-			local_key.cloned(),
+			local_key,
 			self.voter_set_state.has_voted(),
 		);
 
@@ -479,7 +508,7 @@ where
 		let outgoing = Box::new(outgoing.sink_map_err(Into::into));
 
 		voter::RoundData {
-			voter_id: self.config.local_key.as_ref().map(|pair| pair.public().clone()),
+			voter_id,
 			prevote_timer: Box::new(prevote_timer.map_err(|e| Error::Timer(e).into())),
 			precommit_timer: Box::new(precommit_timer.map_err(|e| Error::Timer(e).into())),
 			incoming,
@@ -488,7 +517,7 @@ where
 	}
 
 	fn proposed(&self, _round: u64, propose: PrimaryPropose<Block>) -> Result<(), Self::Error> {
-		let local_id = self.config.local_key.as_ref()
+		let local_id = self.config.local_key.get()
 			.map(|pair| pair.public().into())
 			.filter(|id| self.voters.contains_key(&id));
 
@@ -526,7 +555,7 @@ where
 	}
 
 	fn prevoted(&self, _round: u64, prevote: Prevote<Block>) -> Result<(), Self::Error> {
-		let local_id = self.config.local_key.as_ref()
+		let local_id = self.config.local_key.get()
 			.map(|pair| pair.public().into())
 			.filter(|id| self.voters.contains_key(&id));
 
@@ -567,7 +596,7 @@ where
 	}
 
 	fn precommitted(&self, _round: u64, precommit: Precommit<Block>) -> Result<(), Self::Error> {
-		let local_id = self.config.local_key.as_ref()
+		let local_id = self.config.local_key.get()
 			.map(|pair| pair.public().into())
 			.filter(|id| self.voters.contains_key(&id));
 
@@ -688,20 +717,34 @@ where
 
 	fn prevote_equivocation(
 		&self,
-		_round: u64,
+		round: u64,
 		equivocation: ::grandpa::Equivocation<Self::Id, Prevote<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected prevote equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet; this could craft misbehavior reports of some kind.
+		let Equivocation { identity, first, second, .. } = equivocation;
+		self.report_equivocation(
+			round,
+			VoteKind::Prevote,
+			identity,
+			((first.0.target_hash, first.0.target_number), first.1),
+			((second.0.target_hash, second.0.target_number), second.1),
+		);
 	}
 
 	fn precommit_equivocation(
 		&self,
-		_round: u64,
+		round: u64,
 		equivocation: Equivocation<Self::Id, Precommit<Block>, Self::Signature>
 	) {
 		warn!(target: "afg", "Detected precommit equivocation in the finality worker: {:?}", equivocation);
-		// nothing yet
+		let Equivocation { identity, first, second, .. } = equivocation;
+		self.report_equivocation(
+			round,
+			VoteKind::Precommit,
+			identity,
+			((first.0.target_hash, first.0.target_number), first.1),
+			((second.0.target_hash, second.0.target_number), second.1),
+		);
 	}
 }
 
@@ -69,7 +69,7 @@ use runtime_primitives::traits::{
 use fg_primitives::GrandpaApi;
 use inherents::InherentDataProviders;
 use runtime_primitives::generic::BlockId;
-use consensus_common::SelectChain;
+use consensus_common::{SelectChain, ReportMisbehavior, SharedKey};
 use substrate_primitives::{ed25519, H256, Pair, Blake2Hasher};
 use substrate_telemetry::{telemetry, CONSENSUS_INFO, CONSENSUS_DEBUG, CONSENSUS_WARN};
 use serde_json;
@@ -83,7 +83,7 @@ use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
-pub use fg_primitives::ScheduledChange;
+pub use fg_primitives::{ScheduledChange, EquivocationProof};
 
 mod authorities;
 mod aux_schema;
@@ -158,8 +158,9 @@ pub struct Config {
 	/// at least every justification_period blocks. There are some other events which might cause
 	/// justification generation.
 	pub justification_period: u64,
-	/// The local signing key.
-	pub local_key: Option<Arc<ed25519::Pair>>,
+	/// The local signing key. It is read again at the start of every round, so a
+	/// rotated key takes effect without restarting the voter.
+	pub local_key: SharedKey<ed25519::Pair>,
 	/// Some local identifier of the voter.
 	pub name: Option<String>,
 }
@@ -355,7 +356,7 @@ where
 }
 
 fn global_communication<Block: BlockT<Hash=H256>, B, E, N, RA>(
-	local_key: Option<&Arc<ed25519::Pair>>,
+	local_key: Option<Arc<ed25519::Pair>>,
 	set_id: u64,
 	voters: &Arc<VoterSet<AuthorityId>>,
 	client: &Arc<Client<B, E, Block, RA>>,
@@ -447,6 +448,11 @@ fn register_finality_tracker_inherent_data_provider<B, E, Block: BlockT<Hash=H25
 	}
 }
 
+/// Something that reports equivocations of GRANDPA voters to the chain.
+pub type MisbehaviorReporter<Block> = Arc<
+	dyn ReportMisbehavior<EquivocationProof<<Block as BlockT>::Hash, NumberFor<Block>>>
+>;
+
 /// Parameters used to run Grandpa.
 pub struct GrandpaParams<'a, B, E, Block: BlockT<Hash=H256>, N, RA, SC, X> {
 	/// Configuration for the GRANDPA service.
@@ -461,6 +467,8 @@ pub struct GrandpaParams<'a, B, E, Block: BlockT<Hash=H256>, N, RA, SC, X> {
 	pub on_exit: X,
 	/// If supplied, can be used to hook on telemetry connection established events.
 	pub telemetry_on_connect: Option<TelemetryOnConnect<'a>>,
+	/// If supplied, equivocations detected by the voter are reported with it.
+	pub misbehavior_reporter: Option<MisbehaviorReporter<Block>>,
 }
 
 /// Run a GRANDPA voter as a task. Provide configuration and a link to a
@@ -486,6 +494,7 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 		inherent_data_providers,
 		on_exit,
 		telemetry_on_connect,
+		misbehavior_reporter,
 	} = grandpa_params;
 
 	use futures::future::{self, Loop as FutureLoop};
@@ -524,6 +533,9 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 		telemetry_on_connect.executor.spawn(events);
 	}
 
+	// registered once, every environment of this voter updates the same gauge.
+	let round_gauge = metrics::gauge("substrate_finality_grandpa_round", "Number of the current GRANDPA round");
+
 	let voters = authority_set.current_authorities();
 	let initial_environment = Arc::new(Environment {
 		inner: client.clone(),
@@ -535,12 +547,14 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 		authority_set: authority_set.clone(),
 		consensus_changes: consensus_changes.clone(),
 		voter_set_state: set_state.clone(),
+		misbehavior_reporter: misbehavior_reporter.clone(),
+		round_gauge: round_gauge.clone(),
 	});
 
 	initial_environment.update_voter_set_state(|voter_set_state| {
 		match voter_set_state {
 			VoterSetState::Live { current_round: HasVoted::Yes(id, _), completed_rounds } => {
-				let local_id = config.local_key.clone().map(|pair| pair.public());
+				let local_id = config.local_key.get().map(|pair| pair.public());
 				let has_voted = match local_id {
 					Some(local_id) => if *id == local_id {
 						// keep the previous votes
@@ -583,7 +597,7 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 				);
 
 				let global_comms = global_communication(
-					config.local_key.as_ref(),
+					config.local_key.get(),
 					env.set_id,
 					&env.voters,
 					&client,
@@ -618,6 +632,8 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 		let select_chain = select_chain.clone();
 		let authority_set = authority_set.clone();
 		let consensus_changes = consensus_changes.clone();
+		let misbehavior_reporter = misbehavior_reporter.clone();
+		let round_gauge = round_gauge.clone();
 
 		let handle_voter_command = move |command: VoterCommand<_, _>, voter_commands_rx| {
 			match command {
@@ -661,6 +677,8 @@ pub fn run_grandpa_voter<B, E, Block: BlockT<Hash=H256>, N, RA, SC, X>(
 						authority_set,
 						consensus_changes,
 						voter_set_state: set_state,
+						misbehavior_reporter,
+						round_gauge,
 					});
 
 					Ok(FutureLoop::Continue((env, voter_commands_rx)))
@@ -499,7 +499,7 @@ fn run_to_completion_with<F>(
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
-				local_key: Some(Arc::new(key.clone().into())),
+				local_key: SharedKey::new(Some(Arc::new(key.clone().into()))),
 				name: Some(format!("peer#{}", peer_id)),
 			},
 			link: link,
@@ -507,6 +507,7 @@ fn run_to_completion_with<F>(
 			inherent_data_providers: InherentDataProviders::new(),
 			on_exit: Exit,
 			telemetry_on_connect: None,
+			misbehavior_reporter: None,
 		};
 		let voter = run_grandpa_voter(grandpa_params).expect("all in order with client and network");
 
@@ -600,7 +601,7 @@ fn finalize_3_voters_1_full_observer() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
-				local_key,
+				local_key: SharedKey::new(local_key),
 				name: Some(format!("peer#{}", peer_id)),
 			},
 			link: link,
@@ -608,6 +609,7 @@ fn finalize_3_voters_1_full_observer() {
 			inherent_data_providers: InherentDataProviders::new(),
 			on_exit: Exit,
 			telemetry_on_connect: None,
+			misbehavior_reporter: None,
 		};
 		let voter = run_grandpa_voter(grandpa_params).expect("all in order with client and network");
 
@@ -767,7 +769,7 @@ fn transition_3_voters_twice_1_full_observer() {
 			config: Config {
 				gossip_duration: TEST_GOSSIP_DURATION,
 				justification_period: 32,
-				local_key,
+				local_key: SharedKey::new(local_key),
 				name: Some(format!("peer#{}", peer_id)),
 			},
 			link: link,
@@ -775,6 +777,7 @@ fn transition_3_voters_twice_1_full_observer() {
 			inherent_data_providers: InherentDataProviders::new(),
 			on_exit: Exit,
 			telemetry_on_connect: None,
+			misbehavior_reporter: None,
 		};
 		let voter = run_grandpa_voter(grandpa_params).expect("all in order with client and network");
 
@@ -1183,7 +1186,7 @@ fn voter_persists_its_votes() {
 				config: Config {
 					gossip_duration: TEST_GOSSIP_DURATION,
 					justification_period: 32,
-					local_key: Some(Arc::new(peers[0].clone().into())),
+					local_key: SharedKey::new(Some(Arc::new(peers[0].clone().into()))),
 					name: Some(format!("peer#{}", 0)),
 				},
 				link: link,
@@ -1191,6 +1194,7 @@ fn voter_persists_its_votes() {
 				inherent_data_providers: InherentDataProviders::new(),
 				on_exit: Exit,
 				telemetry_on_connect: None,
+				misbehavior_reporter: None,
 			};
 			let mut voter = run_grandpa_voter(grandpa_params).expect("all in order with client and network");
 
@@ -1234,7 +1238,7 @@ fn voter_persists_its_votes() {
 		let config = Config {
 			gossip_duration: TEST_GOSSIP_DURATION,
 			justification_period: 32,
-			local_key: Some(Arc::new(peers[1].clone().into())),
+			local_key: SharedKey::new(Some(Arc::new(peers[1].clone().into()))),
 			name: Some(format!("peer#{}", 1)),
 		};
 		let routing = MessageRouting::new(net.clone(), 1);
@@ -1245,7 +1249,7 @@ fn voter_persists_its_votes() {
 			communication::Round(1),
 			communication::SetId(0),
 			Arc::new(VoterSet::from_iter(voters)),
-			Some(config.local_key.unwrap()),
+			config.local_key.get(),
 			HasVoted::No,
 		);
 
@@ -1364,7 +1368,7 @@ fn finalize_3_voters_1_light_observer() {
 				Config {
 					gossip_duration: TEST_GOSSIP_DURATION,
 					justification_period: 32,
-					local_key: None,
+					local_key: SharedKey::default(),
 					name: Some("observer".to_string()),
 				},
 				link,
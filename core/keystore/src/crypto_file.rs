@@ -0,0 +1,179 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate. If not, see <http://www.gnu.org/licenses/>.
+
+//! Encrypted key file format.
+//!
+//! The secret is encrypted with AES-128-CTR using the first half of a PBKDF2-SHA256 derived
+//! key, and authenticated with a Keccak-256 MAC over the second half of the derived key and
+//! the ciphertext.
+
+use crypto::Keccak256;
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+use subtle::ConstantTimeEq;
+
+use crate::{ErrorKind, Result};
+
+/// The version of the key file format written by this crate.
+pub const CURRENT_VERSION: u32 = 1;
+
+const CIPHER: &str = "aes-128-ctr";
+const KDF: &str = "pbkdf2-sha256";
+const SALT_LENGTH: usize = 32;
+const IV_LENGTH: usize = 16;
+/// Bounds on the key derivation iterations accepted from a key file, past the upper one
+/// unlocking the key would take unreasonably long.
+const MIN_ITERATIONS: u32 = 1;
+const MAX_ITERATIONS: u32 = 1 << 20;
+
+/// Contents of an encrypted key file.
+///
+/// All binary fields are hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyFile {
+	/// Version of the key file format.
+	pub version: u32,
+	/// Cipher used to encrypt the secret.
+	pub cipher: String,
+	/// Key derivation function used to derive the encryption key from the password.
+	pub kdf: String,
+	/// Number of key derivation iterations.
+	pub iterations: u32,
+	/// Key derivation salt.
+	pub salt: String,
+	/// Cipher initialization vector.
+	pub iv: String,
+	/// The encrypted secret.
+	pub ciphertext: String,
+	/// MAC authenticating the ciphertext.
+	pub mac: String,
+}
+
+impl KeyFile {
+	/// Encrypt `secret` with a key derived from `password`.
+	pub fn encrypt(secret: &str, password: &str, iterations: u32) -> Result<Self> {
+		let mut rng = rand::thread_rng();
+		let mut salt = [0u8; SALT_LENGTH];
+		let mut iv = [0u8; IV_LENGTH];
+		rng.fill(&mut salt);
+		rng.fill(&mut iv);
+
+		let (derived_left_bits, derived_right_bits) =
+			crypto::derive_key_iterations(password.as_bytes(), &salt, iterations);
+
+		let mut ciphertext = vec![0u8; secret.len()];
+		crypto::aes::encrypt_128_ctr(&derived_left_bits, &iv, secret.as_bytes(), &mut ciphertext)
+			.map_err(|_| ErrorKind::InvalidKeyFile("encryption failed".into()))?;
+		let mac = crypto::derive_mac(&derived_right_bits, &ciphertext).keccak256();
+
+		Ok(KeyFile {
+			version: CURRENT_VERSION,
+			cipher: CIPHER.into(),
+			kdf: KDF.into(),
+			iterations,
+			salt: hex::encode(&salt[..]),
+			iv: hex::encode(&iv[..]),
+			ciphertext: hex::encode(&ciphertext),
+			mac: hex::encode(&mac[..]),
+		})
+	}
+
+	/// Decrypt the secret with a key derived from `password`.
+	pub fn decrypt(&self, password: &str) -> Result<String> {
+		if self.version != CURRENT_VERSION {
+			return Err(ErrorKind::UnsupportedVersion(self.version).into());
+		}
+		if self.cipher != CIPHER || self.kdf != KDF {
+			return Err(ErrorKind::InvalidKeyFile(format!("unknown cipher {} or kdf {}", self.cipher, self.kdf)).into());
+		}
+
+		let salt = decode_hex("salt", &self.salt)?;
+		let iv = decode_hex("iv", &self.iv)?;
+		let ciphertext = decode_hex("ciphertext", &self.ciphertext)?;
+		let mac = decode_hex("mac", &self.mac)?;
+		if iv.len() != IV_LENGTH {
+			return Err(ErrorKind::InvalidKeyFile("invalid iv length".into()).into());
+		}
+		if salt.len() != SALT_LENGTH {
+			return Err(ErrorKind::InvalidKeyFile("invalid salt length".into()).into());
+		}
+		if self.iterations < MIN_ITERATIONS || self.iterations > MAX_ITERATIONS {
+			return Err(ErrorKind::InvalidKeyFile(format!("invalid iteration count {}", self.iterations)).into());
+		}
+
+		let (derived_left_bits, derived_right_bits) =
+			crypto::derive_key_iterations(password.as_bytes(), &salt, self.iterations);
+
+		let expected_mac = crypto::derive_mac(&derived_right_bits, &ciphertext).keccak256();
+		if !bool::from(expected_mac[..].ct_eq(&mac[..])) {
+			return Err(ErrorKind::InvalidPassword.into());
+		}
+
+		let mut plain = vec![0u8; ciphertext.len()];
+		crypto::aes::decrypt_128_ctr(&derived_left_bits, &iv, &ciphertext, &mut plain)
+			.map_err(|_| ErrorKind::InvalidKeyFile("decryption failed".into()))?;
+
+		String::from_utf8(plain).map_err(|_| ErrorKind::InvalidKeyFile("secret is not valid UTF-8".into()).into())
+	}
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>> {
+	hex::decode(value).map_err(|_| ErrorKind::InvalidKeyFile(format!("{} is not valid hex", field)).into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encrypt_decrypt_roundtrip() {
+		let key_file = KeyFile::encrypt("the secret phrase", "thepassword", 16).unwrap();
+
+		assert_eq!(key_file.decrypt("thepassword").unwrap(), "the secret phrase");
+		assert!(key_file.decrypt("notthepassword").is_err());
+	}
+
+	#[test]
+	fn tampered_ciphertext_is_rejected() {
+		let mut key_file = KeyFile::encrypt("the secret phrase", "thepassword", 16).unwrap();
+		key_file.ciphertext.replace_range(0..2, if &key_file.ciphertext[0..2] == "00" { "01" } else { "00" });
+
+		assert!(key_file.decrypt("thepassword").is_err());
+	}
+
+	#[test]
+	fn unreasonable_parameters_are_rejected() {
+		let key_file = KeyFile::encrypt("the secret phrase", "thepassword", 16).unwrap();
+
+		for iterations in &[0, u32::max_value()] {
+			let key_file = KeyFile { iterations: *iterations, ..key_file.clone() };
+			assert!(key_file.decrypt("thepassword").is_err());
+		}
+		let key_file = KeyFile { salt: key_file.salt[..16].to_owned(), ..key_file };
+		assert!(key_file.decrypt("thepassword").is_err());
+	}
+
+	#[test]
+	fn unknown_version_is_rejected() {
+		let mut key_file = KeyFile::encrypt("the secret phrase", "thepassword", 16).unwrap();
+		key_file.version = CURRENT_VERSION + 1;
+
+		match key_file.decrypt("thepassword").unwrap_err().kind() {
+			ErrorKind::UnsupportedVersion(v) => assert_eq!(*v, CURRENT_VERSION + 1),
+			e => panic!("Unexpected error: {:?}", e),
+		}
+	}
+}
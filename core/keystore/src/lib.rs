@@ -14,23 +14,33 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate. If not, see <http://www.gnu.org/licenses/>.
 
-//! Keystore (and session key management) for ed25519 based chains like Polkadot.
+//! Keystore (and session key management) for ed25519 and sr25519 based chains like Polkadot.
+//!
+//! Every key is kept in its own file, holding the BIP39 phrase of the key (or the secret URI
+//! it was inserted with) encrypted with a password-derived key. Files written by older versions of the store hold the phrase in
+//! plain text; they are upgraded to the encrypted format the first time they are unlocked.
 
 // Silence: `use of deprecated item 'std::error::Error::cause': replaced by Error::source, which can support downcasting`
 // https://github.com/paritytech/substrate/issues/1547
 #![allow(deprecated)]
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs::{self, File};
 use std::io::{self, Write};
 
 use error_chain::{bail, error_chain, error_chain_processing, impl_error_chain_processed,
 	impl_extract_backtrace, impl_error_chain_kind};
 
-use substrate_primitives::{ed25519::{Pair, Public}, Pair as PairT};
+use substrate_primitives::{ed25519::{Pair, Public}, sr25519, Pair as PairT};
+
+mod crypto_file;
 
 pub use crypto::KEY_ITERATIONS;
+pub use crypto_file::{KeyFile, CURRENT_VERSION};
+
+/// Name of the file holding the public key selected with `Store::set_authority_key`.
+const AUTHORITY_KEY_FILE: &str = "authority";
 
 error_chain! {
 	foreign_links {
@@ -51,73 +61,179 @@ error_chain! {
 			description("Invalid seed"),
 			display("Invalid seed"),
 		}
+		InvalidKeyFile(reason: String) {
+			description("Invalid key file"),
+			display("Invalid key file: {}", reason),
+		}
+		UnsupportedVersion(version: u32) {
+			description("Unsupported key file version"),
+			display("Unsupported key file version: {}", version),
+		}
+	}
+}
+
+/// A key pair type which can be kept in the store.
+pub trait StoreKey: PairT {
+	/// Prefix of the names of the files holding keys of this type.
+	///
+	/// File names are made of this prefix followed by the hex encoded public key.
+	const FILE_PREFIX: &'static str;
+
+	/// Get the raw bytes of a public key.
+	fn public_to_raw(public: &Self::Public) -> [u8; 32];
+
+	/// Build a public key from raw bytes.
+	fn public_from_raw(raw: [u8; 32]) -> Self::Public;
+}
+
+impl StoreKey for Pair {
+	// ed25519 keys have been stored without a prefix from the very beginning.
+	const FILE_PREFIX: &'static str = "";
+
+	fn public_to_raw(public: &Public) -> [u8; 32] {
+		public.0
+	}
+
+	fn public_from_raw(raw: [u8; 32]) -> Public {
+		Public(raw)
+	}
+}
+
+impl StoreKey for sr25519::Pair {
+	const FILE_PREFIX: &'static str = "sr25519-";
+
+	fn public_to_raw(public: &sr25519::Public) -> [u8; 32] {
+		public.0
+	}
+
+	fn public_from_raw(raw: [u8; 32]) -> sr25519::Public {
+		sr25519::Public(raw)
 	}
 }
 
 /// Key store.
 pub struct Store {
 	path: PathBuf,
-	additional: HashMap<Public, Pair>,
+	/// Password the key files are encrypted with.
+	password: String,
+	/// Seeds of keys which are not placed into the store, by key type prefix and public key.
+	additional: HashMap<(&'static str, [u8; 32]), String>,
 }
 
 impl Store {
-	/// Create a new store at the given path.
-	pub fn open(path: PathBuf) -> Result<Self> {
+	/// Create a new store at the given path, encrypting and decrypting key files with `password`.
+	pub fn open(path: PathBuf, password: String) -> Result<Self> {
 		fs::create_dir_all(&path)?;
-		Ok(Store { path, additional: HashMap::new() })
+		Ok(Store { path, password, additional: HashMap::new() })
 	}
 
-	/// Generate a new key, placing it into the store.
-	pub fn generate(&self, password: &str) -> Result<Pair> {
-		let (pair, phrase) = Pair::generate_with_phrase(Some(password));
-		let mut file = File::create(self.key_file_path(&pair.public()))?;
-		::serde_json::to_writer(&file, &phrase)?;
-		file.flush()?;
+	/// Generate a new ed25519 key, placing it into the store.
+	pub fn generate(&self) -> Result<Pair> {
+		self.generate_by_type::<Pair>()
+	}
+
+	/// Generate a new key of the given type, placing it into the store.
+	pub fn generate_by_type<P: StoreKey>(&self) -> Result<P> {
+		let (pair, phrase) = P::generate_with_phrase(Some(&self.password));
+		self.write_key_file::<P>(&pair.public(), &phrase)?;
+		Ok(pair)
+	}
+
+	/// Insert a key of the given type derived from the secret URI, placing it into the store.
+	pub fn insert_by_type<P: StoreKey>(&self, suri: &str) -> Result<P> {
+		let pair = P::from_string(suri, None)
+			.map_err(|_| Error::from(ErrorKind::InvalidSeed))?;
+		self.write_key_file::<P>(&pair.public(), suri)?;
 		Ok(pair)
 	}
 
-	/// Create a new key from seed. Do not place it into the store.
+	/// Create a new ed25519 key from seed. Do not place it into the store.
 	pub fn generate_from_seed(&mut self, seed: &str) -> Result<Pair> {
-		let pair = Pair::from_string(seed, None)
+		self.generate_from_seed_by_type::<Pair>(seed)
+	}
+
+	/// Create a new key of the given type from seed. Do not place it into the store.
+	pub fn generate_from_seed_by_type<P: StoreKey>(&mut self, seed: &str) -> Result<P> {
+		let pair = P::from_string(seed, None)
 			.map_err(|_| Error::from(ErrorKind::InvalidSeed))?;
-		self.additional.insert(pair.public(), pair.clone());
+		self.additional.insert((P::FILE_PREFIX, P::public_to_raw(&pair.public())), seed.to_owned());
 		Ok(pair)
 	}
 
-	/// Load a key file with given public key.
-	pub fn load(&self, public: &Public, password: &str) -> Result<Pair> {
-		if let Some(pair) = self.additional.get(public) {
-			return Ok(pair.clone());
+	/// Load an ed25519 key file with given public key.
+	pub fn load(&self, public: &Public) -> Result<Pair> {
+		self.load_by_type::<Pair>(public)
+	}
+
+	/// Load a key file of the given type with given public key.
+	///
+	/// Key files in the legacy plain text format are rewritten in the encrypted format
+	/// once they were successfully unlocked with the store's password.
+	pub fn load_by_type<P: StoreKey>(&self, public: &P::Public) -> Result<P> {
+		let password = &self.password[..];
+		let raw_public = P::public_to_raw(public);
+		if let Some(seed) = self.additional.get(&(P::FILE_PREFIX, raw_public)) {
+			return P::from_string(seed, None).map_err(|_| ErrorKind::InvalidSeed.into());
 		}
-		let path = self.key_file_path(public);
-		let file = File::open(path)?;
 
-		let phrase: String = ::serde_json::from_reader(&file)?;
-		let pair = Pair::from_phrase(&phrase, Some(password))
-			.map_err(|_| Error::from(ErrorKind::InvalidPhrase))?;
-		if &pair.public() != public {
-			bail!(ErrorKind::InvalidPassword);
+		let path = self.key_file_path::<P>(public);
+		let file = File::open(path)?;
+		let (phrase, is_legacy) = match serde_json::from_reader(&file)? {
+			serde_json::Value::String(phrase) => (phrase, true),
+			value => {
+				let key_file: KeyFile = serde_json::from_value(value)?;
+				(key_file.decrypt(password)?, false)
+			}
+		};
+
+		// generated keys are stored as a phrase protected by the password, inserted keys as a secret URI.
+		let matches = |pair: &P| P::public_to_raw(&pair.public()) == raw_public;
+		let pair = P::from_phrase(&phrase, Some(password)).ok()
+			.filter(&matches)
+			.or_else(|| if is_legacy {
+				None
+			} else {
+				P::from_string(&phrase, None).ok().filter(&matches)
+			});
+		let pair = match pair {
+			Some(pair) => pair,
+			None => bail!(ErrorKind::InvalidPassword),
+		};
+
+		if is_legacy {
+			self.write_key_file::<P>(public, &phrase)?;
 		}
+
 		Ok(pair)
 	}
 
-	/// Get public keys of all stored keys.
+	/// Get public keys of all stored ed25519 keys.
 	pub fn contents(&self) -> Result<Vec<Public>> {
-		let mut public_keys: Vec<Public> = self.additional.keys().cloned().collect();
+		self.contents_by_type::<Pair>()
+	}
+
+	/// Get public keys of all stored keys of the given type.
+	pub fn contents_by_type<P: StoreKey>(&self) -> Result<Vec<P::Public>> {
+		let mut public_keys: Vec<P::Public> = self.additional.keys()
+			.filter(|(prefix, _)| *prefix == P::FILE_PREFIX)
+			.map(|(_, raw)| P::public_from_raw(*raw))
+			.collect();
 		for entry in fs::read_dir(&self.path)? {
 			let entry = entry?;
 			let path = entry.path();
 
 			// skip directories and non-unicode file names (hex is unicode)
 			if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-				if name.len() != 64 { continue }
+				if name.len() != P::FILE_PREFIX.len() + 64 || !name.starts_with(P::FILE_PREFIX) {
+					continue
+				}
 
-				match hex::decode(name) {
+				match hex::decode(&name[P::FILE_PREFIX.len()..]) {
 					Ok(ref hex) if hex.len() == 32 => {
 						let mut buf = [0; 32];
 						buf.copy_from_slice(&hex[..]);
 
-						public_keys.push(Public(buf));
+						public_keys.push(P::public_from_raw(buf));
 					}
 					_ => continue,
 				}
@@ -127,42 +243,187 @@ impl Store {
 		Ok(public_keys)
 	}
 
-	fn key_file_path(&self, public: &Public) -> PathBuf {
+	/// Select the stored ed25519 key to be used as authority key.
+	pub fn set_authority_key(&self, public: &Public) -> Result<()> {
+		write_atomically(&self.path.join(AUTHORITY_KEY_FILE), hex::encode(&public.0[..]).as_bytes())
+	}
+
+	/// Get the public key of the ed25519 key to be used as authority key.
+	///
+	/// Keys created from a seed come first, then the key selected with `set_authority_key`
+	/// if it is still stored. Other stored keys are never used implicitly.
+	pub fn authority_key(&self) -> Result<Option<Public>> {
+		let from_seed = self.additional.keys()
+			.filter(|(prefix, _)| *prefix == Pair::FILE_PREFIX)
+			.map(|(_, raw)| *raw)
+			.min();
+		if let Some(raw) = from_seed {
+			return Ok(Some(Public(raw)));
+		}
+
+		let stored = self.contents()?;
+		let selected = match fs::read_to_string(self.path.join(AUTHORITY_KEY_FILE)) {
+			Ok(encoded) => match hex::decode(encoded.trim()) {
+				Ok(ref raw) if raw.len() == 32 => {
+					let mut buf = [0; 32];
+					buf.copy_from_slice(&raw[..]);
+					Some(Public(buf))
+				},
+				_ => None,
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => None,
+			Err(e) => return Err(e.into()),
+		};
+
+		Ok(selected.filter(|selected| stored.contains(selected)))
+	}
+
+	fn write_key_file<P: StoreKey>(&self, public: &P::Public, phrase: &str) -> Result<()> {
+		let key_file = KeyFile::encrypt(phrase, &self.password, KEY_ITERATIONS as u32)?;
+		// the key file may be the only copy of the secret, so it is replaced atomically.
+		write_atomically(&self.key_file_path::<P>(public), &serde_json::to_vec(&key_file)?)
+	}
+
+	fn key_file_path<P: StoreKey>(&self, public: &P::Public) -> PathBuf {
 		let mut buf = self.path.clone();
-		buf.push(hex::encode(public.as_slice()));
+		buf.push(format!("{}{}", P::FILE_PREFIX, hex::encode(&P::public_to_raw(public)[..])));
 		buf
 	}
 }
 
+/// Replace the file at `path` with `contents`, readable by the owner only.
+///
+/// The contents are written to a temporary file first, so that a crash leaves either
+/// the previous or the new file in place.
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+	let tmp_path = path.with_extension("tmp");
+	let mut options = fs::OpenOptions::new();
+	options.write(true).create(true).truncate(true);
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+		options.mode(0o600);
+	}
+	let mut file = options.open(&tmp_path)?;
+	file.write_all(contents)?;
+	file.sync_all()?;
+	fs::rename(&tmp_path, path)?;
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use tempdir::TempDir;
 	use substrate_primitives::crypto::Ss58Codec;
 
+	fn open(temp_dir: &TempDir, password: &str) -> Store {
+		Store::open(temp_dir.path().to_owned(), password.into()).unwrap()
+	}
+
 	#[test]
 	fn basic_store() {
 		let temp_dir = TempDir::new("keystore").unwrap();
-		let store = Store::open(temp_dir.path().to_owned()).unwrap();
+		let store = open(&temp_dir, "thepassword");
 
 		assert!(store.contents().unwrap().is_empty());
 
-		let key = store.generate("thepassword").unwrap();
-		let key2 = store.load(&key.public(), "thepassword").unwrap();
+		let key = store.generate().unwrap();
+		let key2 = store.load(&key.public()).unwrap();
 
-		assert!(store.load(&key.public(), "notthepassword").is_err());
+		assert!(open(&temp_dir, "notthepassword").load(&key.public()).is_err());
 
 		assert_eq!(key.public(), key2.public());
 
 		assert_eq!(store.contents().unwrap()[0], key.public());
 	}
 
+	#[test]
+	fn authority_key_is_selected_explicitly() {
+		let temp_dir = TempDir::new("keystore").unwrap();
+		let store = open(&temp_dir, "");
+		assert_eq!(store.authority_key().unwrap(), None);
+
+		let first = store.generate().unwrap().public();
+		let second = store.generate().unwrap().public();
+		assert_eq!(store.authority_key().unwrap(), None);
+
+		store.set_authority_key(&second).unwrap();
+		assert_eq!(store.authority_key().unwrap(), Some(second.clone()));
+		store.set_authority_key(&first).unwrap();
+		assert_eq!(store.authority_key().unwrap(), Some(first));
+	}
+
 	#[test]
 	fn test_generate_from_seed() {
 		let temp_dir = TempDir::new("keystore").unwrap();
-		let mut store = Store::open(temp_dir.path().to_owned()).unwrap();
+		let mut store = open(&temp_dir, "");
 
 		let pair = store.generate_from_seed("0x3d97c819d68f9bafa7d6e79cb991eebcd77d966c5334c0b94d9e1fa7ad0869dc").unwrap();
 		assert_eq!("5DKUrgFqCPV8iAXx9sjy1nyBygQCeiUYRFWurZGhnrn3HJCA", pair.public().to_ss58check());
 	}
+
+	#[test]
+	fn key_files_do_not_contain_the_phrase() {
+		let temp_dir = TempDir::new("keystore").unwrap();
+		let store = open(&temp_dir, "thepassword");
+
+		let key = store.generate().unwrap();
+		let path = store.key_file_path::<Pair>(&key.public());
+		let key_file: KeyFile = serde_json::from_reader(File::open(path).unwrap()).unwrap();
+
+		assert_eq!(key_file.version, CURRENT_VERSION);
+		assert!(key_file.decrypt("notthepassword").is_err());
+		let phrase = key_file.decrypt("thepassword").unwrap();
+		assert_eq!(Pair::from_phrase(&phrase, Some("thepassword")).unwrap().public(), key.public());
+	}
+
+	#[test]
+	fn sr25519_keys_are_stored_separately() {
+		let temp_dir = TempDir::new("keystore").unwrap();
+		let store = open(&temp_dir, "thepassword");
+
+		let ed_key = store.generate().unwrap();
+		let sr_key = store.generate_by_type::<sr25519::Pair>().unwrap();
+		let sr_key2 = store.load_by_type::<sr25519::Pair>(&sr_key.public()).unwrap();
+
+		assert_eq!(sr_key.public(), sr_key2.public());
+		assert_eq!(store.contents().unwrap(), vec![ed_key.public()]);
+		assert_eq!(store.contents_by_type::<sr25519::Pair>().unwrap(), vec![sr_key.public()]);
+	}
+
+	#[test]
+	fn inserted_keys_are_loaded_from_secret_uri() {
+		let temp_dir = TempDir::new("keystore").unwrap();
+		let store = open(&temp_dir, "thepassword");
+
+		let key = store.insert_by_type::<sr25519::Pair>("//Alice///secret").unwrap();
+		let key2 = store.load_by_type::<sr25519::Pair>(&key.public()).unwrap();
+
+		assert_eq!(key.public(), sr25519::Pair::from_string("//Alice///secret", None).unwrap().public());
+		assert_eq!(key.public(), key2.public());
+		assert!(open(&temp_dir, "notthepassword").load_by_type::<sr25519::Pair>(&key.public()).is_err());
+		assert_eq!(store.contents_by_type::<sr25519::Pair>().unwrap(), vec![key.public()]);
+		assert!(store.insert_by_type::<Pair>("not a valid/seed").is_err());
+	}
+
+	#[test]
+	fn legacy_key_files_are_upgraded_on_load() {
+		let temp_dir = TempDir::new("keystore").unwrap();
+		let store = open(&temp_dir, "thepassword");
+
+		let (key, phrase) = Pair::generate_with_phrase(Some("thepassword"));
+		let path = store.key_file_path::<Pair>(&key.public());
+		serde_json::to_writer(&File::create(&path).unwrap(), &phrase).unwrap();
+
+		// a wrong password must leave the legacy file untouched.
+		assert!(open(&temp_dir, "notthepassword").load(&key.public()).is_err());
+		let legacy: String = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+		assert_eq!(legacy, phrase);
+
+		assert_eq!(store.load(&key.public()).unwrap().public(), key.public());
+		let key_file: KeyFile = serde_json::from_reader(File::open(&path).unwrap()).unwrap();
+		assert_eq!(key_file.decrypt("thepassword").unwrap(), phrase);
+		assert_eq!(store.load(&key.public()).unwrap().public(), key.public());
+	}
 }
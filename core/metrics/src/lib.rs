@@ -0,0 +1,329 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node metrics.
+//!
+//! Counters, gauges and histograms may be registered in the global `registry()`
+//! from anywhere in the Substrate codebase and updated without any further
+//! coordination. If enabled, the current values are served in the Prometheus
+//! text format on the `/metrics` path of the server started with `init_prometheus`.
+
+#![warn(missing_docs)]
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use log::warn;
+use parking_lot::{Mutex, RwLock};
+
+mod server;
+
+pub use server::init_prometheus;
+
+/// Default upper bounds of histogram buckets, in seconds.
+pub const DEFAULT_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+lazy_static! {
+	static ref REGISTRY: Registry = Registry::default();
+}
+
+/// The global registry, its metrics are exported by the Prometheus server.
+pub fn registry() -> &'static Registry {
+	&REGISTRY
+}
+
+/// Registers a counter in the global registry.
+pub fn counter(name: &'static str, help: &'static str) -> Arc<Counter> {
+	registry().counter(name, help)
+}
+
+/// Registers a gauge in the global registry.
+pub fn gauge(name: &'static str, help: &'static str) -> Arc<Gauge> {
+	registry().gauge(name, help)
+}
+
+/// Registers a histogram with given bucket upper bounds in the global registry.
+pub fn histogram(name: &'static str, help: &'static str, buckets: &[f64]) -> Arc<Histogram> {
+	registry().histogram(name, help, buckets)
+}
+
+/// Monotonically increasing value.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+	/// Increment the counter by one.
+	pub fn inc(&self) {
+		self.inc_by(1);
+	}
+
+	/// Increment the counter by `value`.
+	pub fn inc_by(&self, value: u64) {
+		self.0.fetch_add(value, Ordering::Relaxed);
+	}
+
+	/// Current value of the counter.
+	pub fn get(&self) -> u64 {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+/// Value which may go up and down.
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+	/// Set the gauge to `value`.
+	pub fn set(&self, value: i64) {
+		self.0.store(value, Ordering::Relaxed);
+	}
+
+	/// Add `value` to the gauge.
+	pub fn add(&self, value: i64) {
+		self.0.fetch_add(value, Ordering::Relaxed);
+	}
+
+	/// Subtract `value` from the gauge.
+	pub fn sub(&self, value: i64) {
+		self.0.fetch_sub(value, Ordering::Relaxed);
+	}
+
+	/// Current value of the gauge.
+	pub fn get(&self) -> i64 {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+/// Distribution of observed values over a set of buckets.
+#[derive(Debug)]
+pub struct Histogram {
+	/// Upper bounds of the buckets, in ascending order.
+	buckets: Vec<f64>,
+	inner: Mutex<HistogramInner>,
+}
+
+#[derive(Debug)]
+struct HistogramInner {
+	/// Number of observations per bucket, the last one is the `+Inf` bucket.
+	counts: Vec<u64>,
+	sum: f64,
+	count: u64,
+}
+
+impl Histogram {
+	/// Create new histogram with given bucket upper bounds.
+	pub fn new(buckets: &[f64]) -> Self {
+		let mut buckets = buckets.to_vec();
+		buckets.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+		buckets.dedup();
+
+		Histogram {
+			inner: Mutex::new(HistogramInner {
+				counts: vec![0; buckets.len() + 1],
+				sum: 0.0,
+				count: 0,
+			}),
+			buckets,
+		}
+	}
+
+	/// Record an observation.
+	pub fn observe(&self, value: f64) {
+		let bucket = self.buckets.iter()
+			.position(|upper| value <= *upper)
+			.unwrap_or(self.buckets.len());
+
+		let mut inner = self.inner.lock();
+		inner.counts[bucket] += 1;
+		inner.sum += value;
+		inner.count += 1;
+	}
+
+	/// Record a duration, in seconds.
+	pub fn observe_duration(&self, duration: Duration) {
+		self.observe(duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1_000_000_000.0);
+	}
+
+	/// Total number of observations.
+	pub fn count(&self) -> u64 {
+		self.inner.lock().count
+	}
+
+	fn encode(&self, name: &str, out: &mut String) {
+		let inner = self.inner.lock();
+		let mut cumulative = 0;
+		for (upper, count) in self.buckets.iter().zip(inner.counts.iter()) {
+			cumulative += count;
+			let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, upper, cumulative);
+		}
+		let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, inner.count);
+		let _ = writeln!(out, "{}_sum {}", name, inner.sum);
+		let _ = writeln!(out, "{}_count {}", name, inner.count);
+	}
+}
+
+#[derive(Clone)]
+enum Metric {
+	Counter(Arc<Counter>),
+	Gauge(Arc<Gauge>),
+	Histogram(Arc<Histogram>),
+}
+
+impl Metric {
+	fn type_name(&self) -> &'static str {
+		match self {
+			Metric::Counter(_) => "counter",
+			Metric::Gauge(_) => "gauge",
+			Metric::Histogram(_) => "histogram",
+		}
+	}
+}
+
+struct Entry {
+	help: &'static str,
+	metric: Metric,
+}
+
+/// Collection of named metrics.
+#[derive(Default)]
+pub struct Registry {
+	metrics: RwLock<BTreeMap<&'static str, Entry>>,
+}
+
+impl Registry {
+	/// Register a counter or return the one already registered under `name`.
+	pub fn counter(&self, name: &'static str, help: &'static str) -> Arc<Counter> {
+		match self.register(name, help, || Metric::Counter(Default::default())) {
+			Some(Metric::Counter(counter)) => counter,
+			_ => Default::default(),
+		}
+	}
+
+	/// Register a gauge or return the one already registered under `name`.
+	pub fn gauge(&self, name: &'static str, help: &'static str) -> Arc<Gauge> {
+		match self.register(name, help, || Metric::Gauge(Default::default())) {
+			Some(Metric::Gauge(gauge)) => gauge,
+			_ => Default::default(),
+		}
+	}
+
+	/// Register a histogram or return the one already registered under `name`.
+	pub fn histogram(&self, name: &'static str, help: &'static str, buckets: &[f64]) -> Arc<Histogram> {
+		match self.register(name, help, || Metric::Histogram(Arc::new(Histogram::new(buckets)))) {
+			Some(Metric::Histogram(histogram)) => histogram,
+			_ => Arc::new(Histogram::new(buckets)),
+		}
+	}
+
+	/// Returns the metric registered under `name`, registering the one built by `metric`
+	/// if there is none.
+	///
+	/// Returns `None` if a metric of a different type is already registered under `name`.
+	fn register<F: FnOnce() -> Metric>(&self, name: &'static str, help: &'static str, metric: F) -> Option<Metric> {
+		let mut metrics = self.metrics.write();
+		let metric = metric();
+		let entry = metrics.entry(name).or_insert_with(|| Entry { help, metric: metric.clone() });
+		if entry.metric.type_name() != metric.type_name() {
+			warn!(
+				"Metric {} is already registered as a {}, the new {} is not exported",
+				name,
+				entry.metric.type_name(),
+				metric.type_name(),
+			);
+			return None;
+		}
+
+		Some(entry.metric.clone())
+	}
+
+	/// Encode all registered metrics in the Prometheus text format.
+	pub fn encode(&self) -> String {
+		let mut out = String::new();
+		for (name, entry) in self.metrics.read().iter() {
+			let _ = writeln!(out, "# HELP {} {}", name, entry.help);
+			let _ = writeln!(out, "# TYPE {} {}", name, entry.metric.type_name());
+			match &entry.metric {
+				Metric::Counter(counter) => { let _ = writeln!(out, "{} {}", name, counter.get()); },
+				Metric::Gauge(gauge) => { let _ = writeln!(out, "{} {}", name, gauge.get()); },
+				Metric::Histogram(histogram) => histogram.encode(name, &mut out),
+			}
+		}
+		out
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_encode_counters_and_gauges() {
+		let registry = Registry::default();
+		let counter = registry.counter("test_counter", "A counter");
+		let gauge = registry.gauge("test_gauge", "A gauge");
+
+		counter.inc();
+		counter.inc_by(2);
+		gauge.set(10);
+		gauge.sub(12);
+
+		assert_eq!(registry.encode(), concat!(
+			"# HELP test_counter A counter\n",
+			"# TYPE test_counter counter\n",
+			"test_counter 3\n",
+			"# HELP test_gauge A gauge\n",
+			"# TYPE test_gauge gauge\n",
+			"test_gauge -2\n",
+		));
+	}
+
+	#[test]
+	fn should_encode_cumulative_histogram_buckets() {
+		let registry = Registry::default();
+		let histogram = registry.histogram("test_histogram", "A histogram", &[1.0, 0.5]);
+
+		histogram.observe(0.25);
+		histogram.observe(0.75);
+		histogram.observe(2.0);
+
+		assert_eq!(registry.encode(), concat!(
+			"# HELP test_histogram A histogram\n",
+			"# TYPE test_histogram histogram\n",
+			"test_histogram_bucket{le=\"0.5\"} 1\n",
+			"test_histogram_bucket{le=\"1\"} 2\n",
+			"test_histogram_bucket{le=\"+Inf\"} 3\n",
+			"test_histogram_sum 3\n",
+			"test_histogram_count 3\n",
+		));
+	}
+
+	#[test]
+	fn should_share_metrics_registered_under_the_same_name() {
+		let registry = Registry::default();
+		registry.counter("test_metric", "A counter").inc();
+		registry.counter("test_metric", "A counter").inc();
+
+		let gauge = registry.gauge("test_metric", "A gauge");
+		gauge.set(42);
+
+		assert_eq!(registry.counter("test_metric", "A counter").get(), 2);
+		assert!(registry.encode().contains("test_metric 2\n"));
+	}
+}
@@ -0,0 +1,54 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP server exporting the global registry.
+
+use std::{io, net::SocketAddr};
+
+use futures::Future;
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::service_fn_ok;
+use log::{info, warn};
+
+/// Content type of the Prometheus text format.
+const CONTENT_TYPE_TEXT: &str = "text/plain; version=0.0.4";
+
+/// Bind the Prometheus server to given address.
+///
+/// The returned future serves the metrics of the global registry on `/metrics` and
+/// needs to be spawned on an executor.
+pub fn init_prometheus(address: SocketAddr) -> io::Result<impl Future<Item = (), Error = ()> + Send> {
+	let server = Server::try_bind(&address)
+		.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+		.serve(|| service_fn_ok(handle));
+
+	info!("Prometheus server started at {}", server.local_addr());
+	Ok(server.map_err(|e| warn!("Prometheus server error: {}", e)))
+}
+
+fn handle(request: Request<Body>) -> Response<Body> {
+	let response = match (request.method(), request.uri().path()) {
+		(&Method::GET, "/metrics") => Response::builder()
+			.header(CONTENT_TYPE, CONTENT_TYPE_TEXT)
+			.body(Body::from(crate::registry().encode())),
+		_ => Response::builder()
+			.status(StatusCode::NOT_FOUND)
+			.body(Body::empty()),
+	};
+
+	response.expect("Response is built from valid static parts; qed")
+}
@@ -14,18 +14,32 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::{cmp, sync::Arc, thread};
 use futures::{Stream, Future, sync::mpsc};
 use inherents::pool::InherentsPool;
 use log::{info, debug, warn};
 use parity_codec::Decode;
-use primitives::OffchainExt;
+use primitives::{
+	OffchainExt,
+	offchain::{
+		OffchainStorage, STORAGE_PREFIX, CryptoKind,
+		HttpError, HttpRequestId, HttpRequestStatus, Timestamp,
+	},
+};
 use runtime_primitives::{
 	generic::BlockId,
 	traits::{self, Extrinsic},
 };
 use transaction_pool::txpool::{Pool, ChainApi};
 
+pub(crate) mod http;
+pub(crate) mod keys;
+pub(crate) mod timestamp;
+
+/// Longest time a worker run may spend waiting for HTTP responses or sleeping,
+/// later deadlines (including no deadline at all) are clamped to it.
+const MAX_WORKER_RUN_MILLIS: u64 = 60_000;
+
 /// A message between the offchain extension and the processing thread.
 enum ExtMessage {
 	SubmitExtrinsic(Vec<u8>),
@@ -34,11 +48,120 @@ enum ExtMessage {
 /// Asynchronous offchain API.
 ///
 /// NOTE this is done to prevent recursive calls into the runtime (which are not supported currently).
-pub(crate) struct AsyncApi(mpsc::UnboundedSender<ExtMessage>);
+pub(crate) struct AsyncApi<S> {
+	sender: mpsc::UnboundedSender<ExtMessage>,
+	db: S,
+	http: http::HttpApi,
+	keys: keys::KeysApi,
+	run_deadline: Timestamp,
+}
 
-impl OffchainExt for AsyncApi {
+impl<S> AsyncApi<S> {
+	/// Limit `deadline` to the end of the run.
+	fn clamp(&self, deadline: Option<Timestamp>) -> Option<Timestamp> {
+		Some(deadline.map_or(self.run_deadline, |deadline| cmp::min(deadline, self.run_deadline)))
+	}
+}
+
+impl<S: OffchainStorage> OffchainExt for AsyncApi<S> {
 	fn submit_extrinsic(&mut self, ext: Vec<u8>) {
-		let _ = self.0.unbounded_send(ExtMessage::SubmitExtrinsic(ext));
+		let _ = self.sender.unbounded_send(ExtMessage::SubmitExtrinsic(ext));
+	}
+
+	fn local_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		self.db.set(STORAGE_PREFIX, key, value)
+	}
+
+	fn local_storage_compare_and_set(&mut self, key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool {
+		self.db.compare_and_set(STORAGE_PREFIX, key, old_value, new_value)
+	}
+
+	fn local_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		self.db.get(STORAGE_PREFIX, key)
+	}
+
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		_meta: &[u8]
+	) -> Result<HttpRequestId, ()> {
+		self.http.request_start(method, uri)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str
+	) -> Result<(), ()> {
+		self.http.request_add_header(request_id, name, value)
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), HttpError> {
+		let deadline = self.clamp(deadline);
+		self.http.request_write_body(request_id, chunk, deadline)
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>
+	) -> Vec<HttpRequestStatus> {
+		let deadline = self.clamp(deadline);
+		self.http.response_wait(ids, deadline)
+	}
+
+	fn http_response_headers(
+		&mut self,
+		request_id: HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.http.response_headers(request_id)
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, HttpError> {
+		let deadline = self.clamp(deadline);
+		self.http.response_read_body(request_id, buffer, deadline)
+	}
+
+	fn authority_pubkeys(&mut self, kind: CryptoKind) -> Vec<Vec<u8>> {
+		self.keys.authority_pubkeys(kind)
+	}
+
+	fn sign(&mut self, kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		self.keys.sign(kind, public, data)
+	}
+
+	fn verify(
+		&mut self,
+		kind: CryptoKind,
+		public: &[u8],
+		msg: &[u8],
+		signature: &[u8]
+	) -> bool {
+		self.keys.verify(kind, public, msg, signature)
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		timestamp::now()
+	}
+
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		thread::sleep(timestamp::time_until(cmp::min(deadline, self.run_deadline)));
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		rand::random()
 	}
 }
 
@@ -51,19 +174,25 @@ pub(crate) struct Api<A: ChainApi> {
 }
 
 impl<A: ChainApi> Api<A> {
-	pub fn new(
+	pub fn new<S: OffchainStorage>(
 		transaction_pool: Arc<Pool<A>>,
 		inherents_pool: Arc<InherentsPool<<A::Block as traits::Block>::Extrinsic>>,
+		db: S,
+		http_transport: Arc<dyn http::HttpTransport>,
+		key_provider: Option<Arc<dyn keys::KeyProvider>>,
 		at: BlockId<A::Block>,
-	) -> (AsyncApi, Self) {
-		let (tx, rx) = mpsc::unbounded();
+	) -> (AsyncApi<S>, Self) {
+		let (sender, rx) = mpsc::unbounded();
 		let api = Self {
 			receiver: Some(rx),
 			transaction_pool,
 			inherents_pool,
 			at,
 		};
-		(AsyncApi(tx), api)
+		let http = http::HttpApi::new(http_transport, Arc::new(timestamp::now));
+		let keys = keys::KeysApi::new(key_provider);
+		let run_deadline = timestamp::now().add_millis(MAX_WORKER_RUN_MILLIS);
+		(AsyncApi { sender, db, http, keys, run_deadline }, api)
 	}
 
 	/// Run a processing task for the API
@@ -97,3 +226,135 @@ impl<A: ChainApi> Api<A> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::offchain::InMemOffchainStorage;
+	use crate::testing::TestTransport;
+
+	fn async_api() -> AsyncApi<InMemOffchainStorage> {
+		let (sender, _) = mpsc::unbounded();
+		let http = http::HttpApi::new(Arc::new(TestTransport::pending()), Arc::new(timestamp::now));
+		AsyncApi {
+			sender,
+			db: InMemOffchainStorage::default(),
+			http,
+			keys: Default::default(),
+			run_deadline: timestamp::now().add_millis(MAX_WORKER_RUN_MILLIS),
+		}
+	}
+
+	#[test]
+	fn should_set_and_get_local_storage() {
+		// given
+		let mut api = async_api();
+		let key = b"test";
+
+		// when
+		assert_eq!(api.local_storage_get(key), None);
+		api.local_storage_set(key, b"value");
+
+		// then
+		assert_eq!(api.local_storage_get(key), Some(b"value".to_vec()));
+	}
+
+	#[test]
+	fn should_compare_and_set_local_storage() {
+		// given
+		let mut api = async_api();
+		let key = b"test";
+		api.local_storage_set(key, b"value");
+
+		// when
+		assert_eq!(api.local_storage_compare_and_set(key, Some(b"val"), b"xxx"), false);
+		assert_eq!(api.local_storage_get(key), Some(b"value".to_vec()));
+
+		// when
+		assert_eq!(api.local_storage_compare_and_set(key, Some(b"value"), b"xxx"), true);
+		assert_eq!(api.local_storage_get(key), Some(b"xxx".to_vec()));
+	}
+
+	#[test]
+	fn should_share_local_storage_between_workers() {
+		// given
+		let mut api1 = async_api();
+		let mut api2 = async_api();
+		api2.db = api1.db.clone();
+		let key = b"test";
+
+		// when
+		assert_eq!(api1.local_storage_compare_and_set(key, None, b"first"), true);
+		assert_eq!(api2.local_storage_compare_and_set(key, None, b"second"), false);
+
+		// then
+		assert_eq!(api2.local_storage_get(key), Some(b"first".to_vec()));
+	}
+
+	#[test]
+	fn should_perform_http_requests_through_transport() {
+		// given
+		let transport = TestTransport::new(|request| Ok(http::HttpResponse {
+			status: 200,
+			headers: vec![],
+			body: request.uri.into_bytes(),
+		}));
+		let (sender, _) = mpsc::unbounded();
+		let mut api = AsyncApi {
+			sender,
+			db: InMemOffchainStorage::default(),
+			http: http::HttpApi::new(Arc::new(transport.clone()), Arc::new(timestamp::now)),
+			keys: Default::default(),
+			run_deadline: timestamp::now().add_millis(MAX_WORKER_RUN_MILLIS),
+		};
+
+		// when
+		let id = api.http_request_start("GET", "http://localhost/price", &[]).unwrap();
+		api.http_request_write_body(id, &[], None).unwrap();
+		let status = api.http_response_wait(&[id], None);
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::Finished(200)]);
+		let mut buffer = [0u8; 64];
+		let read = api.http_response_read_body(id, &mut buffer, None).unwrap();
+		assert_eq!(&buffer[..read], b"http://localhost/price");
+		assert_eq!(transport.requests()[0].method, "GET");
+	}
+
+	#[test]
+	fn should_sleep_until_deadline() {
+		// given
+		let mut api = async_api();
+		let start = api.timestamp();
+
+		// when
+		api.sleep_until(start.add_millis(20));
+
+		// then
+		assert!(api.timestamp() >= start.add_millis(20));
+	}
+
+	#[test]
+	fn should_clamp_deadlines_to_the_end_of_the_run() {
+		// given
+		let mut api = async_api();
+		api.run_deadline = api.timestamp();
+		let id = api.http_request_start("GET", "http://localhost/price", &[]).unwrap();
+
+		// when
+		let status = api.http_response_wait(&[id], None);
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::DeadlineReached]);
+		let start = api.timestamp();
+		api.sleep_until(start.add_millis(60_000));
+		assert!(api.timestamp() < start.add_millis(1_000));
+	}
+
+	#[test]
+	fn should_generate_random_seeds() {
+		let mut api = async_api();
+
+		assert_ne!(api.random_seed(), api.random_seed());
+	}
+}
@@ -0,0 +1,465 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP requests of the offchain workers.
+//!
+//! Requests are built up by the runtime and sent as a whole through an `HttpTransport`
+//! once the body is finalised. The default transport is backed by `hyper` and runs on its
+//! own event loop. Waiting for a response blocks the calling thread, which is why the workers
+//! run on a thread of their own and their deadlines are bounded.
+
+use std::{
+	collections::BTreeMap,
+	fmt,
+	sync::{Arc, Mutex, mpsc},
+	time::Duration,
+};
+
+use futures::{Future, Stream};
+use log::warn;
+use tokio::timer::Timeout;
+use primitives::offchain::{HttpError, HttpRequestId, HttpRequestStatus, Timestamp};
+
+/// Source of the current time, against which the deadlines are measured.
+pub(crate) type Clock = Arc<dyn Fn() -> Timestamp + Send + Sync>;
+
+/// A complete HTTP request ready to be dispatched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+	/// HTTP verb.
+	pub method: String,
+	/// Request URI.
+	pub uri: String,
+	/// Request headers in the order they were added.
+	pub headers: Vec<(String, String)>,
+	/// Request body.
+	pub body: Vec<u8>,
+}
+
+/// A response to an `HttpRequest`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HttpResponse {
+	/// HTTP status code.
+	pub status: u16,
+	/// Response headers.
+	pub headers: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Response body.
+	pub body: Vec<u8>,
+}
+
+/// A transport used to perform the HTTP requests of the offchain workers.
+///
+/// The default implementation is `HyperTransport`, tests may plug in an in-process fake
+/// (see `testing::TestTransport`).
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+	/// Dispatch the request.
+	///
+	/// The result of the request has to be delivered through the returned receiver.
+	/// Dropping the sending side without a message is treated as an IO error.
+	fn dispatch(&self, request: HttpRequest) -> mpsc::Receiver<Result<HttpResponse, String>>;
+}
+
+/// `hyper`-based HTTP transport.
+pub struct HyperTransport {
+	client: hyper::Client<hyper::client::HttpConnector>,
+	/// Event loop driving the requests, started on first use.
+	runtime: Mutex<Option<tokio::runtime::Runtime>>,
+}
+
+impl fmt::Debug for HyperTransport {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("HyperTransport").finish()
+	}
+}
+
+impl Default for HyperTransport {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl HyperTransport {
+	/// Create new `hyper` transport.
+	pub fn new() -> Self {
+		HyperTransport {
+			client: hyper::Client::new(),
+			runtime: Mutex::new(None),
+		}
+	}
+}
+
+impl HttpTransport for HyperTransport {
+	fn dispatch(&self, request: HttpRequest) -> mpsc::Receiver<Result<HttpResponse, String>> {
+		let (tx, rx) = mpsc::channel();
+
+		let mut builder = hyper::Request::builder();
+		builder.method(request.method.as_str()).uri(request.uri.as_str());
+		for (name, value) in &request.headers {
+			builder.header(name.as_str(), value.as_str());
+		}
+		let hyper_request = match builder.body(hyper::Body::from(request.body)) {
+			Ok(hyper_request) => hyper_request,
+			Err(e) => {
+				let _ = tx.send(Err(format!("Invalid request: {}", e)));
+				return rx;
+			},
+		};
+
+		let future = self.client.request(hyper_request)
+			.and_then(|response| {
+				let status = response.status().as_u16();
+				let headers = response.headers().iter()
+					.map(|(name, value)| (name.as_str().as_bytes().to_vec(), value.as_bytes().to_vec()))
+					.collect();
+				response.into_body().concat2().map(move |body| HttpResponse {
+					status,
+					headers,
+					body: body.to_vec(),
+				})
+			});
+		// nobody waits for the response after the end of the worker run, the request is dropped then.
+		let future = Timeout::new(future, Duration::from_millis(super::MAX_WORKER_RUN_MILLIS))
+			.then(move |result| {
+				let _ = tx.send(result.map_err(|e| format!("{}", e)));
+				Ok(())
+			});
+
+		let mut runtime = self.runtime.lock().expect("runtime lock is never poisoned; qed");
+		if runtime.is_none() {
+			match tokio::runtime::Runtime::new() {
+				Ok(rt) => *runtime = Some(rt),
+				Err(e) => {
+					warn!("Unable to start the offchain HTTP event loop: {:?}", e);
+					// dropping the future drops the sender as well, which is reported as an IO error.
+					return rx;
+				},
+			}
+		}
+		if let Some(ref rt) = *runtime {
+			rt.executor().spawn(future);
+		}
+
+		rx
+	}
+}
+
+enum RequestState {
+	/// The request is being built, nothing has been sent yet.
+	NotDispatched(HttpRequest),
+	/// The request has been sent, waiting for the response.
+	Dispatched(mpsc::Receiver<Result<HttpResponse, String>>),
+	/// The response has been received and its body is being read.
+	Response {
+		response: HttpResponse,
+		read: usize,
+	},
+}
+
+/// HTTP requests of a single offchain worker run.
+pub(crate) struct HttpApi {
+	transport: Arc<dyn HttpTransport>,
+	clock: Clock,
+	next_id: u16,
+	requests: BTreeMap<HttpRequestId, RequestState>,
+}
+
+impl HttpApi {
+	/// Create new HTTP API using given transport, with deadlines relative to `clock`.
+	pub fn new(transport: Arc<dyn HttpTransport>, clock: Clock) -> Self {
+		HttpApi {
+			transport,
+			clock,
+			next_id: 0,
+			requests: BTreeMap::new(),
+		}
+	}
+
+	/// Start a new request.
+	pub fn request_start(&mut self, method: &str, uri: &str) -> Result<HttpRequestId, ()> {
+		if method.is_empty() || uri.parse::<hyper::Uri>().is_err() {
+			return Err(());
+		}
+
+		// find a free id, there are at most `u16::max_value() + 1` requests in flight.
+		let mut id = HttpRequestId(self.next_id);
+		let mut attempts = 0u32;
+		while self.requests.contains_key(&id) {
+			attempts += 1;
+			if attempts > u32::from(u16::max_value()) {
+				return Err(());
+			}
+			id = HttpRequestId(id.0.wrapping_add(1));
+		}
+		self.next_id = id.0.wrapping_add(1);
+
+		self.requests.insert(id, RequestState::NotDispatched(HttpRequest {
+			method: method.to_owned(),
+			uri: uri.to_owned(),
+			headers: Vec::new(),
+			body: Vec::new(),
+		}));
+
+		Ok(id)
+	}
+
+	/// Add a header to a request which hasn't been dispatched yet.
+	pub fn request_add_header(&mut self, id: HttpRequestId, name: &str, value: &str) -> Result<(), ()> {
+		match self.requests.get_mut(&id) {
+			Some(RequestState::NotDispatched(request)) => {
+				request.headers.push((name.to_owned(), value.to_owned()));
+				Ok(())
+			},
+			_ => Err(()),
+		}
+	}
+
+	/// Append a chunk to the body of the request, an empty chunk dispatches the request.
+	pub fn request_write_body(
+		&mut self,
+		id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		if deadline.map_or(false, |deadline| self.time_until(deadline) == Duration::from_millis(0)) {
+			return Err(HttpError::DeadlineReached);
+		}
+
+		match self.requests.get_mut(&id) {
+			Some(RequestState::NotDispatched(request)) => if !chunk.is_empty() {
+				request.body.extend_from_slice(chunk);
+				return Ok(());
+			},
+			_ => return Err(HttpError::Invalid),
+		}
+
+		self.dispatch(id);
+		Ok(())
+	}
+
+	/// Wait for the responses to given requests.
+	///
+	/// Requests which are still being built are dispatched first. Requests whose response
+	/// doesn't arrive before the deadline are destroyed.
+	pub fn response_wait(&mut self, ids: &[HttpRequestId], deadline: Option<Timestamp>) -> Vec<HttpRequestStatus> {
+		for id in ids {
+			if let Some(RequestState::NotDispatched(_)) = self.requests.get(id) {
+				self.dispatch(*id);
+			}
+		}
+
+		ids.iter().map(|id| self.wait_one(*id, deadline)).collect()
+	}
+
+	/// Headers of a received response, empty if the response is not available.
+	pub fn response_headers(&mut self, id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		match self.requests.get(&id) {
+			Some(RequestState::Response { response, .. }) => response.headers.clone(),
+			_ => Vec::new(),
+		}
+	}
+
+	/// Read a chunk of the response body.
+	///
+	/// Once the body is fully read the request is destroyed and `0` is returned.
+	pub fn response_read_body(
+		&mut self,
+		id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		match self.response_wait(&[id], deadline)[0] {
+			HttpRequestStatus::Finished(_) => {},
+			HttpRequestStatus::DeadlineReached => return Err(HttpError::DeadlineReached),
+			HttpRequestStatus::IoError => return Err(HttpError::IoError),
+			HttpRequestStatus::Invalid => return Err(HttpError::Invalid),
+		}
+
+		let written = match self.requests.get_mut(&id) {
+			Some(RequestState::Response { response, read }) => {
+				let remaining = &response.body[*read..];
+				let len = ::std::cmp::min(remaining.len(), buffer.len());
+				buffer[..len].copy_from_slice(&remaining[..len]);
+				*read += len;
+				len
+			},
+			_ => return Err(HttpError::Invalid),
+		};
+
+		if written == 0 {
+			self.requests.remove(&id);
+		}
+
+		Ok(written)
+	}
+
+	/// Time left until given deadline, zero if it has already passed.
+	fn time_until(&self, deadline: Timestamp) -> Duration {
+		Duration::from_millis((self.clock)().millis_until(&deadline))
+	}
+
+	fn dispatch(&mut self, id: HttpRequestId) {
+		if let Some(RequestState::NotDispatched(request)) = self.requests.remove(&id) {
+			let receiver = self.transport.dispatch(request);
+			self.requests.insert(id, RequestState::Dispatched(receiver));
+		}
+	}
+
+	fn wait_one(&mut self, id: HttpRequestId, deadline: Option<Timestamp>) -> HttpRequestStatus {
+		let result = match self.requests.get(&id) {
+			Some(RequestState::Response { response, .. }) => return HttpRequestStatus::Finished(response.status),
+			Some(RequestState::Dispatched(receiver)) => match deadline {
+				Some(deadline) => receiver.recv_timeout(self.time_until(deadline)),
+				None => receiver.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+			},
+			_ => return HttpRequestStatus::Invalid,
+		};
+
+		match result {
+			Ok(Ok(response)) => {
+				let status = HttpRequestStatus::Finished(response.status);
+				self.requests.insert(id, RequestState::Response { response, read: 0 });
+				status
+			},
+			Err(mpsc::RecvTimeoutError::Timeout) => {
+				self.requests.remove(&id);
+				HttpRequestStatus::DeadlineReached
+			},
+			Ok(Err(e)) => {
+				warn!("Offchain HTTP request {:?} failed: {}", id, e);
+				self.requests.remove(&id);
+				HttpRequestStatus::IoError
+			},
+			Err(mpsc::RecvTimeoutError::Disconnected) => {
+				self.requests.remove(&id);
+				HttpRequestStatus::IoError
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::TestTransport;
+
+	fn http_api(transport: TestTransport) -> HttpApi {
+		HttpApi::new(Arc::new(transport), Arc::new(crate::api::timestamp::now))
+	}
+
+	fn echo_transport() -> TestTransport {
+		TestTransport::new(|request| Ok(HttpResponse {
+			status: 200,
+			headers: request.headers.iter()
+				.map(|(name, value)| (name.as_bytes().to_vec(), value.as_bytes().to_vec()))
+				.collect(),
+			body: request.body,
+		}))
+	}
+
+	#[test]
+	fn should_send_request_and_read_response() {
+		// given
+		let mut api = http_api(echo_transport());
+		let id = api.request_start("POST", "http://localhost:1234/price").unwrap();
+		api.request_add_header(id, "X-Auth", "secret").unwrap();
+		api.request_write_body(id, b"hello ", None).unwrap();
+		api.request_write_body(id, b"world", None).unwrap();
+		api.request_write_body(id, &[], None).unwrap();
+
+		// when
+		let status = api.response_wait(&[id], None);
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::Finished(200)]);
+		assert_eq!(api.response_headers(id), vec![(b"X-Auth".to_vec(), b"secret".to_vec())]);
+		let mut buffer = [0u8; 8];
+		assert_eq!(api.response_read_body(id, &mut buffer, None), Ok(8));
+		assert_eq!(&buffer, b"hello wo");
+		assert_eq!(api.response_read_body(id, &mut buffer, None), Ok(3));
+		assert_eq!(&buffer[..3], b"rld");
+		assert_eq!(api.response_read_body(id, &mut buffer, None), Ok(0));
+		assert_eq!(api.response_read_body(id, &mut buffer, None), Err(HttpError::Invalid));
+	}
+
+	#[test]
+	fn should_dispatch_unfinished_requests_on_wait() {
+		// given
+		let transport = echo_transport();
+		let mut api = http_api(transport.clone());
+		let id = api.request_start("GET", "http://localhost:1234/").unwrap();
+
+		// when
+		let status = api.response_wait(&[id, HttpRequestId(id.0 + 1)], None);
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::Finished(200), HttpRequestStatus::Invalid]);
+		assert_eq!(transport.requests().len(), 1);
+		assert_eq!(api.request_add_header(id, "X-Too-Late", "1"), Err(()));
+		assert_eq!(api.request_write_body(id, b"late", None), Err(HttpError::Invalid));
+	}
+
+	#[test]
+	fn should_reject_invalid_requests() {
+		let mut api = http_api(echo_transport());
+
+		assert_eq!(api.request_start("", "http://localhost:1234/"), Err(()));
+		assert_eq!(api.request_start("GET", "not a uri"), Err(()));
+		assert_eq!(api.request_add_header(HttpRequestId(5), "X-Auth", "secret"), Err(()));
+		assert_eq!(api.response_headers(HttpRequestId(5)), vec![]);
+	}
+
+	#[test]
+	fn should_report_io_errors_and_destroy_the_request() {
+		// given
+		let mut api = http_api(TestTransport::new(|_| Err("connection refused".into())));
+		let id = api.request_start("GET", "http://localhost:1234/").unwrap();
+
+		// when
+		let status = api.response_wait(&[id], None);
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::IoError]);
+		assert_eq!(api.response_wait(&[id], None), vec![HttpRequestStatus::Invalid]);
+	}
+
+	#[test]
+	fn should_destroy_requests_reaching_their_deadline() {
+		// given
+		let transport = TestTransport::pending();
+		let mut api = http_api(transport.clone());
+		let first = api.request_start("GET", "http://localhost:1234/").unwrap();
+		let second = api.request_start("GET", "http://localhost:1234/").unwrap();
+		let deadline = Timestamp::from_unix_millis(0);
+
+		// when
+		let status = api.response_wait(&[first], Some(deadline));
+		let mut buffer = [0u8; 8];
+		let read = api.response_read_body(second, &mut buffer, Some(deadline));
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::DeadlineReached]);
+		assert_eq!(read, Err(HttpError::DeadlineReached));
+
+		// when
+		transport.respond(0, Ok(HttpResponse { status: 404, ..Default::default() }));
+
+		// then
+		assert_eq!(
+			api.response_wait(&[first, second], None),
+			vec![HttpRequestStatus::Invalid, HttpRequestStatus::Invalid],
+		);
+	}
+}
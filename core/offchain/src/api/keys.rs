@@ -0,0 +1,167 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Access to the keys of the node from the offchain workers.
+
+use std::sync::Arc;
+
+use keystore::{Store, StoreKey};
+use log::warn;
+use primitives::{ed25519, sr25519, Pair, offchain::{CryptoKind, SIGNING_CONTEXT}};
+
+/// Provides the local authority keys to the offchain workers.
+pub trait KeyProvider: Send + Sync {
+	/// Raw public keys of given crypto kind.
+	fn public_keys(&self, kind: CryptoKind) -> Vec<Vec<u8>>;
+
+	/// Sign `msg` with the key matching the raw `public` key.
+	///
+	/// Returns `None` if the key is not available.
+	fn sign(&self, kind: CryptoKind, public: &[u8], msg: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// The ed25519 key available is the authority key of the node, sr25519 keys are only used
+/// by the offchain workers and all of them are available.
+impl KeyProvider for Store {
+	fn public_keys(&self, kind: CryptoKind) -> Vec<Vec<u8>> {
+		let keys = match kind {
+			CryptoKind::Ed25519 => self.authority_key()
+				.map(|key| key.into_iter().map(|public| public.0.to_vec()).collect()),
+			CryptoKind::Sr25519 => raw_public_keys::<sr25519::Pair>(self),
+		};
+
+		keys.unwrap_or_else(|e| {
+			warn!("Unable to read the keystore contents: {:?}", e);
+			Vec::new()
+		})
+	}
+
+	fn sign(&self, kind: CryptoKind, public: &[u8], msg: &[u8]) -> Option<Vec<u8>> {
+		if !self.public_keys(kind).iter().any(|key| &key[..] == public) {
+			return None;
+		}
+
+		match kind {
+			CryptoKind::Ed25519 => load::<ed25519::Pair>(self, public)
+				.map(|pair| pair.sign(msg).0.to_vec()),
+			CryptoKind::Sr25519 => load::<sr25519::Pair>(self, public)
+				.map(|pair| pair.sign(msg).0.to_vec()),
+		}
+	}
+}
+
+fn raw_public_keys<P: StoreKey>(store: &Store) -> keystore::Result<Vec<Vec<u8>>> {
+	Ok(store.contents_by_type::<P>()?
+		.iter()
+		.map(|public| P::public_to_raw(public).to_vec())
+		.collect())
+}
+
+fn load<P: StoreKey>(store: &Store, public: &[u8]) -> Option<P> {
+	if public.len() != 32 {
+		return None;
+	}
+	let mut raw = [0u8; 32];
+	raw.copy_from_slice(public);
+
+	store.load_by_type::<P>(&P::public_from_raw(raw)).ok()
+}
+
+/// Key-related functions of a single offchain worker run.
+#[derive(Clone, Default)]
+pub(crate) struct KeysApi {
+	provider: Option<Arc<dyn KeyProvider>>,
+}
+
+impl KeysApi {
+	/// Create new keys API, without a provider no keys are available.
+	pub fn new(provider: Option<Arc<dyn KeyProvider>>) -> Self {
+		KeysApi { provider }
+	}
+
+	/// Raw public keys of the local authority keys of given kind.
+	pub fn authority_pubkeys(&self, kind: CryptoKind) -> Vec<Vec<u8>> {
+		self.provider.as_ref()
+			.map(|provider| provider.public_keys(kind))
+			.unwrap_or_default()
+	}
+
+	/// Sign `SIGNING_CONTEXT` followed by `data` with the local authority key matching `public`.
+	pub fn sign(&self, kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		self.provider.as_ref()
+			.and_then(|provider| provider.sign(kind, public, &signing_payload(data)))
+	}
+
+	/// Verify a signature of `SIGNING_CONTEXT` followed by `msg` made with `public`.
+	pub fn verify(&self, kind: CryptoKind, public: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+		let msg = signing_payload(msg);
+		match kind {
+			CryptoKind::Ed25519 => ed25519::Pair::verify_weak(signature, &msg, public),
+			CryptoKind::Sr25519 => sr25519::Pair::verify_weak(signature, &msg, public),
+		}
+	}
+}
+
+fn signing_payload(data: &[u8]) -> Vec<u8> {
+	let mut payload = Vec::with_capacity(SIGNING_CONTEXT.len() + data.len());
+	payload.extend_from_slice(SIGNING_CONTEXT);
+	payload.extend_from_slice(data);
+	payload
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempdir::TempDir;
+
+	#[test]
+	fn should_sign_with_keystore_keys() {
+		// given
+		let temp_dir = TempDir::new("offchain-keystore").unwrap();
+		let store = Store::open(temp_dir.path().to_owned(), "password".into()).unwrap();
+		let ed_key = store.generate().unwrap();
+		let other_ed_key = store.generate().unwrap();
+		let sr_key = store.generate_by_type::<sr25519::Pair>().unwrap();
+		store.set_authority_key(&ed_key.public()).unwrap();
+		let api = KeysApi::new(Some(Arc::new(store)));
+
+		// when
+		let ed_keys = api.authority_pubkeys(CryptoKind::Ed25519);
+		let sr_keys = api.authority_pubkeys(CryptoKind::Sr25519);
+
+		// then
+		assert_eq!(ed_keys, vec![ed_key.public().0.to_vec()]);
+		assert_eq!(sr_keys, vec![sr_key.public().0.to_vec()]);
+
+		let signature = api.sign(CryptoKind::Ed25519, &ed_keys[0], b"payload").unwrap();
+		assert!(api.verify(CryptoKind::Ed25519, &ed_keys[0], b"payload", &signature));
+		assert!(!api.verify(CryptoKind::Ed25519, &ed_keys[0], b"other", &signature));
+		assert!(!ed25519::Pair::verify_weak(&signature, b"payload", &ed_keys[0]));
+		assert_eq!(api.sign(CryptoKind::Ed25519, &other_ed_key.public().0, b"payload"), None);
+
+		let signature = api.sign(CryptoKind::Sr25519, &sr_keys[0], b"payload").unwrap();
+		assert!(api.verify(CryptoKind::Sr25519, &sr_keys[0], b"payload", &signature));
+		assert_eq!(api.sign(CryptoKind::Sr25519, &ed_keys[0], b"payload"), None);
+	}
+
+	#[test]
+	fn should_not_provide_keys_without_provider() {
+		let api = KeysApi::new(None);
+
+		assert!(api.authority_pubkeys(CryptoKind::Ed25519).is_empty());
+		assert_eq!(api.sign(CryptoKind::Ed25519, &[0u8; 32], b"payload"), None);
+	}
+}
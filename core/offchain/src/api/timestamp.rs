@@ -0,0 +1,56 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helper methods dedicated to timestamps.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use primitives::offchain::Timestamp;
+
+/// Returns the current time as a `Timestamp`.
+pub fn now() -> Timestamp {
+	let now = SystemTime::now();
+	let epoch_duration = now.duration_since(UNIX_EPOCH);
+	match epoch_duration {
+		Err(_) => {
+			// Current time is earlier than UNIX_EPOCH.
+			Timestamp::from_unix_millis(0)
+		},
+		Ok(d) => {
+			let duration = d.as_secs() * 1_000 + u64::from(d.subsec_millis());
+			Timestamp::from_unix_millis(duration)
+		},
+	}
+}
+
+/// Returns the time left until given deadline, zero if the deadline has already passed.
+pub fn time_until(deadline: Timestamp) -> Duration {
+	Duration::from_millis(now().millis_until(&deadline))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_compute_time_until_deadline() {
+		let now = now();
+
+		assert_eq!(time_until(Timestamp::from_unix_millis(0)), Duration::from_millis(0));
+		assert_eq!(time_until(now), Duration::from_millis(0));
+		assert!(time_until(now.add_millis(60_000)) > Duration::from_millis(50_000));
+	}
+}
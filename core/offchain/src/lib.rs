@@ -30,18 +30,25 @@
 //! 1. Challenge period for incorrect computations
 //! 2. Majority voting for results
 //! 3. etc
+//!
+//! Workers may also fetch data from external services with HTTP requests.
+//! The requests are performed by an `HttpTransport`, which defaults to a
+//! `hyper` client and can be replaced, for instance with
+//! `testing::TestTransport` in tests.
 
 #![warn(missing_docs)]
 
 use std::{
+	fmt,
 	marker::PhantomData,
-	sync::Arc,
+	sync::{Arc, atomic::{AtomicUsize, Ordering}},
+	thread,
 };
 
 use client::runtime_api::ApiExt;
 use inherents::pool::InherentsPool;
 use log::{debug, warn};
-use primitives::ExecutionContext;
+use primitives::{ExecutionContext, offchain::OffchainStorage};
 use runtime_primitives::{
 	generic::BlockId,
 	traits::{self, ProvideRuntimeApi},
@@ -50,60 +57,158 @@ use tokio::runtime::TaskExecutor;
 use transaction_pool::txpool::{Pool, ChainApi};
 
 mod api;
+pub mod testing;
 
+pub use api::http::{HttpRequest, HttpResponse, HttpTransport, HyperTransport};
+pub use api::keys::KeyProvider;
 pub use offchain_primitives::OffchainWorkerApi;
 
+/// Maximal number of offchain worker runs in progress at the same time.
+///
+/// Blocks imported while that many runs are in progress are skipped.
+const MAX_RUNNING_WORKERS: usize = 4;
+
 /// An offchain workers manager.
-#[derive(Debug)]
-pub struct OffchainWorkers<C, Block: traits::Block> {
+pub struct OffchainWorkers<C, S, Block: traits::Block> {
 	client: Arc<C>,
+	db: S,
+	http_transport: Arc<dyn HttpTransport>,
+	key_provider: Option<Arc<dyn KeyProvider>>,
 	inherents_pool: Arc<InherentsPool<<Block as traits::Block>::Extrinsic>>,
 	executor: TaskExecutor,
+	running: Arc<AtomicUsize>,
 	_block: PhantomData<Block>,
 }
 
-impl<C, Block: traits::Block> OffchainWorkers<C, Block> {
+impl<C, S, Block: traits::Block> fmt::Debug for OffchainWorkers<C, S, Block> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("OffchainWorkers")
+			.field("http_transport", &self.http_transport)
+			.field("has_keys", &self.key_provider.is_some())
+			.finish()
+	}
+}
+
+impl<C, S, Block: traits::Block> OffchainWorkers<C, S, Block> {
 	/// Creates new `OffchainWorkers`.
+	///
+	/// HTTP requests of the workers are performed with `HyperTransport`
+	/// and no keys are available to the workers.
 	pub fn new(
 		client: Arc<C>,
+		db: S,
 		inherents_pool: Arc<InherentsPool<<Block as traits::Block>::Extrinsic>>,
 		executor: TaskExecutor,
 	) -> Self {
 		Self {
 			client,
+			db,
+			http_transport: Arc::new(HyperTransport::new()),
+			key_provider: None,
 			inherents_pool,
 			executor,
+			running: Arc::new(AtomicUsize::new(0)),
 			_block: PhantomData,
 		}
 	}
+
+	/// Use given transport for HTTP requests of the workers.
+	pub fn with_http_transport(mut self, http_transport: Arc<dyn HttpTransport>) -> Self {
+		self.http_transport = http_transport;
+		self
+	}
+
+	/// Make the keys of given provider available to the workers for signing.
+	pub fn with_key_provider(mut self, key_provider: Arc<dyn KeyProvider>) -> Self {
+		self.key_provider = Some(key_provider);
+		self
+	}
 }
 
-impl<C, Block> OffchainWorkers<C, Block> where
+impl<C, S, Block> OffchainWorkers<C, S, Block> where
 	Block: traits::Block,
-	C: ProvideRuntimeApi,
+	S: OffchainStorage + 'static,
+	C: ProvideRuntimeApi + Send + Sync + 'static,
 	C::Api: OffchainWorkerApi<Block>,
 {
 	/// Start the offchain workers after given block.
+	///
+	/// The workers run on a dedicated thread, a worker waiting for HTTP responses or
+	/// sleeping never holds up the caller. Such waits end at the latest one minute after
+	/// the run started. At most `MAX_RUNNING_WORKERS` runs are in progress at the same
+	/// time, the block is skipped if that many are.
 	pub fn on_block_imported<A>(
 		&self,
 		number: &<Block::Header as traits::Header>::Number,
+		hash: Block::Hash,
 		pool: &Arc<Pool<A>>,
 	) where
 		A: ChainApi<Block=Block> + 'static,
 	{
-		let runtime = self.client.runtime_api();
-		let at = BlockId::number(*number);
-		let has_api = runtime.has_api::<OffchainWorkerApi<Block>>(&at);
-		debug!("Checking offchain workers at {:?}: {:?}", at, has_api);
-
-		if has_api.unwrap_or(false) {
-			let (api, runner) = api::Api::new(pool.clone(), self.inherents_pool.clone(), at.clone());
-			self.executor.spawn(runner.process());
-
-			debug!("Running offchain workers at {:?}", at);
-			let api = Box::new(api);
-			runtime.offchain_worker_with_context(&at, ExecutionContext::OffchainWorker(api), *number).unwrap();
+		let at = BlockId::hash(hash);
+		let running = match RunningWorker::start(&self.running) {
+			Some(running) => running,
+			None => {
+				debug!("Skipping offchain workers at {:?}, {} runs are in progress", at, MAX_RUNNING_WORKERS);
+				return;
+			},
+		};
+		let (api, runner) = api::Api::new(
+			pool.clone(),
+			self.inherents_pool.clone(),
+			self.db.clone(),
+			self.http_transport.clone(),
+			self.key_provider.clone(),
+			at.clone(),
+		);
+		self.executor.spawn(runner.process());
+
+		let client = self.client.clone();
+		let number = *number;
+		let spawned = thread::Builder::new()
+			.name(format!("offchain-worker-{:?}", number))
+			.spawn(move || {
+				let _running = running;
+				let runtime = client.runtime_api();
+				let has_api = runtime.has_api::<OffchainWorkerApi<Block>>(&at);
+				debug!("Checking offchain workers at {:?}: {:?}", at, has_api);
+
+				if has_api.unwrap_or(false) {
+					debug!("Running offchain workers at {:?}", at);
+					let api = Box::new(api);
+					let result = runtime.offchain_worker_with_context(
+						&at,
+						ExecutionContext::OffchainWorker(api),
+						number,
+					);
+					if let Err(e) = result {
+						warn!("Offchain workers failed at {:?}: {:?}", at, e);
+					}
+				}
+			});
+
+		if let Err(e) = spawned {
+			warn!("Unable to start the offchain workers thread: {:?}", e);
+		}
+	}
+}
+
+/// A worker run in progress, counted until it is dropped.
+struct RunningWorker(Arc<AtomicUsize>);
+
+impl RunningWorker {
+	fn start(running: &Arc<AtomicUsize>) -> Option<Self> {
+		if running.fetch_add(1, Ordering::SeqCst) >= MAX_RUNNING_WORKERS {
+			running.fetch_sub(1, Ordering::SeqCst);
+			return None;
 		}
+		Some(RunningWorker(running.clone()))
+	}
+}
+
+impl Drop for RunningWorker {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
 	}
 }
 
@@ -119,14 +224,29 @@ mod tests {
 		let runtime = tokio::runtime::Runtime::new().unwrap();
 		let client = Arc::new(test_client::new());
 		let pool = Arc::new(Pool::new(Default::default(), ::transaction_pool::ChainApi::new(client.clone())));
+		let db = primitives::offchain::InMemOffchainStorage::default();
 		let inherents = Arc::new(InherentsPool::default());
 
 		// when
-		let offchain = OffchainWorkers::new(client, inherents.clone(), runtime.executor());
-		offchain.on_block_imported(&0u64, &pool);
+		let genesis = client.info().unwrap().chain.genesis_hash;
+		let offchain = OffchainWorkers::new(client, db, inherents.clone(), runtime.executor());
+		offchain.on_block_imported(&0u64, genesis, &pool);
 
 		// then
 		runtime.shutdown_on_idle().wait().unwrap();
 		assert_eq!(inherents.drain().len(), 1);
 	}
+
+	#[test]
+	fn should_limit_the_number_of_running_workers() {
+		let running = Arc::new(AtomicUsize::new(0));
+		let mut workers: Vec<_> = (0..MAX_RUNNING_WORKERS)
+			.map(|_| RunningWorker::start(&running).unwrap())
+			.collect();
+
+		assert!(RunningWorker::start(&running).is_none());
+		workers.pop();
+		assert!(RunningWorker::start(&running).is_some());
+		assert_eq!(running.load(Ordering::SeqCst), MAX_RUNNING_WORKERS - 1);
+	}
 }
@@ -0,0 +1,442 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Utilities for testing offchain workers.
+//!
+//! `TestOffchainExt` can be passed to `TestExternalities::set_offchain_externalities`
+//! to run the offchain worker code of runtime modules without a node.
+
+use std::{
+	fmt,
+	sync::{Arc, Mutex, RwLock, mpsc},
+};
+
+use primitives::{
+	OffchainExt, ed25519, sr25519, Pair,
+	offchain::{
+		OffchainStorage, InMemOffchainStorage, STORAGE_PREFIX, CryptoKind,
+		HttpError, HttpRequestId, HttpRequestStatus, Timestamp,
+	},
+};
+
+use crate::api::{
+	http::{HttpApi, HttpRequest, HttpResponse, HttpTransport},
+	keys::{KeyProvider, KeysApi},
+};
+
+type Handler = Box<Fn(HttpRequest) -> Result<HttpResponse, String> + Send + Sync>;
+
+#[derive(Default)]
+struct Inner {
+	handler: Option<Handler>,
+	requests: Vec<HttpRequest>,
+	pending: Vec<mpsc::Sender<Result<HttpResponse, String>>>,
+}
+
+/// In-process HTTP transport.
+///
+/// Either answers every request right away with a handler function,
+/// or keeps the requests pending until they are answered with `respond`.
+#[derive(Clone, Default)]
+pub struct TestTransport {
+	inner: Arc<Mutex<Inner>>,
+}
+
+impl fmt::Debug for TestTransport {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("TestTransport")
+			.field("requests", &self.requests())
+			.finish()
+	}
+}
+
+impl TestTransport {
+	/// Create a transport answering all requests with given handler.
+	pub fn new<F>(handler: F) -> Self where
+		F: Fn(HttpRequest) -> Result<HttpResponse, String> + Send + Sync + 'static,
+	{
+		let transport = Self::default();
+		transport.lock().handler = Some(Box::new(handler));
+		transport
+	}
+
+	/// Create a transport which leaves requests pending until `respond` is called.
+	pub fn pending() -> Self {
+		Self::default()
+	}
+
+	/// All requests dispatched so far.
+	pub fn requests(&self) -> Vec<HttpRequest> {
+		self.lock().requests.clone()
+	}
+
+	/// Answer the pending request with given index (in the order of dispatching).
+	///
+	/// Panics if there is no such request.
+	pub fn respond(&self, index: usize, response: Result<HttpResponse, String>) {
+		let inner = self.lock();
+		let sender = inner.pending.get(index).expect("No pending request with given index");
+		let _ = sender.send(response);
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<Inner> {
+		self.inner.lock().expect("test transport lock is never poisoned; qed")
+	}
+}
+
+impl HttpTransport for TestTransport {
+	fn dispatch(&self, request: HttpRequest) -> mpsc::Receiver<Result<HttpResponse, String>> {
+		let (tx, rx) = mpsc::channel();
+		let mut inner = self.lock();
+		inner.requests.push(request.clone());
+
+		let response = inner.handler.as_ref().map(|handler| handler(request));
+		match response {
+			Some(response) => {
+				let _ = tx.send(response);
+			},
+			None => inner.pending.push(tx),
+		}
+
+		rx
+	}
+}
+
+/// In-memory keystore.
+///
+/// Keys are kept as seeds, so that tests can use well-known keys like `//Alice`.
+#[derive(Clone, Default)]
+pub struct InMemoryKeystore {
+	keys: Arc<RwLock<Vec<(CryptoKind, Vec<u8>, String)>>>,
+}
+
+impl fmt::Debug for InMemoryKeystore {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("InMemoryKeystore").finish()
+	}
+}
+
+impl InMemoryKeystore {
+	/// Add a key of given kind created from `seed` (a phrase, hex seed or derivation path).
+	///
+	/// Returns the raw public key, panics if the seed is invalid.
+	pub fn insert(&self, kind: CryptoKind, seed: &str) -> Vec<u8> {
+		let public = match kind {
+			CryptoKind::Ed25519 => ed25519::Pair::from_string(seed, None)
+				.expect("Invalid ed25519 seed")
+				.public().0.to_vec(),
+			CryptoKind::Sr25519 => sr25519::Pair::from_string(seed, None)
+				.expect("Invalid sr25519 seed")
+				.public().0.to_vec(),
+		};
+		self.keys.write().expect("keystore lock is never poisoned; qed")
+			.push((kind, public.clone(), seed.to_owned()));
+		public
+	}
+}
+
+impl KeyProvider for InMemoryKeystore {
+	fn public_keys(&self, kind: CryptoKind) -> Vec<Vec<u8>> {
+		self.keys.read().expect("keystore lock is never poisoned; qed")
+			.iter()
+			.filter(|(k, _, _)| *k == kind)
+			.map(|(_, public, _)| public.clone())
+			.collect()
+	}
+
+	fn sign(&self, kind: CryptoKind, public: &[u8], msg: &[u8]) -> Option<Vec<u8>> {
+		let keys = self.keys.read().expect("keystore lock is never poisoned; qed");
+		let seed = keys.iter()
+			.find(|(k, p, _)| *k == kind && &p[..] == public)
+			.map(|(_, _, seed)| seed)?;
+
+		match kind {
+			CryptoKind::Ed25519 => ed25519::Pair::from_string(seed, None).ok()
+				.map(|pair| pair.sign(msg).0.to_vec()),
+			CryptoKind::Sr25519 => sr25519::Pair::from_string(seed, None).ok()
+				.map(|pair| pair.sign(msg).0.to_vec()),
+		}
+	}
+}
+
+/// Offchain externalities for testing runtime modules.
+///
+/// Uses in-memory local storage, `TestTransport` for HTTP requests
+/// and `InMemoryKeystore` for the authority keys. Time is simulated: it only
+/// moves with `set_timestamp` and `sleep_until`, and the random seed is fixed
+/// with `set_random_seed`. Clones share the state, so a clone can be kept
+/// to inspect the effects of the worker.
+#[derive(Clone)]
+pub struct TestOffchainExt {
+	storage: InMemOffchainStorage,
+	transport: TestTransport,
+	http: Arc<Mutex<HttpApi>>,
+	keystore: InMemoryKeystore,
+	keys: KeysApi,
+	transactions: Arc<Mutex<Vec<Vec<u8>>>>,
+	timestamp: Arc<Mutex<Timestamp>>,
+	random_seed: Arc<Mutex<[u8; 32]>>,
+}
+
+impl Default for TestOffchainExt {
+	fn default() -> Self {
+		Self::new(TestTransport::pending())
+	}
+}
+
+impl TestOffchainExt {
+	/// Create new offchain externalities performing HTTP requests with given transport.
+	pub fn new(transport: TestTransport) -> Self {
+		let keystore = InMemoryKeystore::default();
+		let timestamp: Arc<Mutex<Timestamp>> = Default::default();
+		// HTTP deadlines are measured against the simulated time.
+		let clock = {
+			let timestamp = timestamp.clone();
+			Arc::new(move || *timestamp.lock().expect("timestamp lock is never poisoned; qed"))
+		};
+		TestOffchainExt {
+			storage: Default::default(),
+			http: Arc::new(Mutex::new(HttpApi::new(Arc::new(transport.clone()), clock))),
+			transport,
+			keys: KeysApi::new(Some(Arc::new(keystore.clone()))),
+			keystore,
+			transactions: Default::default(),
+			timestamp,
+			random_seed: Default::default(),
+		}
+	}
+
+	/// The HTTP transport of the externalities.
+	pub fn transport(&self) -> &TestTransport {
+		&self.transport
+	}
+
+	/// The keystore of the externalities.
+	pub fn keystore(&self) -> &InMemoryKeystore {
+		&self.keystore
+	}
+
+	/// Value in the local storage under given key.
+	pub fn local_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.storage.get(STORAGE_PREFIX, key)
+	}
+
+	/// Extrinsics submitted so far.
+	pub fn transactions(&self) -> Vec<Vec<u8>> {
+		self.transactions.lock().expect("transactions lock is never poisoned; qed").clone()
+	}
+
+	/// Set the current time of the externalities.
+	pub fn set_timestamp(&self, timestamp: Timestamp) {
+		*self.timestamp.lock().expect("timestamp lock is never poisoned; qed") = timestamp;
+	}
+
+	/// Set the seed returned by `random_seed`.
+	pub fn set_random_seed(&self, seed: [u8; 32]) {
+		*self.random_seed.lock().expect("random seed lock is never poisoned; qed") = seed;
+	}
+
+	fn http(&self) -> std::sync::MutexGuard<HttpApi> {
+		self.http.lock().expect("http lock is never poisoned; qed")
+	}
+}
+
+impl fmt::Debug for TestOffchainExt {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("TestOffchainExt")
+			.field("transport", &self.transport)
+			.field("transactions", &self.transactions())
+			.finish()
+	}
+}
+
+impl OffchainExt for TestOffchainExt {
+	fn submit_extrinsic(&mut self, extrinsic: Vec<u8>) {
+		self.transactions.lock().expect("transactions lock is never poisoned; qed").push(extrinsic);
+	}
+
+	fn local_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		self.storage.set(STORAGE_PREFIX, key, value)
+	}
+
+	fn local_storage_compare_and_set(&mut self, key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool {
+		self.storage.compare_and_set(STORAGE_PREFIX, key, old_value, new_value)
+	}
+
+	fn local_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		self.storage.get(STORAGE_PREFIX, key)
+	}
+
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		_meta: &[u8]
+	) -> Result<HttpRequestId, ()> {
+		self.http().request_start(method, uri)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str
+	) -> Result<(), ()> {
+		self.http().request_add_header(request_id, name, value)
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), HttpError> {
+		self.http().request_write_body(request_id, chunk, deadline)
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>
+	) -> Vec<HttpRequestStatus> {
+		self.http().response_wait(ids, deadline)
+	}
+
+	fn http_response_headers(
+		&mut self,
+		request_id: HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.http().response_headers(request_id)
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, HttpError> {
+		self.http().response_read_body(request_id, buffer, deadline)
+	}
+
+	fn authority_pubkeys(&mut self, kind: CryptoKind) -> Vec<Vec<u8>> {
+		self.keys.authority_pubkeys(kind)
+	}
+
+	fn sign(&mut self, kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		self.keys.sign(kind, public, data)
+	}
+
+	fn verify(
+		&mut self,
+		kind: CryptoKind,
+		public: &[u8],
+		msg: &[u8],
+		signature: &[u8]
+	) -> bool {
+		self.keys.verify(kind, public, msg, signature)
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		*self.timestamp.lock().expect("timestamp lock is never poisoned; qed")
+	}
+
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		let mut timestamp = self.timestamp.lock().expect("timestamp lock is never poisoned; qed");
+		if *timestamp < deadline {
+			*timestamp = deadline;
+		}
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		*self.random_seed.lock().expect("random seed lock is never poisoned; qed")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_sign_with_in_memory_keys() {
+		// given
+		let mut ext = TestOffchainExt::default();
+		let alice = ext.keystore().insert(CryptoKind::Sr25519, "//Alice");
+		let bob = ext.keystore().insert(CryptoKind::Ed25519, "//Bob");
+
+		// when
+		let signature = ext.sign(CryptoKind::Sr25519, &alice, b"payload").unwrap();
+
+		// then
+		assert_eq!(ext.authority_pubkeys(CryptoKind::Sr25519), vec![alice.clone()]);
+		assert_eq!(ext.authority_pubkeys(CryptoKind::Ed25519), vec![bob.clone()]);
+		assert!(ext.verify(CryptoKind::Sr25519, &alice, b"payload", &signature));
+		assert!(!ext.verify(CryptoKind::Sr25519, &alice, b"payload2", &signature));
+		assert_eq!(ext.sign(CryptoKind::Ed25519, &alice, b"payload"), None);
+	}
+
+	#[test]
+	fn should_share_state_between_clones() {
+		// given
+		let ext = TestOffchainExt::default();
+		let mut worker = ext.clone();
+
+		// when
+		worker.submit_extrinsic(vec![1, 2, 3]);
+		worker.local_storage_set(b"key", b"value");
+
+		// then
+		assert_eq!(ext.transactions(), vec![vec![1, 2, 3]]);
+		assert_eq!(ext.local_storage(b"key"), Some(b"value".to_vec()));
+	}
+
+	#[test]
+	fn should_simulate_time_and_randomness() {
+		// given
+		let mut ext = TestOffchainExt::default();
+		ext.set_timestamp(Timestamp::from_unix_millis(1_000));
+		ext.set_random_seed([7; 32]);
+
+		// when
+		ext.sleep_until(Timestamp::from_unix_millis(5_000));
+		ext.sleep_until(Timestamp::from_unix_millis(2_000));
+
+		// then
+		assert_eq!(ext.timestamp(), Timestamp::from_unix_millis(5_000));
+		assert_eq!(ext.random_seed(), [7; 32]);
+	}
+
+	#[test]
+	fn should_measure_http_deadlines_against_simulated_time() {
+		// given
+		let mut ext = TestOffchainExt::default();
+		ext.set_timestamp(Timestamp::from_unix_millis(1_000));
+		let id = ext.http_request_start("GET", "http://localhost/price", &[]).unwrap();
+
+		// when
+		let started = std::time::Instant::now();
+		let status = ext.http_response_wait(&[id], Some(Timestamp::from_unix_millis(1_050)));
+
+		// then
+		assert_eq!(status, vec![HttpRequestStatus::DeadlineReached]);
+		assert!(started.elapsed() >= std::time::Duration::from_millis(50));
+		ext.set_timestamp(Timestamp::from_unix_millis(2_000));
+		let id = ext.http_request_start("GET", "http://localhost/price", &[]).unwrap();
+		assert_eq!(
+			ext.http_response_wait(&[id], Some(Timestamp::from_unix_millis(1_050))),
+			vec![HttpRequestStatus::DeadlineReached],
+		);
+	}
+}
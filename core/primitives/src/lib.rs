@@ -58,6 +58,7 @@ pub mod hash;
 mod hasher;
 pub mod sandbox;
 pub mod storage;
+pub mod offchain;
 pub mod uint;
 mod changes_trie;
 
@@ -97,11 +98,228 @@ pub trait OffchainExt {
 	/// The extrinsic will either go to the pool (signed)
 	/// or to the next produced block (inherent).
 	fn submit_extrinsic(&mut self, extrinsic: Vec<u8>);
+
+	/// Sets a value in the local storage.
+	///
+	/// Note this storage is not part of the consensus, it's only accessible by
+	/// offchain worker tasks running on the same machine. It IS persisted between runs.
+	fn local_storage_set(&mut self, key: &[u8], value: &[u8]);
+
+	/// Sets a value in the local storage if it matches current value.
+	///
+	/// Since multiple offchain workers may be running concurrently, to prevent
+	/// data races use CAS to coordinate between them.
+	///
+	/// `None` as `old_value` means that the key must not be present in the storage.
+	/// Returns `true` if the value has been set, `false` otherwise.
+	fn local_storage_compare_and_set(&mut self, key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool;
+
+	/// Gets a value from the local storage.
+	///
+	/// If the value does not exist in the storage `None` will be returned.
+	fn local_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Initiates a http request given HTTP verb and the URL.
+	///
+	/// Meta is a future-reserved field containing additional, parity-codec encoded parameters.
+	/// Returns the id of newly started request.
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		meta: &[u8]
+	) -> Result<offchain::HttpRequestId, ()>;
+
+	/// Append header to the request.
+	fn http_request_add_header(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		name: &str,
+		value: &str
+	) -> Result<(), ()>;
+
+	/// Write a chunk of request body.
+	///
+	/// Writing an empty chunks finalises the request.
+	/// Passing `None` as deadline blocks forever.
+	///
+	/// Returns an error in case deadline is reached or the chunk couldn't be written.
+	fn http_request_write_body(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<offchain::Timestamp>
+	) -> Result<(), offchain::HttpError>;
+
+	/// Block and wait for the responses for given requests.
+	///
+	/// Returns a vector of request statuses (the len is the same as ids).
+	/// Note that if deadline is not provided the method will block indefinitely,
+	/// otherwise unready responses will produce `DeadlineReached` status.
+	///
+	/// Passing `None` as deadline blocks forever.
+	fn http_response_wait(
+		&mut self,
+		ids: &[offchain::HttpRequestId],
+		deadline: Option<offchain::Timestamp>
+	) -> Vec<offchain::HttpRequestStatus>;
+
+	/// Read all response headers.
+	///
+	/// Returns a vector of pairs `(HeaderKey, HeaderValue)`.
+	/// NOTE response headers have to be read before response body.
+	fn http_response_headers(
+		&mut self,
+		request_id: offchain::HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)>;
+
+	/// Read a chunk of body response to given buffer.
+	///
+	/// Returns the number of bytes written or an error in case a deadline
+	/// is reached or server closed the connection.
+	/// If `0` is returned it means that the response has been fully consumed
+	/// and the `request_id` is now invalid.
+	/// NOTE this implies that response headers must be read before draining the body.
+	/// Passing `None` as a deadline blocks forever.
+	fn http_response_read_body(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<offchain::Timestamp>
+	) -> Result<usize, offchain::HttpError>;
+
+	/// Returns the raw public keys of the local authority keys of given crypto kind.
+	///
+	/// The list is empty if the node is not an authority.
+	fn authority_pubkeys(&mut self, kind: offchain::CryptoKind) -> Vec<Vec<u8>>;
+
+	/// Sign a message with the local authority key matching given public key.
+	///
+	/// The signature covers `offchain::SIGNING_CONTEXT` followed by `data`.
+	/// Returns `None` if the key is not available.
+	fn sign(&mut self, kind: offchain::CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>>;
+
+	/// Verifies a signature of a message made with given public key.
+	///
+	/// Like with `sign`, the message is prefixed with `offchain::SIGNING_CONTEXT`.
+	/// Returns `true` if the signature is valid, `false` otherwise.
+	fn verify(
+		&mut self,
+		kind: offchain::CryptoKind,
+		public: &[u8],
+		msg: &[u8],
+		signature: &[u8]
+	) -> bool;
+
+	/// Returns current UNIX timestamp (in millis)
+	fn timestamp(&mut self) -> offchain::Timestamp;
+
+	/// Pause the execution until `deadline` is reached.
+	fn sleep_until(&mut self, deadline: offchain::Timestamp);
+
+	/// Returns a random seed.
+	///
+	/// This is a truly random non deterministic seed generated by host environment.
+	/// Obviously fine in the off-chain worker context.
+	fn random_seed(&mut self) -> [u8; 32];
 }
 impl<T: OffchainExt + ?Sized> OffchainExt for Box<T> {
 	fn submit_extrinsic(&mut self, ex: Vec<u8>) {
 		(&mut **self).submit_extrinsic(ex)
 	}
+
+	fn local_storage_set(&mut self, key: &[u8], value: &[u8]) {
+		(&mut **self).local_storage_set(key, value)
+	}
+
+	fn local_storage_compare_and_set(&mut self, key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool {
+		(&mut **self).local_storage_compare_and_set(key, old_value, new_value)
+	}
+
+	fn local_storage_get(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		(&mut **self).local_storage_get(key)
+	}
+
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		meta: &[u8]
+	) -> Result<offchain::HttpRequestId, ()> {
+		(&mut **self).http_request_start(method, uri, meta)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		name: &str,
+		value: &str
+	) -> Result<(), ()> {
+		(&mut **self).http_request_add_header(request_id, name, value)
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<offchain::Timestamp>
+	) -> Result<(), offchain::HttpError> {
+		(&mut **self).http_request_write_body(request_id, chunk, deadline)
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[offchain::HttpRequestId],
+		deadline: Option<offchain::Timestamp>
+	) -> Vec<offchain::HttpRequestStatus> {
+		(&mut **self).http_response_wait(ids, deadline)
+	}
+
+	fn http_response_headers(
+		&mut self,
+		request_id: offchain::HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		(&mut **self).http_response_headers(request_id)
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: offchain::HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<offchain::Timestamp>
+	) -> Result<usize, offchain::HttpError> {
+		(&mut **self).http_response_read_body(request_id, buffer, deadline)
+	}
+
+	fn authority_pubkeys(&mut self, kind: offchain::CryptoKind) -> Vec<Vec<u8>> {
+		(&mut **self).authority_pubkeys(kind)
+	}
+
+	fn sign(&mut self, kind: offchain::CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		(&mut **self).sign(kind, public, data)
+	}
+
+	fn verify(
+		&mut self,
+		kind: offchain::CryptoKind,
+		public: &[u8],
+		msg: &[u8],
+		signature: &[u8]
+	) -> bool {
+		(&mut **self).verify(kind, public, msg, signature)
+	}
+
+	fn timestamp(&mut self) -> offchain::Timestamp {
+		(&mut **self).timestamp()
+	}
+
+	fn sleep_until(&mut self, deadline: offchain::Timestamp) {
+		(&mut **self).sleep_until(deadline)
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		(&mut **self).random_seed()
+	}
 }
 
 /// Hex-serialized shim for `Vec<u8>`.
@@ -0,0 +1,295 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offchain workers types.
+
+use rstd::prelude::Vec;
+use parity_codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+
+/// Prefix of the keys written to the offchain storage by the runtime local storage API.
+pub const STORAGE_PREFIX: &[u8] = b"storage";
+
+/// Node-local storage of the offchain workers.
+///
+/// The storage is not part of the consensus and is not fork-aware: every write is
+/// persisted immediately and visible to all offchain workers running on the node.
+#[cfg(feature = "std")]
+pub trait OffchainStorage: Clone + Send + Sync {
+	/// Persist a value in storage under given key and prefix.
+	fn set(&mut self, prefix: &[u8], key: &[u8], value: &[u8]);
+
+	/// Retrieve a value from storage under given key and prefix.
+	fn get(&self, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>>;
+
+	/// Replace the value in storage if given `old_value` matches the current one.
+	///
+	/// `None` as `old_value` means that the key must not be present in storage.
+	/// Returns `true` if the value has been set and `false` otherwise.
+	fn compare_and_set(
+		&mut self,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool;
+}
+
+/// In-memory storage for offchain workers.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct InMemOffchainStorage {
+	storage: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>,
+}
+
+#[cfg(feature = "std")]
+impl InMemOffchainStorage {
+	fn key(prefix: &[u8], key: &[u8]) -> Vec<u8> {
+		prefix.iter().chain(key).cloned().collect()
+	}
+}
+
+#[cfg(feature = "std")]
+impl OffchainStorage for InMemOffchainStorage {
+	fn set(&mut self, prefix: &[u8], key: &[u8], value: &[u8]) {
+		let key = Self::key(prefix, key);
+		self.storage.lock().expect("storage lock is never poisoned; qed").insert(key, value.to_vec());
+	}
+
+	fn get(&self, prefix: &[u8], key: &[u8]) -> Option<Vec<u8>> {
+		let key = Self::key(prefix, key);
+		self.storage.lock().expect("storage lock is never poisoned; qed").get(&key).cloned()
+	}
+
+	fn compare_and_set(
+		&mut self,
+		prefix: &[u8],
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		let key = Self::key(prefix, key);
+		let mut storage = self.storage.lock().expect("storage lock is never poisoned; qed");
+
+		let is_set = storage.get(&key).map(|v| &v[..]) == old_value;
+		if is_set {
+			storage.insert(key, new_value.to_vec());
+		}
+
+		is_set
+	}
+}
+
+/// Tag prepended to every message signed or verified on behalf of an offchain worker.
+///
+/// The workers sign with the keys of the node, the tag keeps their signatures from passing
+/// for consensus messages signed with the same keys, like block seals or GRANDPA votes.
+pub const SIGNING_CONTEXT: &[u8] = b"substrate-offchain-worker:";
+
+/// A type of supported crypto.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+#[repr(C)]
+pub enum CryptoKind {
+	/// SR25519 crypto (Schnorrkel)
+	Sr25519 = 1,
+	/// ED25519 crypto (Edwards)
+	Ed25519 = 2,
+}
+
+impl CryptoKind {
+	/// Decode a crypto kind from its numeric code.
+	pub fn from_code(code: u32) -> Option<Self> {
+		match code {
+			1 => Some(CryptoKind::Sr25519),
+			2 => Some(CryptoKind::Ed25519),
+			_ => None,
+		}
+	}
+}
+
+impl From<CryptoKind> for u32 {
+	fn from(kind: CryptoKind) -> Self {
+		kind as u32
+	}
+}
+
+/// Timestamp is a number of milliseconds since UNIX epoch.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+	/// Creates new `Timestamp` given unix timestamp in milliseconds.
+	pub fn from_unix_millis(millis: u64) -> Self {
+		Timestamp(millis)
+	}
+
+	/// Returns the number of milliseconds since UNIX epoch.
+	pub fn unix_millis(&self) -> u64 {
+		self.0
+	}
+
+	/// Increase the timestamp by given number of milliseconds.
+	pub fn add_millis(&self, millis: u64) -> Self {
+		Timestamp(self.0.saturating_add(millis))
+	}
+
+	/// Returns the number of milliseconds between `self` and a later `other` timestamp,
+	/// or `0` if `other` is not later than `self`.
+	pub fn millis_until(&self, other: &Timestamp) -> u64 {
+		other.0.saturating_sub(self.0)
+	}
+}
+
+/// Opaque type for offchain http requests.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Hash))]
+pub struct HttpRequestId(pub u16);
+
+/// An error enum returned by some http methods.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+#[repr(u8)]
+pub enum HttpError {
+	/// The requested action couldn't been completed within a deadline.
+	DeadlineReached = 1,
+	/// There was an IO Error while processing the request.
+	IoError = 2,
+	/// The ID of the request is invalid in this context.
+	Invalid = 3,
+}
+
+impl HttpError {
+	/// Decode an error from its numeric code.
+	pub fn from_code(code: u32) -> Option<Self> {
+		match code {
+			1 => Some(HttpError::DeadlineReached),
+			2 => Some(HttpError::IoError),
+			3 => Some(HttpError::Invalid),
+			_ => None,
+		}
+	}
+}
+
+impl From<HttpError> for u32 {
+	fn from(e: HttpError) -> Self {
+		e as u8 as u32
+	}
+}
+
+/// Status of the HTTP request
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum HttpRequestStatus {
+	/// Deadline was reached while we waited for this request to finish.
+	///
+	/// Note the deadline is controlled by the calling part, it not necessarily means
+	/// that the request has timed out.
+	DeadlineReached,
+	/// An error has occured during the request, for example a timeout or the
+	/// remote has closed our socket.
+	///
+	/// The request is now considered destroyed. To retry the request you need
+	/// to construct it again.
+	IoError,
+	/// The passed ID is invalid in this context.
+	Invalid,
+	/// The request has finished with given status code.
+	Finished(u16),
+}
+
+impl HttpRequestStatus {
+	/// Decode a status from its numeric code.
+	pub fn from_code(code: u32) -> Option<Self> {
+		match code {
+			0 => Some(HttpRequestStatus::Invalid),
+			10 => Some(HttpRequestStatus::DeadlineReached),
+			20 => Some(HttpRequestStatus::IoError),
+			100..=999 => Some(HttpRequestStatus::Finished(code as u16)),
+			_ => None,
+		}
+	}
+}
+
+impl From<HttpRequestStatus> for u32 {
+	fn from(status: HttpRequestStatus) -> Self {
+		match status {
+			HttpRequestStatus::Invalid => 0,
+			HttpRequestStatus::DeadlineReached => 10,
+			HttpRequestStatus::IoError => 20,
+			HttpRequestStatus::Finished(code) => u32::from(code),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_compare_and_set_in_memory() {
+		let mut storage = InMemOffchainStorage::default();
+		let prefix = STORAGE_PREFIX;
+		let key = b"key";
+
+		assert!(storage.compare_and_set(prefix, key, None, b"asd"));
+		assert!(!storage.compare_and_set(prefix, key, None, b"qwe"));
+		assert_eq!(storage.get(prefix, key), Some(b"asd".to_vec()));
+
+		assert!(!storage.compare_and_set(prefix, key, Some(b"qwe"), b"zxc"));
+		assert!(storage.compare_and_set(prefix, key, Some(b"asd"), b"zxc"));
+		assert_eq!(storage.get(prefix, key), Some(b"zxc".to_vec()));
+		assert_eq!(storage.get(b"other", key), None);
+	}
+
+	#[test]
+	fn http_status_codec() {
+		assert_eq!(HttpRequestStatus::from_code(0), Some(HttpRequestStatus::Invalid));
+		assert_eq!(HttpRequestStatus::from_code(10), Some(HttpRequestStatus::DeadlineReached));
+		assert_eq!(HttpRequestStatus::from_code(20), Some(HttpRequestStatus::IoError));
+		assert_eq!(HttpRequestStatus::from_code(200), Some(HttpRequestStatus::Finished(200)));
+		assert_eq!(HttpRequestStatus::from_code(1000), None);
+
+		for status in &[
+			HttpRequestStatus::Invalid,
+			HttpRequestStatus::DeadlineReached,
+			HttpRequestStatus::IoError,
+			HttpRequestStatus::Finished(404),
+		] {
+			assert_eq!(HttpRequestStatus::from_code(u32::from(*status)), Some(*status));
+		}
+	}
+
+	#[test]
+	fn crypto_kind_codec() {
+		for kind in &[CryptoKind::Sr25519, CryptoKind::Ed25519] {
+			assert_eq!(CryptoKind::from_code(u32::from(*kind)), Some(*kind));
+		}
+		assert_eq!(CryptoKind::from_code(0), None);
+	}
+
+	#[test]
+	fn http_error_codec() {
+		for error in &[HttpError::DeadlineReached, HttpError::IoError, HttpError::Invalid] {
+			assert_eq!(HttpError::from_code(u32::from(*error)), Some(*error));
+		}
+		assert_eq!(HttpError::from_code(0), None);
+	}
+}
@@ -18,7 +18,10 @@
 
 #[warn(missing_docs)]
 
+mod middleware;
+
 pub use substrate_rpc as apis;
+pub use middleware::{RpcMethods, RpcMiddleware, UNSAFE_METHODS, UNSAFE_METHOD_ERROR, is_unsafe};
 
 use std::io;
 use log::error;
@@ -28,16 +31,42 @@ use sr_primitives::{traits::{Block as BlockT, NumberFor}, generic::SignedBlock};
 const MAX_PAYLOAD: usize = 15 * 1024 * 1024;
 
 type Metadata = apis::metadata::Metadata;
-type RpcHandler = pubsub::PubSubHandler<Metadata>;
+/// Handler the RPC APIs are registered with.
+pub type RpcHandler = pubsub::PubSubHandler<Metadata, RpcMiddleware>;
 pub type HttpServer = http::Server;
 pub type WsServer = ws::Server;
 
+/// Custom RPC methods registered next to the core APIs.
+///
+/// Methods of the extensions are exposed on every server, unless they are listed
+/// by `unsafe_methods`.
+pub trait RpcExtension: Send + Sync {
+	/// Register the extension delegates with given handler.
+	fn augment(&self, io: &mut RpcHandler);
+
+	/// Methods of the extension which are denied like `UNSAFE_METHODS`.
+	fn unsafe_methods(&self) -> Vec<&'static str> {
+		Vec::new()
+	}
+}
+
+impl<F> RpcExtension for F where F: Fn(&mut RpcHandler) + Send + Sync {
+	fn augment(&self, io: &mut RpcHandler) {
+		self(io)
+	}
+}
+
 /// Construct rpc `IoHandler`
-pub fn rpc_handler<Block: BlockT, ExHash, S, C, A, Y>(
+///
+/// Calls to unsafe methods are handled according to given `middleware`.
+pub fn rpc_handler<Block: BlockT, ExHash, S, C, A, Y, O>(
 	state: S,
 	chain: C,
 	author: A,
 	system: Y,
+	offchain: Option<O>,
+	extensions: &[Box<dyn RpcExtension>],
+	middleware: RpcMiddleware,
 ) -> RpcHandler where
 	Block: BlockT + 'static,
 	ExHash: Send + Sync + 'static + sr_primitives::Serialize + sr_primitives::DeserializeOwned,
@@ -45,12 +74,21 @@ pub fn rpc_handler<Block: BlockT, ExHash, S, C, A, Y>(
 	C: apis::chain::ChainApi<NumberFor<Block>, Block::Hash, Block::Header, SignedBlock<Block>, Metadata=Metadata>,
 	A: apis::author::AuthorApi<ExHash, Block::Hash, Metadata=Metadata>,
 	Y: apis::system::SystemApi<Block::Hash, NumberFor<Block>>,
+	O: apis::offchain::OffchainApi,
 {
-	let mut io = pubsub::PubSubHandler::default();
+	let unsafe_methods = extensions.iter().flat_map(|extension| extension.unsafe_methods()).collect();
+	let middleware = middleware.with_unsafe_methods(unsafe_methods);
+	let mut io = pubsub::PubSubHandler::new(rpc::MetaIoHandler::with_middleware(middleware));
 	io.extend_with(state.to_delegate());
 	io.extend_with(chain.to_delegate());
 	io.extend_with(author.to_delegate());
 	io.extend_with(system.to_delegate());
+	if let Some(offchain) = offchain {
+		io.extend_with(offchain.to_delegate());
+	}
+	for extension in extensions {
+		extension.augment(&mut io);
+	}
 	io
 }
 
@@ -0,0 +1,265 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Classification of RPC methods and the middleware denying unsafe ones.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use rpc::futures::{Future, future::{self, Either}};
+use rpc::{Call, Error, ErrorCode, Failure, Metadata, Middleware, Output, Request, Response};
+
+/// Methods which must not be reachable by everyone.
+///
+/// They allow to alter the node's state, submit transactions on behalf of the node
+/// or reveal information about the node and its peers.
+pub const UNSAFE_METHODS: &[&str] = &[
+	"author_submitExtrinsic",
+	"author_submitAndWatchExtrinsic",
+	"author_insertKey",
+	"author_rotateKeys",
+	"author_removeExtrinsic",
+	"author_poolContent",
+	"state_traceBlock",
+	"state_diff",
+	"system_dryRun",
+	"system_peers",
+	"system_networkState",
+	"offchain_localStorageSet",
+	"offchain_localStorageGet",
+];
+
+/// Error code returned when an unsafe method is called on a server exposing safe methods only.
+pub const UNSAFE_METHOD_ERROR: i64 = 3001;
+
+/// Returns true if given RPC method is unsafe.
+pub fn is_unsafe(method: &str) -> bool {
+	UNSAFE_METHODS.contains(&method)
+}
+
+/// Which RPC methods to expose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcMethods {
+	/// Expose every method on local interfaces and only safe methods otherwise.
+	Auto,
+	/// Expose only safe methods.
+	Safe,
+	/// Expose every method.
+	Unsafe,
+}
+
+impl Default for RpcMethods {
+	fn default() -> Self {
+		RpcMethods::Auto
+	}
+}
+
+impl RpcMethods {
+	/// Returns true if unsafe methods must be denied on a server listening on given address.
+	pub fn deny_unsafe(&self, address: &SocketAddr) -> bool {
+		match self {
+			RpcMethods::Auto => !address.ip().is_loopback(),
+			RpcMethods::Safe => true,
+			RpcMethods::Unsafe => false,
+		}
+	}
+}
+
+/// Middleware denying calls to unsafe methods.
+#[derive(Debug, Clone, Default)]
+pub struct RpcMiddleware {
+	deny_unsafe: bool,
+	/// Unsafe methods of the RPC extensions, denied together with `UNSAFE_METHODS`.
+	extension_unsafe_methods: Arc<Vec<&'static str>>,
+}
+
+impl RpcMiddleware {
+	/// Create new middleware, denying unsafe methods if `deny_unsafe` is set.
+	pub fn new(deny_unsafe: bool) -> Self {
+		RpcMiddleware {
+			deny_unsafe,
+			extension_unsafe_methods: Default::default(),
+		}
+	}
+
+	/// Treat given methods as unsafe as well.
+	pub fn with_unsafe_methods(mut self, methods: Vec<&'static str>) -> Self {
+		self.extension_unsafe_methods = Arc::new(methods);
+		self
+	}
+
+	fn is_unsafe_call(&self, call: &Call) -> bool {
+		let method = match call {
+			Call::MethodCall(call) => &call.method,
+			Call::Notification(notification) => &notification.method,
+			_ => return false,
+		};
+		is_unsafe(method) || self.extension_unsafe_methods.iter().any(|m| *m == method.as_str())
+	}
+}
+
+impl<M: Metadata> Middleware<M> for RpcMiddleware {
+	type Future = Box<dyn Future<Item = Option<Response>, Error = ()> + Send>;
+
+	fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X> where
+		F: FnOnce(Request, M) -> X + Send,
+		X: Future<Item = Option<Response>, Error = ()> + Send + 'static,
+	{
+		let has_unsafe_calls = match &request {
+			Request::Single(call) => self.is_unsafe_call(call),
+			Request::Batch(calls) => calls.iter().any(|call| self.is_unsafe_call(call)),
+		};
+		if !self.deny_unsafe || !has_unsafe_calls {
+			return Either::B(next(request, meta));
+		}
+
+		match request {
+			Request::Single(call) => Either::A(Box::new(future::ok(deny(call).map(Response::Single)))),
+			Request::Batch(calls) => {
+				let (denied, allowed): (Vec<_>, Vec<_>) = calls.into_iter()
+					.partition(|call| self.is_unsafe_call(call));
+				let denied: Vec<_> = denied.into_iter().filter_map(deny).collect();
+				let response = if allowed.is_empty() {
+					Either::A(future::ok(None))
+				} else {
+					Either::B(next(Request::Batch(allowed), meta))
+				};
+
+				Either::A(Box::new(response.map(move |response| {
+					let mut outputs = match response {
+						Some(Response::Batch(outputs)) => outputs,
+						Some(Response::Single(output)) => vec![output],
+						None => Vec::new(),
+					};
+					outputs.extend(denied);
+
+					if outputs.is_empty() {
+						None
+					} else {
+						Some(Response::Batch(outputs))
+					}
+				})))
+			},
+		}
+	}
+}
+
+/// Returns the failure to respond with to a denied call, notifications get no response.
+fn deny(call: Call) -> Option<Output> {
+	match call {
+		Call::MethodCall(call) => Some(Output::Failure(Failure {
+			jsonrpc: call.jsonrpc,
+			error: Error {
+				code: ErrorCode::ServerError(UNSAFE_METHOD_ERROR),
+				message: "Method is unsafe and not allowed on this RPC server".into(),
+				data: None,
+			},
+			id: call.id,
+		})),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rpc::{MetaIoHandler, Value};
+
+	fn handler(deny_unsafe: bool) -> MetaIoHandler<(), RpcMiddleware> {
+		let middleware = RpcMiddleware::new(deny_unsafe).with_unsafe_methods(vec!["custom_reset"]);
+		let mut io = MetaIoHandler::with_middleware(middleware);
+		io.add_method("system_name", |_| Ok(Value::String("substrate".into())));
+		io.add_method("system_peers", |_| Ok(Value::Array(vec![])));
+		io.add_method("custom_reset", |_| Ok(Value::Bool(true)));
+		io
+	}
+
+	#[test]
+	fn should_classify_methods() {
+		assert!(is_unsafe("author_submitExtrinsic"));
+		assert!(is_unsafe("system_networkState"));
+		assert!(is_unsafe("author_poolContent"));
+		assert!(!is_unsafe("system_name"));
+		assert!(!is_unsafe("chain_getBlock"));
+	}
+
+	#[test]
+	fn should_deny_unsafe_depending_on_address() {
+		let local = "127.0.0.1:9933".parse().unwrap();
+		let external = "0.0.0.0:9933".parse().unwrap();
+
+		assert!(!RpcMethods::Auto.deny_unsafe(&local));
+		assert!(RpcMethods::Auto.deny_unsafe(&external));
+		assert!(RpcMethods::Safe.deny_unsafe(&local));
+		assert!(!RpcMethods::Unsafe.deny_unsafe(&external));
+	}
+
+	#[test]
+	fn should_allow_unsafe_methods() {
+		let io = handler(false);
+		let request = r#"{"jsonrpc":"2.0","method":"system_peers","id":1}"#;
+
+		assert_eq!(
+			io.handle_request_sync(request, ()),
+			Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.into())
+		);
+	}
+
+	#[test]
+	fn should_deny_unsafe_methods() {
+		let io = handler(true);
+		let request = r#"{"jsonrpc":"2.0","method":"system_peers","id":1}"#;
+
+		assert_eq!(
+			io.handle_request_sync(request, ()),
+			Some(r#"{"jsonrpc":"2.0","error":{"code":3001,"message":"Method is unsafe and not allowed on this RPC server"},"id":1}"#.into())
+		);
+		assert_eq!(
+			io.handle_request_sync(r#"{"jsonrpc":"2.0","method":"system_peers"}"#, ()),
+			None
+		);
+	}
+
+	#[test]
+	fn should_deny_unsafe_extension_methods() {
+		let request = r#"{"jsonrpc":"2.0","method":"custom_reset","id":1}"#;
+
+		assert_eq!(
+			handler(true).handle_request_sync(request, ()),
+			Some(r#"{"jsonrpc":"2.0","error":{"code":3001,"message":"Method is unsafe and not allowed on this RPC server"},"id":1}"#.into())
+		);
+		assert_eq!(
+			handler(false).handle_request_sync(request, ()),
+			Some(r#"{"jsonrpc":"2.0","result":true,"id":1}"#.into())
+		);
+	}
+
+	#[test]
+	fn should_deny_unsafe_methods_in_batch() {
+		let io = handler(true);
+		let request = r#"[
+			{"jsonrpc":"2.0","method":"system_name","id":1},
+			{"jsonrpc":"2.0","method":"system_peers","id":2}
+		]"#;
+
+		assert_eq!(
+			io.handle_request_sync(request, ()),
+			Some(concat!(
+				r#"[{"jsonrpc":"2.0","result":"substrate","id":1},"#,
+				r#"{"jsonrpc":"2.0","error":{"code":3001,"message":"Method is unsafe and not allowed on this RPC server"},"id":2}]"#,
+			).into())
+		);
+	}
+}
@@ -26,6 +26,7 @@ use crate::errors;
 error_chain! {
 	foreign_links {
 		Client(client::error::Error) #[doc = "Client error"];
+		Keystore(keystore::Error) #[doc = "Keystore error"];
 	}
 	links {
 		Pool(txpool::error::Error, txpool::error::ErrorKind) #[doc = "Pool error"];
@@ -46,6 +47,11 @@ error_chain! {
 			description("extrinsic verification error"),
 			display("Extrinsic verification error: {}", e.description()),
 		}
+		/// Key type is not supported by the keystore.
+		UnsupportedKeyType(key_type: String) {
+			description("unsupported key type"),
+			display("Unsupported key type: {}", key_type),
+		}
 	}
 }
 
@@ -55,6 +61,10 @@ const BASE_ERROR: i64 = 1000;
 const BAD_FORMAT: i64 = BASE_ERROR + 1;
 /// Error during transaction verification in runtime.
 const VERIFICATION_ERROR: i64 = BASE_ERROR + 2;
+/// Key type is not supported.
+const UNSUPPORTED_KEY_TYPE: i64 = BASE_ERROR + 3;
+/// Key could not be inserted into or generated in the keystore.
+const KEYSTORE_ERROR: i64 = BASE_ERROR + 4;
 
 /// Pool rejected the transaction as invalid
 const POOL_INVALID_TX: i64 = BASE_ERROR + 10;
@@ -85,6 +95,16 @@ impl From<Error> for rpc::Error {
 				message: e.description().into(),
 				data: Some(format!("{:?}", e).into()),
 			},
+			Error(ErrorKind::UnsupportedKeyType(key_type), _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(UNSUPPORTED_KEY_TYPE),
+				message: format!("Unsupported key type: {}", key_type),
+				data: Some("Supported key types are `ed25519` and `sr25519`.".into()),
+			},
+			Error(ErrorKind::Keystore(e), _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(KEYSTORE_ERROR),
+				message: "Keystore operation failed".into(),
+				data: Some(format!("{}", e).into()),
+			},
 			Error(ErrorKind::Pool(txpool::error::ErrorKind::InvalidTransaction(code)), _) => rpc::Error {
 				code: rpc::ErrorCode::ServerError(POOL_INVALID_TX),
 				message: "Invalid Transaction".into(),
@@ -20,6 +20,8 @@ use std::sync::Arc;
 
 use log::warn;
 use client::{self, Client};
+use consensus::SharedKey;
+use keystore::Store as Keystore;
 use parity_codec::{Encode, Decode};
 use transaction_pool::{
 	txpool::{
@@ -33,18 +35,21 @@ use transaction_pool::{
 };
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
-use primitives::{Bytes, Blake2Hasher, H256};
+use primitives::{Bytes, Blake2Hasher, H256, Pair, ed25519, sr25519};
 use crate::rpc::futures::{Sink, Stream, Future};
 use runtime_primitives::{generic, traits};
 use crate::subscriptions::Subscriptions;
 
 pub mod error;
+mod pool;
 
 #[cfg(test)]
 mod tests;
 
 use self::error::Result;
 
+pub use self::pool::{ExtrinsicSelector, PoolContent, PoolTransaction};
+
 /// Substrate authoring RPC API
 #[rpc]
 pub trait AuthorApi<Hash, BlockHash> {
@@ -59,6 +64,33 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[rpc(name = "author_pendingExtrinsics")]
 	fn pending_extrinsics(&self) -> Result<Vec<Bytes>>;
 
+	/// Returns the ready and future transactions of the pool, with the hashes of the
+	/// temporarily banned ones.
+	#[rpc(name = "author_poolContent")]
+	fn pool_content(&self) -> Result<PoolContent<Hash>>;
+
+	/// Remove the selected transactions from the pool, along with the ready transactions
+	/// depending on them.
+	///
+	/// Returns the hashes of all removed transactions. Only the selected transactions are
+	/// temporarily banned, the dependent ones may be submitted again.
+	#[rpc(name = "author_removeExtrinsic")]
+	fn remove_extrinsic(&self, selectors: Vec<ExtrinsicSelector<Hash>>) -> Result<Vec<Hash>>;
+
+	/// Insert a key of given type (`ed25519` or `sr25519`) derived from the secret URI into the keystore.
+	///
+	/// The key is only stored, it does not replace the node's authority key.
+	/// Returns the public key.
+	#[rpc(name = "author_insertKey")]
+	fn insert_key(&self, key_type: String, suri: String) -> Result<Bytes>;
+
+	/// Generate new session keys in the keystore and select them as the node's authority keys.
+	///
+	/// Returns the public keys, to be registered on chain with `session::set_key`.
+	/// The node signs with the new keys from the next slot or round on.
+	#[rpc(name = "author_rotateKeys")]
+	fn rotate_keys(&self) -> Result<Bytes>;
+
 	/// Submit an extrinsic to watch.
 	#[pubsub(subscription = "author_extrinsicUpdate", subscribe, name = "author_submitAndWatchExtrinsic")]
 	fn watch_extrinsic(&self, metadata: Self::Metadata, subscriber: Subscriber<Status<Hash, BlockHash>>, bytes: Bytes);
@@ -76,6 +108,10 @@ pub struct Author<B, E, P, RA> where P: PoolChainApi + Sync + Send + 'static {
 	pool: Arc<Pool<P>>,
 	/// Subscriptions manager
 	subscriptions: Subscriptions,
+	/// The node's keystore
+	keystore: Arc<Keystore>,
+	/// The authority key used by the running consensus engines
+	authority_key: SharedKey<ed25519::Pair>,
 }
 
 impl<B, E, P, RA> Author<B, E, P, RA> where P: PoolChainApi + Sync + Send + 'static {
@@ -84,11 +120,15 @@ impl<B, E, P, RA> Author<B, E, P, RA> where P: PoolChainApi + Sync + Send + 'sta
 		client: Arc<Client<B, E, <P as PoolChainApi>::Block, RA>>,
 		pool: Arc<Pool<P>>,
 		subscriptions: Subscriptions,
+		keystore: Arc<Keystore>,
+		authority_key: SharedKey<ed25519::Pair>,
 	) -> Self {
 		Author {
 			client,
 			pool,
 			subscriptions,
+			keystore,
+			authority_key,
 		}
 	}
 }
@@ -118,6 +158,50 @@ impl<B, E, P, RA> AuthorApi<ExHash<P>, BlockHash<P>> for Author<B, E, P, RA> whe
 		Ok(self.pool.ready().map(|tx| tx.data.encode().into()).collect())
 	}
 
+	fn pool_content(&self) -> Result<PoolContent<ExHash<P>>> {
+		Ok(PoolContent {
+			ready: self.pool.ready().map(|tx| PoolTransaction::from(&*tx)).collect(),
+			future: self.pool.futures().iter().map(|tx| PoolTransaction::from(&**tx)).collect(),
+			banned: self.pool.banned(),
+		})
+	}
+
+	fn remove_extrinsic(&self, selectors: Vec<ExtrinsicSelector<ExHash<P>>>) -> Result<Vec<ExHash<P>>> {
+		let mut hashes = Vec::new();
+		let mut tags = Vec::new();
+		for selector in selectors {
+			match selector {
+				ExtrinsicSelector::Hash(hash) => hashes.push(hash),
+				ExtrinsicSelector::Tag(tag) => tags.push(tag.to_vec()),
+			}
+		}
+
+		Ok(self.pool.remove_invalid(&hashes)
+			.into_iter()
+			.chain(self.pool.remove_by_tags(&tags))
+			.map(|tx| tx.hash.clone())
+			.collect())
+	}
+
+	fn insert_key(&self, key_type: String, suri: String) -> Result<Bytes> {
+		let public = match key_type.as_str() {
+			"ed25519" => self.keystore.insert_by_type::<ed25519::Pair>(&suri)?
+				.public().0.to_vec(),
+			"sr25519" => self.keystore.insert_by_type::<sr25519::Pair>(&suri)?
+				.public().0.to_vec(),
+			_ => return Err(error::ErrorKind::UnsupportedKeyType(key_type).into()),
+		};
+		Ok(public.into())
+	}
+
+	fn rotate_keys(&self) -> Result<Bytes> {
+		let key = self.keystore.generate()?;
+		self.keystore.set_authority_key(&key.public())?;
+		let public = key.public();
+		self.authority_key.set(Some(Arc::new(key)));
+		Ok(public.0.to_vec().into())
+	}
+
 	fn watch_extrinsic(&self, _metadata: Self::Metadata, subscriber: Subscriber<Status<ExHash<P>, BlockHash<P>>>, xt: Bytes) {
 		let submit = || -> Result<_> {
 			let best_block_hash = self.client.info()?.chain.best_hash;
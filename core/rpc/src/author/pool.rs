@@ -0,0 +1,75 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool contents returned by `author_poolContent`.
+
+use parity_codec::Encode;
+use primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use transaction_pool::txpool::Transaction;
+
+/// Transactions in the pool.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolContent<Hash> {
+	/// Transactions ready to be included in a block, ordered by priority.
+	pub ready: Vec<PoolTransaction<Hash>>,
+	/// Transactions waiting for some of their required tags.
+	pub future: Vec<PoolTransaction<Hash>>,
+	/// Hashes of the transactions temporarily banned from entering the pool.
+	pub banned: Vec<Hash>,
+}
+
+/// Transaction in the pool.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransaction<Hash> {
+	/// Hash of the transaction.
+	pub hash: Hash,
+	/// Encoded extrinsic.
+	pub extrinsic: Bytes,
+	/// Priority of the transaction, higher is better.
+	pub priority: u64,
+	/// Block number at which the transaction becomes invalid.
+	pub valid_till: u64,
+	/// Tags required by the transaction.
+	pub requires: Vec<Bytes>,
+	/// Tags provided by the transaction.
+	pub provides: Vec<Bytes>,
+}
+
+impl<'a, Hash: Clone, Extrinsic: Encode> From<&'a Transaction<Hash, Extrinsic>> for PoolTransaction<Hash> {
+	fn from(tx: &'a Transaction<Hash, Extrinsic>) -> Self {
+		PoolTransaction {
+			hash: tx.hash.clone(),
+			extrinsic: tx.data.encode().into(),
+			priority: tx.priority,
+			valid_till: tx.valid_till,
+			requires: tx.requires.iter().cloned().map(Into::into).collect(),
+			provides: tx.provides.iter().cloned().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Selects transactions to remove from the pool.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum ExtrinsicSelector<Hash> {
+	/// The transaction with given hash.
+	Hash(Hash),
+	/// The transactions providing given tag.
+	Tag(Bytes),
+}
@@ -24,8 +24,15 @@ use transaction_pool::{
 	ChainApi,
 };
 use primitives::{H256, blake2_256, hexdisplay::HexDisplay};
-use test_client::{self, AccountKeyring, runtime::{Extrinsic, Transfer}};
+use test_client::{self, AccountKeyring, runtime::{AccountId, Extrinsic, Transfer}};
 use tokio::runtime;
+use tempdir::TempDir;
+
+fn keystore() -> (TempDir, Arc<Keystore>) {
+	let dir = TempDir::new("rpc-author-keystore").unwrap();
+	let keystore = Keystore::open(dir.path().to_owned(), "password".into()).unwrap();
+	(dir, Arc::new(keystore))
+}
 
 fn uxt(sender: AccountKeyring, nonce: u64) -> Extrinsic {
 	let tx = Transfer {
@@ -41,10 +48,13 @@ fn uxt(sender: AccountKeyring, nonce: u64) -> Extrinsic {
 fn submit_transaction_should_not_cause_error() {
 	let runtime = runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
+	let (_keystore_dir, keystore) = keystore();
 	let p = Author {
 		client: client.clone(),
 		pool: Arc::new(Pool::new(Default::default(), ChainApi::new(client))),
 		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
 	};
 	let xt = uxt(AccountKeyring::Alice, 1).encode();
 	let h: H256 = blake2_256(&xt).into();
@@ -62,10 +72,13 @@ fn submit_transaction_should_not_cause_error() {
 fn submit_rich_transaction_should_not_cause_error() {
 	let runtime = runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
+	let (_keystore_dir, keystore) = keystore();
 	let p = Author {
 		client: client.clone(),
 		pool: Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone()))),
 		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
 	};
 	let xt = uxt(AccountKeyring::Alice, 0).encode();
 	let h: H256 = blake2_256(&xt).into();
@@ -85,10 +98,13 @@ fn should_watch_extrinsic() {
 	let mut runtime = runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
 	let pool = Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone())));
+	let (_keystore_dir, keystore) = keystore();
 	let p = Author {
 		client,
 		pool: pool.clone(),
 		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
 	};
 	let (subscriber, id_rx, data) = ::jsonrpc_pubsub::typed::Subscriber::new_test("test");
 
@@ -125,10 +141,13 @@ fn should_return_pending_extrinsics() {
 	let runtime = runtime::Runtime::new().unwrap();
 	let client = Arc::new(test_client::new());
 	let pool = Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone())));
+	let (_keystore_dir, keystore) = keystore();
 	let p = Author {
 		client,
 		pool: pool.clone(),
 		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
 	};
 	let ex = uxt(AccountKeyring::Alice, 0);
 	AuthorApi::submit_extrinsic(&p, ex.encode().into()).unwrap();
@@ -137,3 +156,125 @@ fn should_return_pending_extrinsics() {
 		Ok(ref expected) if *expected == vec![Bytes(ex.encode())]
 	);
 }
+
+#[test]
+fn should_insert_key() {
+	let runtime = runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let (_keystore_dir, keystore) = keystore();
+	let p = Author {
+		client: client.clone(),
+		pool: Arc::new(Pool::new(Default::default(), ChainApi::new(client))),
+		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore: keystore.clone(),
+		authority_key: Default::default(),
+	};
+	let expected = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+
+	assert_matches!(
+		p.insert_key("sr25519".into(), "//Alice".into()),
+		Ok(ref public) if *public == Bytes(expected.0.to_vec())
+	);
+	assert_eq!(keystore.contents_by_type::<sr25519::Pair>().unwrap(), vec![expected]);
+	assert_matches!(
+		p.insert_key("secp256k1".into(), "//Alice".into()),
+		Err(error::Error(error::ErrorKind::UnsupportedKeyType(_), _))
+	);
+	assert_matches!(
+		p.insert_key("ed25519".into(), "not a valid/seed".into()),
+		Err(error::Error(error::ErrorKind::Keystore(_), _))
+	);
+}
+
+#[test]
+fn should_rotate_keys() {
+	let runtime = runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let (_keystore_dir, keystore) = keystore();
+	let authority_key = SharedKey::default();
+	let p = Author {
+		client: client.clone(),
+		pool: Arc::new(Pool::new(Default::default(), ChainApi::new(client))),
+		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore: keystore.clone(),
+		authority_key: authority_key.clone(),
+	};
+
+	let first = p.rotate_keys().unwrap();
+	let second = p.rotate_keys().unwrap();
+
+	assert_ne!(first, second);
+	assert_eq!(authority_key.get().map(|pair| Bytes(pair.public().0.to_vec())), Some(second.clone()));
+	assert_eq!(keystore.authority_key().unwrap().map(|public| Bytes(public.0.to_vec())), Some(second.clone()));
+	let mut keys: Vec<_> = keystore.contents().unwrap().into_iter().map(|public| Bytes(public.0.to_vec())).collect();
+	keys.sort();
+	let mut expected = vec![first, second];
+	expected.sort();
+	assert_eq!(keys, expected);
+}
+
+#[test]
+fn should_return_pool_content() {
+	let runtime = runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let pool = Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone())));
+	let (_keystore_dir, keystore) = keystore();
+	let p = Author {
+		client,
+		pool: pool.clone(),
+		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
+	};
+	let ready = uxt(AccountKeyring::Alice, 0);
+	let future = uxt(AccountKeyring::Alice, 2);
+	let ready_hash = AuthorApi::submit_extrinsic(&p, ready.encode().into()).unwrap();
+	let future_hash = AuthorApi::submit_extrinsic(&p, future.encode().into()).unwrap();
+	let tag = |nonce: u64| Bytes((AccountId::from(AccountKeyring::Alice), nonce).encode());
+
+	let content = p.pool_content().unwrap();
+
+	assert_eq!(content.ready.len(), 1);
+	assert_eq!(content.ready[0].hash, ready_hash);
+	assert_eq!(content.ready[0].extrinsic, Bytes(ready.encode()));
+	assert_eq!(content.ready[0].provides, vec![tag(0)]);
+	assert!(content.ready[0].requires.is_empty());
+	assert_eq!(content.future.len(), 1);
+	assert_eq!(content.future[0].hash, future_hash);
+	assert_eq!(content.future[0].provides, vec![tag(2)]);
+	assert_eq!(content.future[0].requires, vec![tag(1)]);
+	assert!(content.banned.is_empty());
+}
+
+#[test]
+fn should_remove_extrinsics_by_hash_or_tag() {
+	let runtime = runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let pool = Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone())));
+	let (_keystore_dir, keystore) = keystore();
+	let p = Author {
+		client,
+		pool: pool.clone(),
+		subscriptions: Subscriptions::new(runtime.executor()),
+		keystore,
+		authority_key: Default::default(),
+	};
+	let hash1 = AuthorApi::submit_extrinsic(&p, uxt(AccountKeyring::Alice, 0).encode().into()).unwrap();
+	let hash2 = AuthorApi::submit_extrinsic(&p, uxt(AccountKeyring::Bob, 0).encode().into()).unwrap();
+	let hash3 = AuthorApi::submit_extrinsic(&p, uxt(AccountKeyring::Charlie, 1).encode().into()).unwrap();
+	let charlie_tag = (AccountId::from(AccountKeyring::Charlie), 1u64).encode();
+
+	let removed = p.remove_extrinsic(vec![
+		ExtrinsicSelector::Hash(hash1),
+		ExtrinsicSelector::Tag(charlie_tag.into()),
+	]).unwrap();
+
+	assert_eq!(removed, vec![hash1, hash3]);
+	assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![hash2]);
+	assert_eq!(pool.status().future, 0);
+	let mut banned = p.pool_content().unwrap().banned;
+	banned.sort();
+	let mut expected = vec![hash1, hash3];
+	expected.sort();
+	assert_eq!(banned, expected);
+}
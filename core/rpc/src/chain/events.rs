@@ -0,0 +1,276 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block events streamed by `chain_subscribeEvents`.
+
+use std::sync::Arc;
+
+use log::{debug, warn};
+use client::{self, Client, backend::Backend, runtime_api::{BlockEvent, EventsApi, Metadata}};
+use parity_codec::Decode;
+use primitives::{Blake2Hasher, Bytes};
+use runtime_primitives::generic::BlockId;
+use runtime_primitives::traits::{Block as BlockT, ProvideRuntimeApi};
+use serde::{Deserialize, Serialize};
+use srml_metadata::{DecodeDifferent, FnEncode, RuntimeMetadata, RuntimeMetadataPrefixed};
+
+/// Filter of the events sent to a `chain_subscribeEvents` subscriber.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EventFilter {
+	/// Follow finalized blocks instead of new best blocks.
+	#[serde(default)]
+	pub finalized: bool,
+	/// Events to send, all of them if not given.
+	#[serde(default)]
+	pub events: Option<Vec<EventSelector>>,
+}
+
+impl EventFilter {
+	/// Returns true if the event with given module and event indices passes the filter.
+	pub fn matches(&self, module: u8, event: u8) -> bool {
+		match self.events {
+			None => true,
+			Some(ref selectors) => selectors.iter().any(|selector| {
+				selector.module == module && selector.event.map_or(true, |e| e == event)
+			}),
+		}
+	}
+}
+
+/// Selects all events of a module, or a single one of them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EventSelector {
+	/// Index of the module in the runtime's event enum.
+	pub module: u8,
+	/// Index of the event in the module's event enum, any event of the module if not given.
+	#[serde(default)]
+	pub event: Option<u8>,
+}
+
+/// Event deposited in a block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// Index of the extrinsic which deposited the event, `None` if it was deposited while
+	/// finalizing the block.
+	pub extrinsic_index: Option<u32>,
+	/// Index of the module in the runtime's event enum.
+	pub module_index: u8,
+	/// Index of the event in the module's event enum.
+	pub event_index: u8,
+	/// Name of the module, if known from the runtime metadata.
+	pub module: Option<String>,
+	/// Name of the event, if known from the runtime metadata.
+	pub event: Option<String>,
+	/// The encoded event, including the module and event indices.
+	pub data: Bytes,
+}
+
+/// Events deposited in a block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockEvents<Hash> {
+	/// Hash of the block.
+	pub block: Hash,
+	/// Events passing the subscription filter, in order.
+	pub events: Vec<Event>,
+}
+
+/// Notification sent to `chain_subscribeEvents` subscribers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventsNotification<Hash> {
+	/// Events of a block added to the followed chain.
+	NewBlock(BlockEvents<Hash>),
+	/// Blocks removed from the followed chain by a reorganization. The events
+	/// previously sent for them did not happen on the new chain.
+	Retracted(Vec<Hash>),
+}
+
+/// Module and event names, read from the runtime metadata.
+#[derive(Debug, Default)]
+pub struct EventNames {
+	/// Name and event names of the modules with events, in the runtime's event enum order.
+	modules: Vec<(String, Vec<String>)>,
+}
+
+impl EventNames {
+	/// Collect the names from given metadata.
+	pub fn new(metadata: &RuntimeMetadataPrefixed) -> Self {
+		let modules = match metadata.1 {
+			RuntimeMetadata::V4(ref metadata) => as_slice(&metadata.modules),
+			_ => return Self::default(),
+		};
+
+		EventNames {
+			modules: modules.iter()
+				.filter_map(|module| {
+					let events = match module.event.as_ref()? {
+						DecodeDifferent::Encode(FnEncode(events)) => events(),
+						DecodeDifferent::Decoded(events) => &events[..],
+					};
+					Some((as_str(&module.name), events.iter().map(|event| as_str(&event.name)).collect()))
+				})
+				.collect(),
+		}
+	}
+
+	/// Returns the module and event names of given indices.
+	pub fn names(&self, module: u8, event: u8) -> (Option<String>, Option<String>) {
+		match self.modules.get(module as usize) {
+			Some((name, events)) => (Some(name.clone()), events.get(event as usize).cloned()),
+			None => (None, None),
+		}
+	}
+}
+
+fn as_slice<T>(array: &DecodeDifferent<&'static [T], Vec<T>>) -> &[T] {
+	match array {
+		DecodeDifferent::Encode(array) => array,
+		DecodeDifferent::Decoded(array) => &array[..],
+	}
+}
+
+fn as_str<O: AsRef<str>>(string: &DecodeDifferent<&'static str, O>) -> String {
+	match string {
+		DecodeDifferent::Encode(string) => string.to_string(),
+		DecodeDifferent::Decoded(string) => string.as_ref().to_string(),
+	}
+}
+
+/// Convert the runtime events passing the filter.
+pub fn filter_events(events: Vec<BlockEvent>, filter: &EventFilter, names: &EventNames) -> Vec<Event> {
+	events.into_iter()
+		.filter_map(|BlockEvent { extrinsic_index, event }| {
+			let (module_index, event_index) = match event.get(..2) {
+				Some(indices) => (indices[0], indices[1]),
+				None => return None,
+			};
+			if !filter.matches(module_index, event_index) {
+				return None;
+			}
+
+			let (module, name) = names.names(module_index, event_index);
+			Some(Event {
+				extrinsic_index,
+				module_index,
+				event_index,
+				module,
+				event: name,
+				data: event.into(),
+			})
+		})
+		.collect()
+}
+
+/// State of a `chain_subscribeEvents` subscription.
+pub struct EventsSubscription<B, E, Block: BlockT, RA> {
+	client: Arc<Client<B, E, Block, RA>>,
+	filter: EventFilter,
+	/// Last block sent to the subscriber.
+	last: Block::Hash,
+	/// Names for the runtime with given spec version.
+	names: Option<(u32, EventNames)>,
+}
+
+impl<B, E, Block, RA> EventsSubscription<B, E, Block, RA> where
+	Block: BlockT + 'static,
+	B: Backend<Block, Blake2Hasher> + Send + Sync + 'static,
+	E: client::CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
+	RA: Send + Sync + 'static,
+	Client<B, E, Block, RA>: ProvideRuntimeApi,
+	<Client<B, E, Block, RA> as ProvideRuntimeApi>::Api: EventsApi<Block> + Metadata<Block>,
+{
+	/// Create a subscription following the chain from the block `last`.
+	pub fn new(client: Arc<Client<B, E, Block, RA>>, filter: EventFilter, last: Block::Hash) -> Self {
+		EventsSubscription {
+			client,
+			filter,
+			last,
+			names: None,
+		}
+	}
+
+	/// Returns the notifications to send now that `head` is the head of the followed chain.
+	pub fn notifications(&mut self, head: Block::Hash) -> Vec<EventsNotification<Block::Hash>> {
+		let route = match client::blockchain::tree_route(
+			self.client.backend().blockchain(),
+			BlockId::Hash(self.last),
+			BlockId::Hash(head),
+		) {
+			Ok(route) => route,
+			Err(e) => {
+				warn!("Error computing tree route for events notification: {}", e);
+				return Vec::new();
+			},
+		};
+		self.last = head;
+
+		let mut notifications = Vec::new();
+		if !route.retracted().is_empty() {
+			notifications.push(EventsNotification::Retracted(
+				route.retracted().iter().map(|entry| entry.hash).collect()
+			));
+		}
+		for entry in route.enacted() {
+			match self.block_events(entry.hash) {
+				Ok(ref events) if events.is_empty() => {},
+				Ok(events) => notifications.push(EventsNotification::NewBlock(BlockEvents {
+					block: entry.hash,
+					events,
+				})),
+				Err(e) => warn!("Error reading events of block {}: {}", entry.hash, e),
+			}
+		}
+		notifications
+	}
+
+	fn block_events(&mut self, hash: Block::Hash) -> client::error::Result<Vec<Event>> {
+		let at = BlockId::Hash(hash);
+		let events = self.client.runtime_api().block_events(&at)?;
+		if events.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let spec_version = self.client.runtime_version_at(&at)?.spec_version;
+		match self.names {
+			Some((version, _)) if version == spec_version => {},
+			_ => self.names = Some((spec_version, self.read_names(&at))),
+		}
+		let names = self.names.as_ref().map(|(_, names)| names).expect("names are set above; qed");
+
+		Ok(filter_events(events, &self.filter, names))
+	}
+
+	fn read_names(&self, at: &BlockId<Block>) -> EventNames {
+		let metadata = match self.client.runtime_api().metadata(at) {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				debug!("Runtime metadata unavailable, sending events without names: {}", e);
+				return EventNames::default();
+			},
+		};
+		match RuntimeMetadataPrefixed::decode(&mut &metadata[..]) {
+			Some(metadata) => EventNames::new(&metadata),
+			None => {
+				debug!("Undecodable runtime metadata, sending events without names");
+				EventNames::default()
+			},
+		}
+	}
+}
@@ -19,23 +19,27 @@
 use std::sync::Arc;
 
 use log::warn;
-use client::{self, Client, BlockchainEvents};
+use client::{self, Client, BlockchainEvents, runtime_api::{EventsApi, Metadata}};
 use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use primitives::{H256, Blake2Hasher};
 use crate::rpc::Result as RpcResult;
 use crate::rpc::futures::{stream, Future, Sink, Stream};
 use runtime_primitives::generic::{BlockId, SignedBlock};
-use runtime_primitives::traits::{Block as BlockT, Header, NumberFor};
+use runtime_primitives::traits::{Block as BlockT, Header, NumberFor, ProvideRuntimeApi};
 
 use crate::subscriptions::Subscriptions;
 
 mod error;
+mod events;
 #[cfg(test)]
 mod tests;
 mod number;
 
 use self::error::Result;
+use self::events::EventsSubscription;
+
+pub use self::events::{BlockEvents, Event, EventFilter, EventSelector, EventsNotification};
 
 /// Substrate blockchain API
 #[rpc]
@@ -96,6 +100,22 @@ pub trait ChainApi<Number, Hash, Header, SignedBlock> {
 		alias("chain_unsubscribeFinalisedHeads")
 	)]
 	fn unsubscribe_finalized_heads(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool>;
+
+	/// Block events subscription.
+	///
+	/// Follows new best blocks, or finalized ones, sending the events passing the filter
+	/// of each block added to the chain and the blocks retracted by reorganizations.
+	#[pubsub(subscription = "chain_events", subscribe, name = "chain_subscribeEvents")]
+	fn subscribe_events(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<EventsNotification<Hash>>,
+		filter: Option<EventFilter>,
+	);
+
+	/// Unsubscribe from block events subscription.
+	#[pubsub(subscription = "chain_events", unsubscribe, name = "chain_unsubscribeEvents")]
+	fn unsubscribe_events(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool>;
 }
 
 /// Chain API with subscriptions support.
@@ -170,7 +190,9 @@ impl<B, E, Block, RA> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Sig
 	Block: BlockT<Hash=H256> + 'static,
 	B: client::backend::Backend<Block, Blake2Hasher> + Send + Sync + 'static,
 	E: client::CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
-	RA: Send + Sync + 'static
+	RA: Send + Sync + 'static,
+	Client<B, E, Block, RA>: ProvideRuntimeApi,
+	<Client<B, E, Block, RA> as ProvideRuntimeApi>::Api: EventsApi<Block> + Metadata<Block>,
 {
 	type Metadata = crate::metadata::Metadata;
 
@@ -223,4 +245,50 @@ impl<B, E, Block, RA> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Sig
 	fn unsubscribe_finalized_heads(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
 		Ok(self.subscriptions.cancel(id))
 	}
+
+	fn subscribe_events(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<EventsNotification<Block::Hash>>,
+		filter: Option<EventFilter>,
+	) {
+		type Heads = Box<dyn Stream<Item=H256, Error=()> + Send>;
+
+		let filter = filter.unwrap_or_default();
+		let (head, heads) = match self.client.info() {
+			Ok(info) if filter.finalized => (
+				info.chain.finalized_hash,
+				Box::new(self.client.finality_notification_stream()
+					.map(|notification| notification.hash)) as Heads,
+			),
+			Ok(info) => (
+				info.chain.best_hash,
+				Box::new(self.client.import_notification_stream()
+					.filter(|notification| notification.is_new_best)
+					.map(|notification| notification.hash)) as Heads,
+			),
+			Err(e) => {
+				let _ = subscriber.reject(error::Error::from(e).into());
+				return;
+			},
+		};
+
+		let mut subscription = EventsSubscription::new(self.client.clone(), filter, head);
+		self.subscriptions.add(subscriber, move |sink| {
+			let stream = heads
+				.map(move |head| stream::iter_ok::<_, ()>(subscription.notifications(head).into_iter().map(Ok)))
+				.flatten()
+				.map_err(|e| warn!("Block notification stream error: {:?}", e));
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_events(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
 }
@@ -16,9 +16,10 @@
 
 use super::*;
 use assert_matches::assert_matches;
-use test_client::{self, TestClient};
-use test_client::runtime::{H256, Block, Header};
+use test_client::{self, TestClient, AccountKeyring, BlockBuilderExt};
+use test_client::runtime::{H256, Block, Header, Transfer};
 use consensus::BlockOrigin;
+use primitives::hexdisplay::HexDisplay;
 
 #[test]
 fn should_return_header() {
@@ -250,3 +251,134 @@ fn should_notify_about_finalized_block() {
 	// no more notifications on this channel
 	assert_eq!(core.block_on(next.into_future()).unwrap().0, None);
 }
+
+#[test]
+fn should_notify_about_retracted_blocks() {
+	let mut core = ::tokio::runtime::Runtime::new().unwrap();
+	let remote = core.executor();
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	let (a1, b1) = {
+		let api = Chain {
+			client: Arc::new(test_client::new()),
+			subscriptions: Subscriptions::new(remote),
+		};
+
+		api.subscribe_events(Default::default(), subscriber, None);
+
+		// assert id assigned
+		assert_eq!(core.block_on(id), Ok(Ok(SubscriptionId::Number(1))));
+
+		// G -> A1
+		let a1 = api.client.new_block().unwrap().bake().unwrap();
+		api.client.import(BlockOrigin::Own, a1.clone()).unwrap();
+
+		// G -> B1 -> B2
+		let mut builder = api.client.new_block_at(&BlockId::Hash(api.client.genesis_hash())).unwrap();
+		// this push is required as otherwise B1 has the same hash as A1 and won't get imported
+		builder.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		}).unwrap();
+		let b1 = builder.bake().unwrap();
+		api.client.import(BlockOrigin::Own, b1.clone()).unwrap();
+		let b2 = api.client.new_block_at(&BlockId::Hash(b1.hash())).unwrap().bake().unwrap();
+		api.client.import(BlockOrigin::Own, b2).unwrap();
+
+		(a1.hash(), b1.hash())
+	};
+	assert_ne!(a1, b1);
+
+	// blocks without events are not notified, only the reorganization is.
+	let (notification, next) = core.block_on(transport.into_future()).unwrap();
+	assert_eq!(
+		notification,
+		Some(format!(
+			r#"{{"jsonrpc":"2.0","method":"test","params":{{"result":{{"retracted":["0x{}"]}},"subscription":1}}}}"#,
+			HexDisplay::from(&a1),
+		))
+	);
+	// no more notifications on this channel
+	assert_eq!(core.block_on(next.into_future()).unwrap().0, None);
+}
+
+#[test]
+fn should_filter_events_and_resolve_names() {
+	use client::runtime_api::BlockEvent;
+	use srml_metadata::{
+		DecodeDifferent, EventMetadata, ModuleMetadata, RuntimeMetadata, RuntimeMetadataPrefixed,
+		RuntimeMetadataV4,
+	};
+	use self::events::{filter_events, EventNames};
+
+	let module = |name: &str, events: Option<Vec<&str>>| ModuleMetadata {
+		name: DecodeDifferent::Decoded(name.into()),
+		prefix: DecodeDifferent::Decoded(name.into()),
+		storage: None,
+		calls: None,
+		event: events.map(|events| DecodeDifferent::Decoded(events.into_iter().map(|event| EventMetadata {
+			name: DecodeDifferent::Decoded(event.into()),
+			arguments: DecodeDifferent::Decoded(Vec::new()),
+			documentation: DecodeDifferent::Decoded(Vec::new()),
+		}).collect())),
+	};
+	let metadata: RuntimeMetadataPrefixed = RuntimeMetadata::V4(RuntimeMetadataV4 {
+		modules: DecodeDifferent::Decoded(vec![
+			module("system", Some(vec!["ExtrinsicSuccess", "ExtrinsicFailed"])),
+			module("timestamp", None),
+			module("balances", Some(vec!["NewAccount", "ReapedAccount", "Transfer"])),
+		]),
+	}).into();
+	let names = EventNames::new(&metadata);
+
+	let events = vec![
+		BlockEvent { extrinsic_index: Some(0), event: vec![0, 0] },
+		BlockEvent { extrinsic_index: Some(1), event: vec![1, 2, 42] },
+		BlockEvent { extrinsic_index: Some(1), event: vec![0, 1] },
+		BlockEvent { extrinsic_index: None, event: vec![2, 0] },
+	];
+	let filter = EventFilter {
+		finalized: false,
+		events: Some(vec![
+			EventSelector { module: 0, event: Some(1) },
+			EventSelector { module: 1, event: None },
+		]),
+	};
+
+	assert_eq!(filter_events(events.clone(), &filter, &names), vec![
+		Event {
+			extrinsic_index: Some(1),
+			module_index: 1,
+			event_index: 2,
+			module: Some("balances".into()),
+			event: Some("Transfer".into()),
+			data: vec![1, 2, 42].into(),
+		},
+		Event {
+			extrinsic_index: Some(1),
+			module_index: 0,
+			event_index: 1,
+			module: Some("system".into()),
+			event: Some("ExtrinsicFailed".into()),
+			data: vec![0, 1].into(),
+		},
+	]);
+	assert_eq!(filter_events(events, &Default::default(), &EventNames::default()).len(), 4);
+}
+
+#[test]
+fn should_deserialize_event_filter() {
+	let filter: EventFilter = serde_json::from_str(r#"{"finalized":true,"events":[{"module":2},{"module":0,"event":1}]}"#).unwrap();
+	assert_eq!(filter, EventFilter {
+		finalized: true,
+		events: Some(vec![
+			EventSelector { module: 2, event: None },
+			EventSelector { module: 0, event: Some(1) },
+		]),
+	});
+	assert!(filter.matches(2, 5));
+	assert!(filter.matches(0, 1));
+	assert!(!filter.matches(0, 0));
+}
@@ -27,6 +27,8 @@ pub use subscriptions::Subscriptions;
 pub mod author;
 pub mod chain;
 pub mod metadata;
+pub mod offchain;
+pub mod payment;
 pub mod state;
 pub mod system;
 
@@ -0,0 +1,74 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate offchain API.
+//!
+//! Gives the node operator access to the local storage of the offchain workers,
+//! for instance to provide them with configuration or credentials.
+
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use jsonrpc_derive::rpc;
+use parking_lot::RwLock;
+use primitives::{Bytes, offchain::{OffchainStorage, STORAGE_PREFIX}};
+
+use self::error::Result;
+
+/// Substrate offchain RPC API
+///
+/// All the methods are unsafe: they must only be exposed on locally bound servers.
+#[rpc]
+pub trait OffchainApi {
+	/// Set offchain local storage under given key.
+	#[rpc(name = "offchain_localStorageSet")]
+	fn set_local_storage(&self, key: Bytes, value: Bytes) -> Result<()>;
+
+	/// Get offchain local storage under given key.
+	#[rpc(name = "offchain_localStorageGet")]
+	fn get_local_storage(&self, key: Bytes) -> Result<Option<Bytes>>;
+}
+
+/// Offchain API
+#[derive(Debug)]
+pub struct Offchain<T: OffchainStorage> {
+	/// Offchain storage
+	storage: Arc<RwLock<T>>,
+}
+
+impl<T: OffchainStorage> Offchain<T> {
+	/// Create new instance of Offchain API.
+	pub fn new(storage: T) -> Self {
+		Offchain {
+			storage: Arc::new(RwLock::new(storage)),
+		}
+	}
+}
+
+impl<T: OffchainStorage + 'static> OffchainApi for Offchain<T> {
+	fn set_local_storage(&self, key: Bytes, value: Bytes) -> Result<()> {
+		self.storage.write().set(STORAGE_PREFIX, &*key, &*value);
+		Ok(())
+	}
+
+	fn get_local_storage(&self, key: Bytes) -> Result<Option<Bytes>> {
+		Ok(self.storage.read().get(STORAGE_PREFIX, &*key).map(Into::into))
+	}
+}
@@ -0,0 +1,55 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction payment RPC module errors.
+
+use error_chain::*;
+
+use client;
+use crate::rpc;
+use crate::errors;
+
+error_chain! {
+	foreign_links {
+		Client(client::error::Error) #[doc = "Client error"];
+	}
+
+	errors {
+		/// Incorrect extrinsic format.
+		BadFormat {
+			description("bad format"),
+			display("Invalid extrinsic format"),
+		}
+	}
+}
+
+/// Base code for all payment errors.
+const BASE_ERROR: i64 = 5000;
+/// Extrinsic has an invalid format.
+const BAD_FORMAT: i64 = BASE_ERROR + 1;
+
+impl From<Error> for rpc::Error {
+	fn from(e: Error) -> Self {
+		match e {
+			Error(ErrorKind::BadFormat, _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BAD_FORMAT),
+				message: "Extrinsic has invalid format.".into(),
+				data: None,
+			},
+			e => errors::internal(e),
+		}
+	}
+}
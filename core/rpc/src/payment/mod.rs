@@ -0,0 +1,92 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate transaction payment API.
+//!
+//! The fees are computed by the runtime, so they stay accurate when the fee
+//! parameters change. Since the balance type is runtime specific, the API is not
+//! part of the default RPC handler and has to be registered as an extension.
+
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use std::{marker::PhantomData, sync::Arc};
+
+use client::{self, Client, CallExecutor, runtime_api::TransactionPaymentApi};
+use jsonrpc_derive::rpc;
+use parity_codec::{Encode, Decode};
+use primitives::{Blake2Hasher, Bytes, U256};
+use runtime_primitives::{generic::BlockId, traits::{Block as BlockT, ProvideRuntimeApi}};
+use serde::Serialize;
+
+use self::error::Result;
+
+/// Fees paid by an extrinsic.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeInfo {
+	/// Fee paid for including the extrinsic in a block.
+	pub fee: U256,
+}
+
+/// Substrate transaction payment RPC API
+#[rpc]
+pub trait PaymentApi<Hash> {
+	/// Returns the fees hex-encoded extrinsic would pay at given block (best block by default).
+	#[rpc(name = "payment_queryInfo")]
+	fn query_info(&self, extrinsic: Bytes, hash: Option<Hash>) -> Result<FeeInfo>;
+}
+
+/// Transaction payment API
+pub struct Payment<B, E, Block: BlockT, RA, Balance> {
+	/// Substrate client.
+	client: Arc<Client<B, E, Block, RA>>,
+	_balance: PhantomData<Balance>,
+}
+
+impl<B, E, Block: BlockT, RA, Balance> Payment<B, E, Block, RA, Balance> {
+	/// Create new instance of Payment API.
+	pub fn new(client: Arc<Client<B, E, Block, RA>>) -> Self {
+		Payment {
+			client,
+			_balance: PhantomData,
+		}
+	}
+}
+
+impl<B, E, Block, RA, Balance> PaymentApi<Block::Hash> for Payment<B, E, Block, RA, Balance> where
+	Block: BlockT + 'static,
+	B: client::backend::Backend<Block, Blake2Hasher> + Send + Sync + 'static,
+	E: CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
+	RA: Send + Sync + 'static,
+	Balance: Encode + Decode + Into<u128> + Send + Sync + 'static,
+	Client<B, E, Block, RA>: ProvideRuntimeApi,
+	<Client<B, E, Block, RA> as ProvideRuntimeApi>::Api: TransactionPaymentApi<Block, Balance>,
+{
+	fn query_info(&self, extrinsic: Bytes, hash: Option<Block::Hash>) -> Result<FeeInfo> {
+		let extrinsic = Decode::decode(&mut &extrinsic[..]).ok_or(error::ErrorKind::BadFormat)?;
+		let hash = match hash {
+			Some(hash) => hash,
+			None => self.client.info()?.chain.best_hash,
+		};
+		let fee: u128 = self.client.runtime_api().query_fee(&BlockId::Hash(hash), extrinsic)?.into();
+		Ok(FeeInfo {
+			fee: U256::from_little_endian(&fee.to_le_bytes()),
+		})
+	}
+}
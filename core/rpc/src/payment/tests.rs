@@ -0,0 +1,55 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use assert_matches::assert_matches;
+use test_client::{self, AccountKeyring, runtime::Transfer};
+
+#[test]
+fn should_query_fee_of_extrinsic() {
+	let payment = Payment::<_, _, _, _, u64>::new(Arc::new(test_client::new()));
+	let extrinsic = Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+		amount: 42,
+		nonce: 0,
+	}.into_signed_tx().encode();
+
+	// the test runtime charges one unit per byte
+	assert_eq!(
+		payment.query_info(extrinsic.clone().into(), None).unwrap(),
+		FeeInfo { fee: (extrinsic.len() as u64).into() }
+	);
+}
+
+#[test]
+fn should_reject_invalid_extrinsic() {
+	let payment = Payment::<_, _, _, _, u64>::new(Arc::new(test_client::new()));
+
+	assert_matches!(
+		payment.query_info(vec![1, 2, 3].into(), None),
+		Err(error::Error(error::ErrorKind::BadFormat, _))
+	);
+}
+
+#[test]
+fn should_serialize_fee_info() {
+	assert_eq!(
+		::serde_json::to_string(&FeeInfo { fee: 1_000u64.into() }).unwrap(),
+		r#"{"fee":"0x3e8"}"#,
+	);
+}
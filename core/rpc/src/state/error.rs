@@ -30,6 +30,11 @@ error_chain! {
 			description("Invalid block range"),
 			display("Cannot resolve a block range ['{:?}' ... '{:?}]. {}", from, to, details),
 		}
+		/// Requested number of entries exceeds the limit.
+		InvalidCount(value: u32, max: u32) {
+			description("Invalid count"),
+			display("Count {} exceeds the maximal count of {}", value, max),
+		}
 		/// Not implemented yet
 		Unimplemented {
 			description("not implemented yet"),
@@ -38,10 +43,20 @@ error_chain! {
 	}
 }
 
+/// Base code for all state errors.
+const BASE_ERROR: i64 = 4000;
+/// Requested number of entries exceeds the limit.
+const INVALID_COUNT: i64 = BASE_ERROR + 1;
+
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
 		match e {
 			Error(ErrorKind::Unimplemented, _) => errors::unimplemented(),
+			Error(ErrorKind::InvalidCount(value, max), _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(INVALID_COUNT),
+				message: format!("Count {} exceeds the maximal count of {}", value, max),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
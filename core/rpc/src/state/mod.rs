@@ -30,6 +30,7 @@ use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use primitives::{H256, Blake2Hasher, Bytes};
 use primitives::hexdisplay::HexDisplay;
 use primitives::storage::{self, StorageKey, StorageData, StorageChangeSet};
+use serde::Serialize;
 use crate::rpc::Result as RpcResult;
 use crate::rpc::futures::{stream, Future, Sink, Stream};
 use runtime_primitives::generic::BlockId;
@@ -40,10 +41,30 @@ use state_machine::{self, ExecutionStrategy};
 use crate::subscriptions::Subscriptions;
 
 mod error;
+mod trace;
 #[cfg(test)]
 mod tests;
 
 use self::error::Result;
+pub use self::trace::{BlockTrace, ExtrinsicTrace, StorageAccess};
+
+/// Maximal number of keys or pairs returned by a single paged storage query.
+const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
+
+/// Maximal number of keys proven by a single read proof query.
+const READ_PROOF_MAX_KEYS: u32 = 1000;
+
+/// Proof of storage entries at a block's state.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadProof<Hash> {
+	/// Block hash used to generate the proof.
+	pub at: Hash,
+	/// State root of the block, the proof is checked against it.
+	pub state_root: Hash,
+	/// Trie nodes of the proof.
+	pub proof: Vec<Bytes>,
+}
 
 /// Substrate state API
 #[rpc]
@@ -59,6 +80,27 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getKeys")]
 	fn storage_keys(&self, key: StorageKey, hash: Option<Hash>) -> Result<Vec<StorageKey>>;
 
+	/// Returns up to `count` keys with prefix in lexicographic order, following `start_key` if given.
+	#[rpc(name = "state_getKeysPaged")]
+	fn storage_keys_paged(
+		&self,
+		prefix: StorageKey,
+		count: u32,
+		start_key: Option<StorageKey>,
+		hash: Option<Hash>,
+	) -> Result<Vec<StorageKey>>;
+
+	/// Returns up to `count` key/value pairs with key prefix in lexicographic order of keys,
+	/// following `start_key` if given.
+	#[rpc(name = "state_getPairs")]
+	fn storage_pairs(
+		&self,
+		prefix: StorageKey,
+		count: u32,
+		start_key: Option<StorageKey>,
+		hash: Option<Hash>,
+	) -> Result<Vec<(StorageKey, StorageData)>>;
+
 	/// Returns a storage entry at a specific block's state.
 	#[rpc(name = "state_getStorage", alias("state_getStorageAt"))]
 	fn storage(&self, key: StorageKey, hash: Option<Hash>) -> Result<Option<StorageData>>;
@@ -71,6 +113,17 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getStorageSize", alias("state_getStorageSizeAt"))]
 	fn storage_size(&self, key: StorageKey, hash: Option<Hash>) -> Result<Option<u64>>;
 
+	/// Returns proof of storage entries at a specific block's state.
+	///
+	/// Fails if more than a fixed number of keys are requested at once.
+	#[rpc(name = "state_getReadProof")]
+	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> Result<ReadProof<Hash>>;
+
+	/// Re-executes a block on its parent's state, returning the storage accesses made
+	/// by the runtime and the dispatch result of each extrinsic.
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(&self, block: Hash) -> Result<BlockTrace<Hash>>;
+
 	/// Returns the runtime metadata as an opaque blob.
 	#[rpc(name = "state_getMetadata")]
 	fn metadata(&self, hash: Option<Hash>) -> Result<Bytes>;
@@ -86,6 +139,14 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_queryStorage")]
 	fn query_storage(&self, keys: Vec<StorageKey>, block: Hash, hash: Option<Hash>) -> Result<Vec<StorageChangeSet<Hash>>>;
 
+	/// Returns the storage entries with key prefix whose value differs between the states of
+	/// the `from` and `to` (best by default) blocks, along with their value at `to`.
+	///
+	/// Removed entries have no value. Changes tries are used to find the changed keys when
+	/// available for the blocks in between, otherwise the state tries of both blocks are compared.
+	#[rpc(name = "state_diff")]
+	fn storage_diff(&self, from: Hash, to: Option<Hash>, prefix: StorageKey) -> Result<StorageChangeSet<Hash>>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
@@ -279,6 +340,13 @@ impl<B, E, Block, RA> State<B, E, Block, RA> where
 	fn unwrap_or_best(&self, hash: Option<Block::Hash>) -> Result<Block::Hash> {
 		crate::helpers::unwrap_or_else(|| Ok(self.client.info()?.chain.best_hash), hash)
 	}
+
+	fn ensure_page_count(count: u32) -> Result<usize> {
+		if count > STORAGE_KEYS_PAGED_MAX_COUNT {
+			bail!(error::ErrorKind::InvalidCount(count, STORAGE_KEYS_PAGED_MAX_COUNT));
+		}
+		Ok(count as usize)
+	}
 }
 
 impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA> where
@@ -309,6 +377,32 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA> where
 		Ok(self.client.storage_keys(&BlockId::Hash(block), &key_prefix)?)
 	}
 
+	fn storage_keys_paged(
+		&self,
+		prefix: StorageKey,
+		count: u32,
+		start_key: Option<StorageKey>,
+		block: Option<Block::Hash>,
+	) -> Result<Vec<StorageKey>> {
+		let count = Self::ensure_page_count(count)?;
+		let block = self.unwrap_or_best(block)?;
+		trace!(target: "rpc", "Querying {} storage keys at {:?}", count, block);
+		Ok(self.client.storage_keys_paged(&BlockId::Hash(block), &prefix, start_key.as_ref(), count)?)
+	}
+
+	fn storage_pairs(
+		&self,
+		prefix: StorageKey,
+		count: u32,
+		start_key: Option<StorageKey>,
+		block: Option<Block::Hash>,
+	) -> Result<Vec<(StorageKey, StorageData)>> {
+		let count = Self::ensure_page_count(count)?;
+		let block = self.unwrap_or_best(block)?;
+		trace!(target: "rpc", "Querying {} storage pairs at {:?}", count, block);
+		Ok(self.client.storage_pairs_paged(&BlockId::Hash(block), &prefix, start_key.as_ref(), count)?)
+	}
+
 	fn storage(&self, key: StorageKey, block: Option<Block::Hash>) -> Result<Option<StorageData>> {
 		let block = self.unwrap_or_best(block)?;
 		trace!(target: "rpc", "Querying storage at {:?} for key {}", block, HexDisplay::from(&key.0));
@@ -324,6 +418,30 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA> where
 		Ok(self.storage(key, block)?.map(|x| x.0.len() as u64))
 	}
 
+	fn read_proof(&self, keys: Vec<StorageKey>, block: Option<Block::Hash>) -> Result<ReadProof<Block::Hash>> {
+		if keys.len() > READ_PROOF_MAX_KEYS as usize {
+			bail!(error::ErrorKind::InvalidCount(keys.len() as u32, READ_PROOF_MAX_KEYS));
+		}
+		let block = self.unwrap_or_best(block)?;
+		trace!(target: "rpc", "Generating read proof of {} keys at {:?}", keys.len(), block);
+		let id = BlockId::Hash(block);
+		let header = self.client.header(&id)?
+			.ok_or_else(|| client::error::Error::UnknownBlock(format!("{}", block)))?;
+		let keys = keys.into_iter().map(|key| key.0).collect::<Vec<_>>();
+		let proof = self.client.read_proof_of_keys(&id, &keys)?;
+		Ok(ReadProof {
+			at: block,
+			state_root: *header.state_root(),
+			proof: proof.into_iter().map(Bytes).collect(),
+		})
+	}
+
+	fn trace_block(&self, block: Block::Hash) -> Result<BlockTrace<Block::Hash>> {
+		trace!(target: "rpc", "Tracing block {:?}", block);
+		let trace = self.client.trace_block(&BlockId::Hash(block))?;
+		Ok(BlockTrace::new(block, trace))
+	}
+
 	fn metadata(&self, block: Option<Block::Hash>) -> Result<Bytes> {
 		let block = self.unwrap_or_best(block)?;
 		self.client.runtime_api().metadata(&BlockId::Hash(block)).map(Into::into).map_err(Into::into)
@@ -342,6 +460,22 @@ impl<B, E, Block, RA> StateApi<Block::Hash> for State<B, E, Block, RA> where
 		Ok(changes)
 	}
 
+	fn storage_diff(
+		&self,
+		from: Block::Hash,
+		to: Option<Block::Hash>,
+		prefix: StorageKey,
+	) -> Result<StorageChangeSet<Block::Hash>> {
+		let to = self.unwrap_or_best(to)?;
+		trace!(target: "rpc", "Computing storage diff from {:?} to {:?} under prefix {}", from, to, HexDisplay::from(&prefix.0));
+		let changes = self.client.storage_diff(
+			&BlockId::Hash(from),
+			&BlockId::Hash(to),
+			&prefix,
+		)?;
+		Ok(StorageChangeSet { block: to, changes })
+	}
+
 	fn subscribe_storage(
 		&self,
 		_meta: Self::Metadata,
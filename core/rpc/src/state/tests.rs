@@ -35,6 +35,96 @@ fn should_return_storage() {
 	)
 }
 
+#[test]
+fn should_return_storage_keys_and_pairs_paged() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let client = State::new(client, Subscriptions::new(core.executor()));
+	let prefix = StorageKey(vec![]);
+
+	let mut all_keys = client.storage_keys(prefix.clone(), Some(genesis_hash)).unwrap();
+	all_keys.sort();
+	assert!(all_keys.len() > 2);
+
+	let first_page = client.storage_keys_paged(prefix.clone(), 2, None, Some(genesis_hash)).unwrap();
+	assert_eq!(first_page, all_keys[..2].to_vec());
+	let next_pages = client.storage_keys_paged(
+		prefix.clone(),
+		1000,
+		first_page.last().cloned(),
+		Some(genesis_hash),
+	).unwrap();
+	assert_eq!(next_pages, all_keys[2..].to_vec());
+
+	let pairs = client.storage_pairs(prefix.clone(), 2, None, Some(genesis_hash)).unwrap();
+	assert_eq!(pairs.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>(), first_page);
+	assert_eq!(
+		client.storage(pairs[0].0.clone(), Some(genesis_hash)).unwrap(),
+		Some(pairs[0].1.clone())
+	);
+
+	assert_matches!(
+		client.storage_keys_paged(prefix, 1001, None, Some(genesis_hash)),
+		Err(Error(ErrorKind::InvalidCount(1001, 1000), _))
+	);
+}
+
+#[test]
+fn should_return_read_proof() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let client = State::new(client, Subscriptions::new(core.executor()));
+	let key = StorageKey(storage::well_known_keys::CODE.to_vec());
+
+	let read_proof = client.read_proof(vec![key.clone()], Some(genesis_hash)).unwrap();
+	assert_eq!(read_proof.at, genesis_hash);
+
+	let proof = read_proof.proof.into_iter().map(|node| node.0).collect();
+	let value = state_machine::read_proof_check::<Blake2Hasher>(read_proof.state_root, proof, &key.0).unwrap();
+	assert_eq!(value.map(StorageData), client.storage(key.clone(), Some(genesis_hash)).unwrap());
+
+	assert_matches!(
+		client.read_proof(vec![key; 1001], Some(genesis_hash)),
+		Err(Error(ErrorKind::InvalidCount(1001, 1000), _))
+	);
+}
+
+#[test]
+fn should_trace_block() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let mut builder = client.new_block().unwrap();
+	builder.push_transfer(runtime::Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 42,
+		nonce: 0,
+	}).unwrap();
+	let block = builder.bake().unwrap();
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+	let client = State::new(client, Subscriptions::new(core.executor()));
+
+	let trace = client.trace_block(block_hash).unwrap();
+	assert_eq!(trace.block, block_hash);
+	assert_eq!(trace.extrinsics.len(), 1);
+
+	let ferdie_balance_key = blake2_256(&test_runtime::system::balance_of_key(AccountKeyring::Ferdie.into()));
+	let extrinsic = &trace.extrinsics[0];
+	assert_eq!(extrinsic.result, Some(Ok(runtime_primitives::ApplyOutcome::Success)));
+	assert!(extrinsic.storage.iter().any(|access| match access {
+		StorageAccess::Write { key, new, .. } => key.0 == ferdie_balance_key && new.is_some(),
+		_ => false,
+	}));
+	assert!(trace.finalization.iter().any(|access| match access {
+		StorageAccess::Write { key, new, .. } =>
+			key.0 == primitives::storage::well_known_keys::EXTRINSIC_INDEX && new.is_none(),
+		_ => false,
+	}));
+}
+
 #[test]
 fn should_call_contract() {
 	let core = ::tokio::runtime::Runtime::new().unwrap();
@@ -202,6 +292,55 @@ fn should_query_storage() {
 	run_tests(Arc::new(test_client::new_with_changes_trie()));
 }
 
+#[test]
+fn should_return_storage_diff() {
+	type TestClient = test_client::client::Client<
+		test_client::Backend,
+		test_client::Executor,
+		runtime::Block,
+		runtime::RuntimeApi
+	>;
+
+	fn run_tests(client: Arc<TestClient>) {
+		let core = ::tokio::runtime::Runtime::new().unwrap();
+		let api = State::new(client.clone(), Subscriptions::new(core.executor()));
+
+		let add_block = |nonce| {
+			let mut builder = client.new_block().unwrap();
+			builder.push_transfer(runtime::Transfer {
+				from: AccountKeyring::Alice.into(),
+				to: AccountKeyring::Ferdie.into(),
+				amount: 42,
+				nonce,
+			}).unwrap();
+			let block = builder.bake().unwrap();
+			let hash = block.header.hash();
+			client.import(BlockOrigin::Own, block).unwrap();
+			hash
+		};
+		let block1_hash = add_block(0);
+		let block2_hash = add_block(1);
+
+		let alice_balance_key = StorageKey(blake2_256(&test_runtime::system::balance_of_key(AccountKeyring::Alice.into())).to_vec());
+		let ferdie_balance_key = StorageKey(blake2_256(&test_runtime::system::balance_of_key(AccountKeyring::Ferdie.into())).to_vec());
+
+		let diff = api.storage_diff(block1_hash, None, StorageKey(Vec::new())).unwrap();
+		assert_eq!(diff.block, block2_hash);
+		assert!(diff.changes.contains(&(alice_balance_key.clone(), Some(StorageData(vec![148, 3, 0, 0, 0, 0, 0, 0])))));
+		assert!(diff.changes.contains(&(ferdie_balance_key.clone(), Some(StorageData(vec![84, 0, 0, 0, 0, 0, 0, 0])))));
+
+		assert_eq!(
+			api.storage_diff(block1_hash, Some(block2_hash), alice_balance_key.clone()).unwrap().changes,
+			vec![(alice_balance_key.clone(), Some(StorageData(vec![148, 3, 0, 0, 0, 0, 0, 0])))],
+		);
+		assert!(api.storage_diff(block2_hash, Some(block2_hash), StorageKey(Vec::new())).unwrap().changes.is_empty());
+		assert!(api.storage_diff(H256::from_low_u64_be(5), None, StorageKey(Vec::new())).is_err());
+	}
+
+	run_tests(Arc::new(test_client::new()));
+	run_tests(Arc::new(test_client::new_with_changes_trie()));
+}
+
 #[test]
 fn should_split_ranges() {
 	assert_eq!(split_range(1, None), (0..1, None));
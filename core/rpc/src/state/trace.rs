@@ -0,0 +1,168 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block execution traces returned by `state_traceBlock`.
+
+use client;
+use parity_codec::Decode;
+use primitives::Bytes;
+use runtime_primitives::ApplyResult;
+use serde::Serialize;
+use state_machine;
+
+/// Storage accesses made while re-executing a block.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace<Hash> {
+	/// Hash of the traced block.
+	pub block: Hash,
+	/// Accesses made while initializing the block.
+	pub initialization: Vec<StorageAccess>,
+	/// Traces of the block's extrinsics, in order.
+	pub extrinsics: Vec<ExtrinsicTrace>,
+	/// Accesses made while finalizing the block.
+	pub finalization: Vec<StorageAccess>,
+}
+
+impl<Hash> BlockTrace<Hash> {
+	/// Convert the client trace of given block.
+	pub fn new(block: Hash, trace: client::BlockTrace) -> Self {
+		BlockTrace {
+			block,
+			initialization: trace.initialization.into_iter().map(Into::into).collect(),
+			extrinsics: trace.extrinsics.into_iter().map(Into::into).collect(),
+			finalization: trace.finalization.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Storage accesses made while applying a single extrinsic.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicTrace {
+	/// Encoded extrinsic.
+	pub extrinsic: Bytes,
+	/// Dispatch result, `None` if the runtime returned something undecodable.
+	pub result: Option<ApplyResult>,
+	/// Storage accesses, in order.
+	pub storage: Vec<StorageAccess>,
+}
+
+impl From<client::ExtrinsicTrace> for ExtrinsicTrace {
+	fn from(trace: client::ExtrinsicTrace) -> Self {
+		ExtrinsicTrace {
+			extrinsic: trace.extrinsic.into(),
+			result: Decode::decode(&mut &trace.result[..]),
+			storage: trace.storage.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Storage access made by the runtime.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StorageAccess {
+	/// Entry was read.
+	Read {
+		/// Key of the entry.
+		key: Bytes,
+		/// Value read, `None` if the entry does not exist.
+		value: Option<Bytes>,
+	},
+	/// Entry was set or cleared.
+	Write {
+		/// Key of the entry.
+		key: Bytes,
+		/// Value before the write.
+		old: Option<Bytes>,
+		/// Value after the write, `None` if the entry was cleared.
+		new: Option<Bytes>,
+	},
+	/// Entry of a child storage was read.
+	#[serde(rename_all = "camelCase")]
+	ChildRead {
+		/// Key of the child storage.
+		storage_key: Bytes,
+		/// Key of the entry.
+		key: Bytes,
+		/// Value read, `None` if the entry does not exist.
+		value: Option<Bytes>,
+	},
+	/// Entry of a child storage was set or cleared.
+	#[serde(rename_all = "camelCase")]
+	ChildWrite {
+		/// Key of the child storage.
+		storage_key: Bytes,
+		/// Key of the entry.
+		key: Bytes,
+		/// Value before the write.
+		old: Option<Bytes>,
+		/// Value after the write, `None` if the entry was cleared.
+		new: Option<Bytes>,
+	},
+	/// All entries with keys starting with the prefix were cleared.
+	ClearPrefix {
+		/// Prefix of the cleared keys.
+		prefix: Bytes,
+		/// Entries before the clear, in order of keys.
+		old: Vec<(Bytes, Bytes)>,
+	},
+	/// Child storage was cleared.
+	#[serde(rename_all = "camelCase")]
+	KillChild {
+		/// Key of the child storage.
+		storage_key: Bytes,
+		/// Entries before the clear, in order of keys.
+		old: Vec<(Bytes, Bytes)>,
+	},
+}
+
+impl From<state_machine::StorageAccess> for StorageAccess {
+	fn from(access: state_machine::StorageAccess) -> Self {
+		use state_machine::StorageAccess as Access;
+
+		match access {
+			Access::Read { key, value } => StorageAccess::Read {
+				key: key.into(),
+				value: value.map(Into::into),
+			},
+			Access::Write { key, old, new } => StorageAccess::Write {
+				key: key.into(),
+				old: old.map(Into::into),
+				new: new.map(Into::into),
+			},
+			Access::ChildRead { storage_key, key, value } => StorageAccess::ChildRead {
+				storage_key: storage_key.into(),
+				key: key.into(),
+				value: value.map(Into::into),
+			},
+			Access::ChildWrite { storage_key, key, old, new } => StorageAccess::ChildWrite {
+				storage_key: storage_key.into(),
+				key: key.into(),
+				old: old.map(Into::into),
+				new: new.map(Into::into),
+			},
+			Access::ClearPrefix { prefix, old } => StorageAccess::ClearPrefix {
+				prefix: prefix.into(),
+				old: old.into_iter().map(|(key, value)| (key.into(), value.into())).collect(),
+			},
+			Access::KillChild { storage_key, old } => StorageAccess::KillChild {
+				storage_key: storage_key.into(),
+				old: old.into_iter().map(|(key, value)| (key.into(), value.into())).collect(),
+			},
+		}
+	}
+}
@@ -0,0 +1,104 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate system account API.
+//!
+//! Since the account and index types are runtime specific, the API is not part of
+//! the default RPC handler and has to be registered as an extension.
+
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+use client::{self, Client, runtime_api::AccountNonceApi};
+use jsonrpc_derive::rpc;
+use parity_codec::{Encode, Decode};
+use primitives::Blake2Hasher;
+use runtime_primitives::{generic::BlockId, traits::{One, ProvideRuntimeApi, SimpleArithmetic}};
+use transaction_pool::txpool::{ChainApi as PoolChainApi, Pool};
+
+use super::error::Result;
+
+/// Substrate system account RPC API
+#[rpc]
+pub trait AccountApi<AccountId, Index> {
+	/// Returns the next valid index (aka nonce) of given account.
+	///
+	/// Includes the transactions of the account waiting in the pool, so that several
+	/// transactions can be sent without waiting for the previous ones to be included.
+	#[rpc(name = "system_accountNextIndex")]
+	fn nonce(&self, account: AccountId) -> Result<Index>;
+}
+
+/// System account API
+pub struct Account<B, E, P, RA, AccountId, Index> where P: PoolChainApi + Sync + Send + 'static {
+	/// Substrate client
+	client: Arc<Client<B, E, <P as PoolChainApi>::Block, RA>>,
+	/// Extrinsic pool
+	pool: Arc<Pool<P>>,
+	_marker: PhantomData<(AccountId, Index)>,
+}
+
+impl<B, E, P, RA, AccountId, Index> Account<B, E, P, RA, AccountId, Index> where
+	P: PoolChainApi + Sync + Send + 'static
+{
+	/// Create new instance of System account API.
+	pub fn new(client: Arc<Client<B, E, <P as PoolChainApi>::Block, RA>>, pool: Arc<Pool<P>>) -> Self {
+		Account {
+			client,
+			pool,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, E, P, RA, AccountId, Index> AccountApi<AccountId, Index> for Account<B, E, P, RA, AccountId, Index> where
+	B: client::backend::Backend<<P as PoolChainApi>::Block, Blake2Hasher> + Send + Sync + 'static,
+	E: client::CallExecutor<<P as PoolChainApi>::Block, Blake2Hasher> + Send + Sync + 'static,
+	P: PoolChainApi + Sync + Send + 'static,
+	RA: Send + Sync + 'static,
+	AccountId: Clone + Encode + Decode + Send + Sync + 'static,
+	Index: SimpleArithmetic + Encode + Decode + Send + Sync + 'static,
+	Client<B, E, <P as PoolChainApi>::Block, RA>: ProvideRuntimeApi,
+	<Client<B, E, <P as PoolChainApi>::Block, RA> as ProvideRuntimeApi>::Api:
+		AccountNonceApi<<P as PoolChainApi>::Block, AccountId, Index>,
+{
+	fn nonce(&self, account: AccountId) -> Result<Index> {
+		let best = self.client.info()?.chain.best_hash;
+		let nonce = self.client.runtime_api().account_nonce(&BlockId::hash(best), account.clone())?;
+		Ok(adjust_nonce(&self.pool, &account, nonce))
+	}
+}
+
+/// Skip the nonces of the account's transactions waiting in the pool.
+///
+/// The transactions are expected to provide `(account, index)` tags, as validated by
+/// `srml-executive`. Only consecutive nonces are skipped, so that gaps in the pool
+/// get filled first.
+fn adjust_nonce<P, AccountId, Index>(pool: &Pool<P>, account: &AccountId, nonce: Index) -> Index where
+	P: PoolChainApi + Sync + Send + 'static,
+	AccountId: Encode,
+	Index: SimpleArithmetic + Encode,
+{
+	let provided = pool.ready()
+		.chain(pool.futures())
+		.flat_map(|tx| tx.provides.clone())
+		.collect::<HashSet<_>>();
+
+	let mut nonce = nonce;
+	while provided.contains(&(account, &nonce).encode()) {
+		nonce += One::one();
+	}
+	nonce
+}
@@ -18,11 +18,16 @@
 
 use error_chain::*;
 
+use client;
 use crate::rpc;
 use crate::errors;
 use crate::system::helpers::Health;
 
 error_chain! {
+	foreign_links {
+		Client(client::error::Error) #[doc = "Client error"];
+	}
+
 	errors {
 		/// Node is not fully functional
 		NotHealthy(h: Health) {
@@ -30,6 +35,12 @@ error_chain! {
 			display("Node is not fully functional: {}", h)
 		}
 
+		/// Incorrect extrinsic format.
+		BadFormat {
+			description("bad format"),
+			display("Invalid extrinsic format"),
+		}
+
 		/// Not implemented yet
 		Unimplemented {
 			description("not yet implemented"),
@@ -39,6 +50,8 @@ error_chain! {
 }
 
 const ERROR: i64 = 2000;
+/// Extrinsic has an invalid format.
+const BAD_FORMAT: i64 = ERROR + 2;
 
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
@@ -49,6 +62,11 @@ impl From<Error> for rpc::Error {
 				message: "node is not healthy".into(),
 				data:serde_json::to_value(h).ok(),
 			},
+			Error(ErrorKind::BadFormat, _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BAD_FORMAT),
+				message: "Extrinsic has invalid format.".into(),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
@@ -17,6 +17,8 @@
 //! Substrate system API helpers.
 
 use std::fmt;
+use primitives::Bytes;
+use runtime_primitives::ApplyResult;
 use serde::Serialize;
 use serde_json::{Value, map::Map};
 
@@ -66,6 +68,16 @@ pub struct PeerInfo<Hash, Number> {
 	pub best_number: Number,
 }
 
+/// Outcome of an extrinsic applied without keeping its changes.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunResult {
+	/// Dispatch result
+	pub result: ApplyResult,
+	/// Encoded events deposited by the extrinsic
+	pub events: Bytes,
+}
+
 impl fmt::Display for Health {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		write!(fmt, "{} peers ({})", self.peers, if self.is_syncing {
@@ -103,4 +115,15 @@ mod tests {
 			r#"{"peerId":"2","roles":"a","protocolVersion":2,"bestHash":5,"bestNumber":6}"#,
 		);
 	}
+
+	#[test]
+	fn should_serialize_dry_run_result() {
+		assert_eq!(
+			::serde_json::to_string(&DryRunResult {
+				result: Ok(runtime_primitives::ApplyOutcome::Success),
+				events: vec![4, 1].into(),
+			}).unwrap(),
+			r#"{"result":{"Ok":"Success"},"events":"0x0401"}"#,
+		);
+	}
 }
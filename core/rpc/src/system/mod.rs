@@ -18,17 +18,22 @@
 
 pub mod error;
 
+mod account;
 mod helpers;
 #[cfg(test)]
 mod tests;
 
 use std::sync::Arc;
+use client::{self, Client, CallExecutor, runtime_api::DryRunApi};
 use jsonrpc_derive::rpc;
 use network;
-use runtime_primitives::traits::{self, Header as HeaderT};
+use parity_codec::Decode;
+use primitives::{Blake2Hasher, Bytes};
+use runtime_primitives::{generic::BlockId, traits::{self, Header as HeaderT, ProvideRuntimeApi}};
 
 use self::error::Result;
-pub use self::helpers::{Properties, SystemInfo, Health, PeerInfo};
+pub use self::account::{Account, AccountApi};
+pub use self::helpers::{Properties, SystemInfo, Health, PeerInfo, DryRunResult};
 
 /// Substrate system RPC API
 #[rpc]
@@ -67,23 +72,31 @@ pub trait SystemApi<Hash, Number> {
 	// TODO: make this stable and move structs https://github.com/paritytech/substrate/issues/1890
 	#[rpc(name = "system_networkState")]
 	fn system_network_state(&self) -> Result<network::NetworkState>;
+
+	/// Applies hex-encoded extrinsic on top of given block (best block by default) without
+	/// keeping the changes, returning the dispatch result and the deposited events.
+	#[rpc(name = "system_dryRun")]
+	fn system_dry_run(&self, extrinsic: Bytes, hash: Option<Hash>) -> Result<DryRunResult>;
 }
 
 /// System API implementation
-pub struct System<B: traits::Block> {
+pub struct System<B, E, Block: traits::Block, RA> {
+	client: Arc<Client<B, E, Block, RA>>,
 	info: SystemInfo,
-	sync: Arc<network::SyncProvider<B>>,
+	sync: Arc<network::SyncProvider<Block>>,
 	should_have_peers: bool,
 }
 
-impl<B: traits::Block> System<B> {
+impl<B, E, Block: traits::Block, RA> System<B, E, Block, RA> {
 	/// Creates new `System` given the `SystemInfo`.
 	pub fn new(
+		client: Arc<Client<B, E, Block, RA>>,
 		info: SystemInfo,
-		sync: Arc<network::SyncProvider<B>>,
+		sync: Arc<network::SyncProvider<Block>>,
 		should_have_peers: bool,
 	) -> Self {
 		System {
+			client,
 			info,
 			should_have_peers,
 			sync,
@@ -91,7 +104,14 @@ impl<B: traits::Block> System<B> {
 	}
 }
 
-impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for System<B> {
+impl<B, E, Block, RA> SystemApi<Block::Hash, <Block::Header as HeaderT>::Number> for System<B, E, Block, RA> where
+	Block: traits::Block + 'static,
+	B: client::backend::Backend<Block, Blake2Hasher> + Send + Sync + 'static,
+	E: CallExecutor<Block, Blake2Hasher> + Send + Sync + 'static,
+	RA: Send + Sync + 'static,
+	Client<B, E, Block, RA>: ProvideRuntimeApi,
+	<Client<B, E, Block, RA> as ProvideRuntimeApi>::Api: DryRunApi<Block>,
+{
 	fn system_name(&self) -> Result<String> {
 		Ok(self.info.impl_name.clone())
 	}
@@ -116,7 +136,7 @@ impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for Sy
 		})
 	}
 
-	fn system_peers(&self) -> Result<Vec<PeerInfo<B::Hash, <B::Header as HeaderT>::Number>>> {
+	fn system_peers(&self) -> Result<Vec<PeerInfo<Block::Hash, <Block::Header as HeaderT>::Number>>> {
 		Ok(self.sync.peers().into_iter().map(|(peer_id, p)| PeerInfo {
 			peer_id: peer_id.to_base58(),
 			roles: format!("{:?}", p.roles),
@@ -129,4 +149,17 @@ impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for Sy
 	fn system_network_state(&self) -> Result<network::NetworkState> {
 		Ok(self.sync.network_state())
 	}
+
+	fn system_dry_run(&self, extrinsic: Bytes, hash: Option<Block::Hash>) -> Result<DryRunResult> {
+		let extrinsic = Decode::decode(&mut &extrinsic[..]).ok_or(error::ErrorKind::BadFormat)?;
+		let hash = match hash {
+			Some(hash) => hash,
+			None => self.client.info()?.chain.best_hash,
+		};
+		// the runtime api initializes a child block of `hash` for the call, like the block
+		// builder would.
+		let api = self.client.runtime_api();
+		let (result, events) = api.dry_run(&BlockId::Hash(hash), extrinsic)?;
+		Ok(DryRunResult { result, events: events.into() })
+	}
 }
@@ -18,7 +18,10 @@ use super::*;
 
 use network::{self, ProtocolStatus, PeerId, PeerInfo as NetworkPeerInfo};
 use network::config::Roles;
-use test_client::runtime::Block;
+use parity_codec::Encode;
+use runtime_primitives::{ApplyError, ApplyOutcome};
+use test_client::{self, AccountKeyring, runtime::{AccountId, Block, RuntimeApi, Transfer}};
+use transaction_pool::{txpool::Pool, ChainApi};
 use assert_matches::assert_matches;
 use futures::sync::mpsc;
 
@@ -80,10 +83,10 @@ impl network::SyncProvider<Block> for Status {
 }
 
 
-fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
+fn api<T: Into<Option<Status>>>(sync: T) -> System<test_client::Backend, test_client::Executor, Block, RuntimeApi> {
 	let status = sync.into().unwrap_or_default();
 	let should_have_peers = !status.is_dev;
-	System::new(SystemInfo {
+	System::new(Arc::new(test_client::new()), SystemInfo {
 		impl_name: "testclient".into(),
 		impl_version: "0.2.0".into(),
 		chain_name: "testchain".into(),
@@ -213,3 +216,56 @@ fn system_network_state() {
 		}
 	);
 }
+
+#[test]
+fn system_dry_run_should_not_keep_changes() {
+	let api = api(None);
+	let transfer = |nonce| Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+		amount: 42,
+		nonce,
+	}.into_signed_tx().encode();
+
+	// both calls succeed since the nonce increment isn't persisted
+	for _ in 0..2 {
+		assert_eq!(
+			api.system_dry_run(transfer(0).into(), None).unwrap(),
+			DryRunResult { result: Ok(ApplyOutcome::Success), events: Bytes(Vec::new()) }
+		);
+	}
+	assert_eq!(api.system_dry_run(transfer(1).into(), None).unwrap().result, Err(ApplyError::Stale));
+}
+
+#[test]
+fn system_dry_run_should_reject_invalid_extrinsic() {
+	assert_matches!(
+		api(None).system_dry_run(vec![1, 2, 3].into(), None),
+		Err(error::Error(error::ErrorKind::BadFormat, _))
+	);
+}
+
+#[test]
+fn system_account_next_index_should_skip_pool_transactions() {
+	let client = Arc::new(test_client::new());
+	let pool = Arc::new(Pool::new(Default::default(), ChainApi::new(client.clone())));
+	let account = Account::<_, _, _, _, AccountId, u64>::new(client, pool.clone());
+	let transfer = |nonce| Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+		amount: 1,
+		nonce,
+	}.into_signed_tx();
+
+	assert_eq!(account.nonce(AccountKeyring::Alice.into()).unwrap(), 0);
+
+	// nonce `3` waits for the missing `2` in the future queue
+	for nonce in &[0, 1, 3] {
+		pool.submit_one(&BlockId::number(0), transfer(*nonce)).unwrap();
+	}
+	assert_eq!(account.nonce(AccountKeyring::Alice.into()).unwrap(), 2);
+	assert_eq!(account.nonce(AccountKeyring::Bob.into()).unwrap(), 0);
+
+	pool.submit_one(&BlockId::number(0), transfer(2)).unwrap();
+	assert_eq!(account.nonce(AccountKeyring::Alice.into()).unwrap(), 4);
+}
@@ -21,9 +21,10 @@ use serde::{Serialize, de::DeserializeOwned};
 use tokio::runtime::TaskExecutor;
 use crate::chain_spec::ChainSpec;
 use client_db;
-use client::{self, Client, runtime_api};
+use client::{self, Client, runtime_api, backend::Backend};
+use keystore::Store as Keystore;
 use crate::{error, Service, maybe_start_server};
-use consensus_common::{import_queue::ImportQueue, SelectChain};
+use consensus_common::{import_queue::ImportQueue, SelectChain, SharedKey};
 use network::{self, OnDemand, FinalityProofProvider};
 use substrate_executor::{NativeExecutor, NativeExecutionDispatch};
 use transaction_pool::txpool::{self, Options as TransactionPoolOptions, Pool as TransactionPool};
@@ -31,7 +32,7 @@ use runtime_primitives::{
 	BuildStorage, traits::{Block as BlockT, Header as HeaderT, ProvideRuntimeApi}, generic::BlockId
 };
 use crate::config::Configuration;
-use primitives::{Blake2Hasher, H256};
+use primitives::{Blake2Hasher, H256, ed25519};
 use rpc::{self, apis::system::SystemInfo};
 use parking_lot::Mutex;
 
@@ -128,6 +129,11 @@ pub type ComponentExtrinsic<C> = <ComponentBlock<C> as BlockT>::Extrinsic;
 /// Extrinsic pool API type for `Components`.
 pub type PoolApi<C> = <C as Components>::TransactionPoolApi;
 
+/// Offchain workers local storage type for `Components`.
+pub type ComponentOffchainStorage<C> = <
+	<C as Components>::Backend as client::backend::Backend<ComponentBlock<C>, Blake2Hasher>
+>::OffchainStorage;
+
 /// A set of traits for the runtime genesis config.
 pub trait RuntimeGenesis: Serialize + DeserializeOwned + BuildStorage {}
 impl<T: Serialize + DeserializeOwned + BuildStorage> RuntimeGenesis for T {}
@@ -144,14 +150,21 @@ pub trait StartRPC<C: Components> {
 		rpc_http: Option<SocketAddr>,
 		rpc_ws: Option<SocketAddr>,
 		rpc_cors: Option<Vec<String>>,
+		rpc_methods: rpc::RpcMethods,
+		rpc_extensions: Vec<Box<dyn rpc::RpcExtension>>,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
+		keystore: Arc<Keystore>,
+		authority_key: SharedKey<ed25519::Pair>,
 	) -> error::Result<Self::ServersHandle>;
 }
 
 impl<C: Components> StartRPC<Self> for C where
 	ComponentClient<C>: ProvideRuntimeApi,
-	<ComponentClient<C> as ProvideRuntimeApi>::Api: runtime_api::Metadata<ComponentBlock<C>>,
+	<ComponentClient<C> as ProvideRuntimeApi>::Api:
+		runtime_api::Metadata<ComponentBlock<C>>
+		+ runtime_api::DryRunApi<ComponentBlock<C>>
+		+ runtime_api::EventsApi<ComponentBlock<C>>,
 {
 	type ServersHandle = (Option<rpc::HttpServer>, Option<Mutex<rpc::WsServer>>);
 
@@ -163,31 +176,39 @@ impl<C: Components> StartRPC<Self> for C where
 		rpc_http: Option<SocketAddr>,
 		rpc_ws: Option<SocketAddr>,
 		rpc_cors: Option<Vec<String>>,
+		rpc_methods: rpc::RpcMethods,
+		rpc_extensions: Vec<Box<dyn rpc::RpcExtension>>,
 		task_executor: TaskExecutor,
 		transaction_pool: Arc<TransactionPool<C::TransactionPoolApi>>,
+		keystore: Arc<Keystore>,
+		authority_key: SharedKey<ed25519::Pair>,
 	) -> error::Result<Self::ServersHandle> {
-		let handler = || {
+		let handler = |address: &SocketAddr| {
 			let client = client.clone();
 			let subscriptions = rpc::apis::Subscriptions::new(task_executor.clone());
 			let chain = rpc::apis::chain::Chain::new(client.clone(), subscriptions.clone());
 			let state = rpc::apis::state::State::new(client.clone(), subscriptions.clone());
 			let author = rpc::apis::author::Author::new(
-				client.clone(), transaction_pool.clone(), subscriptions
+				client.clone(), transaction_pool.clone(), subscriptions, keystore.clone(), authority_key.clone(),
 			);
 			let system = rpc::apis::system::System::new(
-				rpc_system_info.clone(), network.clone(), should_have_peers
+				client.clone(), rpc_system_info.clone(), network.clone(), should_have_peers
 			);
-			rpc::rpc_handler::<ComponentBlock<C>, ComponentExHash<C>, _, _, _, _>(
+			let offchain = client.backend().offchain_storage().map(rpc::apis::offchain::Offchain::new);
+			rpc::rpc_handler::<ComponentBlock<C>, ComponentExHash<C>, _, _, _, _, _>(
 				state,
 				chain,
 				author,
 				system,
+				offchain,
+				&rpc_extensions,
+				rpc::RpcMiddleware::new(rpc_methods.deny_unsafe(address)),
 			)
 		};
 
 		Ok((
-			maybe_start_server(rpc_http, |address| rpc::start_http(address, rpc_cors.as_ref(), handler()))?,
-			maybe_start_server(rpc_ws, |address| rpc::start_ws(address, rpc_cors.as_ref(), handler()))?.map(Mutex::new),
+			maybe_start_server(rpc_http, |address| rpc::start_http(address, rpc_cors.as_ref(), handler(address)))?,
+			maybe_start_server(rpc_ws, |address| rpc::start_ws(address, rpc_cors.as_ref(), handler(address)))?.map(Mutex::new),
 		))
 	}
 }
@@ -243,21 +264,31 @@ impl<C: Components> MaintainTransactionPool<Self> for C where
 pub trait OffchainWorker<C: Components> {
 	fn offchain_workers(
 		number: &FactoryBlockNumber<C::Factory>,
-		offchain: &offchain::OffchainWorkers<ComponentClient<C>, ComponentBlock<C>>,
+		hash: <ComponentBlock<C> as BlockT>::Hash,
+		offchain: &offchain::OffchainWorkers<
+			ComponentClient<C>,
+			ComponentOffchainStorage<C>,
+			ComponentBlock<C>
+		>,
 		pool: &Arc<TransactionPool<C::TransactionPoolApi>>,
 	) -> error::Result<()>;
 }
 
 impl<C: Components> OffchainWorker<Self> for C where
-	ComponentClient<C>: ProvideRuntimeApi,
+	ComponentClient<C>: ProvideRuntimeApi + Send + Sync + 'static,
 	<ComponentClient<C> as ProvideRuntimeApi>::Api: offchain::OffchainWorkerApi<ComponentBlock<C>>,
 {
 	fn offchain_workers(
 		number: &FactoryBlockNumber<C::Factory>,
-		offchain: &offchain::OffchainWorkers<ComponentClient<C>, ComponentBlock<C>>,
+		hash: <ComponentBlock<C> as BlockT>::Hash,
+		offchain: &offchain::OffchainWorkers<
+			ComponentClient<C>,
+			ComponentOffchainStorage<C>,
+			ComponentBlock<C>
+		>,
 		pool: &Arc<TransactionPool<C::TransactionPoolApi>>,
 	) -> error::Result<()> {
-		Ok(offchain.on_block_imported(number, pool))
+		Ok(offchain.on_block_imported(number, hash, pool))
 	}
 }
 
@@ -333,6 +364,24 @@ pub trait ServiceFactory: 'static + Sized {
 		client: Arc<FullClient<Self>>, 
 	) -> Result<Self::SelectChain, error::Error>;
 
+	/// Build custom RPC extensions for the full client.
+	fn build_full_rpc_extensions(
+		_client: Arc<FullClient<Self>>,
+		_transaction_pool: Arc<TransactionPool<Self::FullTransactionPoolApi>>,
+		_network: Arc<network::SyncProvider<Self::Block>>,
+	) -> Result<Vec<Box<dyn rpc::RpcExtension>>, error::Error> {
+		Ok(Vec::new())
+	}
+
+	/// Build custom RPC extensions for the light client.
+	fn build_light_rpc_extensions(
+		_client: Arc<LightClient<Self>>,
+		_transaction_pool: Arc<TransactionPool<Self::LightTransactionPoolApi>>,
+		_network: Arc<network::SyncProvider<Self::Block>>,
+	) -> Result<Vec<Box<dyn rpc::RpcExtension>>, error::Error> {
+		Ok(Vec::new())
+	}
+
 	/// Build full service.
 	fn new_full(config: FactoryFullConfiguration<Self>, executor: TaskExecutor)
 		-> Result<Self::FullService, error::Error>;
@@ -428,6 +477,13 @@ pub trait Components: Sized + 'static {
 		config: &mut FactoryFullConfiguration<Self::Factory>,
 		client: Arc<ComponentClient<Self>>
 	) -> Result<Option<Self::SelectChain>, error::Error>;
+
+	/// Build custom RPC extensions.
+	fn build_rpc_extensions(
+		client: Arc<ComponentClient<Self>>,
+		transaction_pool: Arc<TransactionPool<Self::TransactionPoolApi>>,
+		network: Arc<network::SyncProvider<FactoryBlock<Self::Factory>>>,
+	) -> Result<Vec<Box<dyn rpc::RpcExtension>>, error::Error>;
 }
 
 /// A struct that implement `Components` for the full client.
@@ -520,6 +576,14 @@ impl<Factory: ServiceFactory> Components for FullComponents<Factory> {
 		Self::Factory::build_select_chain(config, client).map(Some)
 	}
 
+	fn build_rpc_extensions(
+		client: Arc<ComponentClient<Self>>,
+		transaction_pool: Arc<TransactionPool<Self::TransactionPoolApi>>,
+		network: Arc<network::SyncProvider<FactoryBlock<Self::Factory>>>,
+	) -> Result<Vec<Box<dyn rpc::RpcExtension>>, error::Error> {
+		Factory::build_full_rpc_extensions(client, transaction_pool, network)
+	}
+
 	fn build_finality_proof_provider(
 		client: Arc<ComponentClient<Self>>
 	) -> Result<Option<Arc<FinalityProofProvider<<Self::Factory as ServiceFactory>::Block>>>, error::Error> {
@@ -614,6 +678,14 @@ impl<Factory: ServiceFactory> Components for LightComponents<Factory> {
 	) -> Result<Option<Self::SelectChain>, error::Error> {
 		Ok(None)
 	}
+
+	fn build_rpc_extensions(
+		client: Arc<ComponentClient<Self>>,
+		transaction_pool: Arc<TransactionPool<Self::TransactionPoolApi>>,
+		network: Arc<network::SyncProvider<FactoryBlock<Self::Factory>>>,
+	) -> Result<Vec<Box<dyn rpc::RpcExtension>>, error::Error> {
+		Factory::build_light_rpc_extensions(client, transaction_pool, network)
+	}
 }
 
 #[cfg(test)]
@@ -22,6 +22,7 @@ use crate::chain_spec::ChainSpec;
 pub use client::ExecutionStrategies;
 pub use client_db::PruningMode;
 pub use network::config::{NetworkConfiguration, Roles};
+pub use rpc::RpcMethods;
 use runtime_primitives::BuildStorage;
 use serde::{Serialize, de::DeserializeOwned};
 use target_info::Target;
@@ -44,6 +45,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub network: NetworkConfiguration,
 	/// Path to key files.
 	pub keystore_path: String,
+	/// Password the key files are encrypted with. `None` for an empty password.
+	pub keystore_password: Option<String>,
 	/// Path to the database.
 	pub database_path: String,
 	/// Cache Size for internal database in MiB
@@ -68,6 +71,10 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub rpc_ws: Option<SocketAddr>,
 	/// CORS settings for HTTP & WS servers. `None` if all origins are allowed.
 	pub rpc_cors: Option<Vec<String>>,
+	/// RPC methods to expose.
+	pub rpc_methods: RpcMethods,
+	/// Prometheus metrics server binding address. `None` if disabled.
+	pub prometheus_endpoint: Option<SocketAddr>,
 	/// Telemetry service URL. `None` if disabled.
 	pub telemetry_endpoints: Option<TelemetryEndpoints>,
 	/// The default number of 64KB pages to allocate for Wasm execution
@@ -93,6 +100,7 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			transaction_pool: Default::default(),
 			network: Default::default(),
 			keystore_path: Default::default(),
+			keystore_password: None,
 			database_path: Default::default(),
 			database_cache_size: Default::default(),
 			state_cache_size: Default::default(),
@@ -103,6 +111,8 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			rpc_http: None,
 			rpc_ws: None,
 			rpc_cors: Some(vec![]),
+			rpc_methods: Default::default(),
+			prometheus_endpoint: None,
 			telemetry_endpoints: None,
 			default_heap_pages: None,
 			offchain_worker: Default::default(),
@@ -31,14 +31,14 @@ use std::collections::HashMap;
 use futures::sync::mpsc;
 use parking_lot::Mutex;
 
-use client::BlockchainEvents;
+use client::{BlockchainEvents, backend::Backend};
 use exit_future::Signal;
 use futures::prelude::*;
 use inherents::pool::InherentsPool;
 use keystore::Store as Keystore;
 use log::{info, warn, debug};
 use parity_codec::{Encode, Decode};
-use primitives::Pair;
+use primitives::{Pair, ed25519};
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{Header, As};
 use substrate_executor::NativeExecutor;
@@ -52,19 +52,21 @@ pub use transaction_pool::txpool::{
 };
 use client::runtime_api::BlockT;
 pub use client::FinalityNotifications;
+pub use consensus_common::SharedKey;
 
 pub use components::{ServiceFactory, FullBackend, FullExecutor, LightBackend,
 	LightExecutor, Components, PoolApi, ComponentClient,
 	ComponentBlock, FullClient, LightClient, FullComponents, LightComponents,
 	CodeExecutor, NetworkService, FactoryChainSpec, FactoryBlock,
 	FactoryFullConfiguration, RuntimeGenesis, FactoryGenesis,
-	ComponentExHash, ComponentExtrinsic, FactoryExtrinsic
+	ComponentExHash, ComponentExtrinsic, FactoryExtrinsic, ComponentOffchainStorage,
 };
 use components::{StartRPC, MaintainTransactionPool, OffchainWorker};
+pub use rpc::{RpcExtension, RpcHandler};
 #[doc(hidden)]
 pub use std::{ops::Deref, result::Result, sync::Arc};
 #[doc(hidden)]
-pub use network::{FinalityProofProvider, OnDemand};
+pub use network::{FinalityProofProvider, OnDemand, SyncProvider};
 #[doc(hidden)]
 pub use tokio::runtime::TaskExecutor;
 
@@ -77,14 +79,19 @@ pub struct Service<Components: components::Components> {
 	network: Option<Arc<components::NetworkService<Components::Factory>>>,
 	transaction_pool: Arc<TransactionPool<Components::TransactionPoolApi>>,
 	inherents_pool: Arc<InherentsPool<ComponentExtrinsic<Components>>>,
-	keystore: Keystore,
+	keystore: Arc<Keystore>,
+	authority_key: SharedKey<ed25519::Pair>,
 	exit: ::exit_future::Exit,
 	signal: Option<Signal>,
 	/// Configuration of this Service
 	pub config: FactoryFullConfiguration<Components::Factory>,
 	_rpc: Box<::std::any::Any + Send + Sync>,
 	_telemetry: Option<Arc<tel::Telemetry>>,
-	_offchain_workers: Option<Arc<offchain::OffchainWorkers<ComponentClient<Components>, ComponentBlock<Components>>>>,
+	_offchain_workers: Option<Arc<offchain::OffchainWorkers<
+		ComponentClient<Components>,
+		ComponentOffchainStorage<Components>,
+		ComponentBlock<Components>>
+	>>,
 	_telemetry_on_connect_sinks: Arc<Mutex<Vec<mpsc::UnboundedSender<()>>>>,
 }
 
@@ -131,7 +138,10 @@ impl<Components: components::Components> Service<Components> {
 		// Create client
 		let executor = NativeExecutor::new(config.default_heap_pages);
 
-		let mut keystore = Keystore::open(config.keystore_path.as_str().into())?;
+		let mut keystore = Keystore::open(
+			config.keystore_path.as_str().into(),
+			config.keystore_password.clone().unwrap_or_default(),
+		)?;
 
 		// This is meant to be for testing only
 		// FIXME #1063 remove this
@@ -139,16 +149,37 @@ impl<Components: components::Components> Service<Components> {
 			keystore.generate_from_seed(seed)?;
 		}
 		// Keep the public key for telemetry
-		let public_key = match keystore.contents()?.get(0) {
-			Some(public_key) => public_key.clone(),
+		let public_key = match keystore.authority_key()? {
+			Some(public_key) => Some(public_key),
 			None => {
-				let key = keystore.generate("")?;
-				let public_key = key.public();
-				info!("Generated a new keypair: {:?}", public_key);
-
-				public_key
+				let stored = keystore.contents()?;
+				match stored.len() {
+					0 => {
+						let key = keystore.generate()?;
+						let public_key = key.public();
+						info!("Generated a new keypair: {:?}", public_key);
+						keystore.set_authority_key(&public_key)?;
+
+						Some(public_key)
+					},
+					// keystores written before the authority key was selected explicitly
+					1 => {
+						keystore.set_authority_key(&stored[0])?;
+						Some(stored[0].clone())
+					},
+					_ => {
+						warn!("No authority key selected among {} stored keys, use `author_rotateKeys`", stored.len());
+						None
+					},
+				}
 			}
 		};
+		let authority_key = SharedKey::new(match public_key {
+			Some(ref public_key) if config.roles == Roles::AUTHORITY =>
+				keystore.load(public_key).ok().map(Arc::new),
+			_ => None,
+		});
+		let keystore = Arc::new(keystore);
 
 		let (client, on_demand) = Components::build_client(&config, executor)?;
 		let select_chain = Components::build_select_chain(&mut config, client.clone())?;
@@ -204,14 +235,28 @@ impl<Components: components::Components> Service<Components> {
 		}
 
 		let inherents_pool = Arc::new(InherentsPool::default());
-		let offchain_workers =  if config.offchain_worker {
-			Some(Arc::new(offchain::OffchainWorkers::new(
-				client.clone(),
-				inherents_pool.clone(),
-				task_executor.clone(),
-			)))
-		} else {
-			None
+		let offchain_storage = client.backend().offchain_storage();
+		let offchain_workers = match (config.offchain_worker, offchain_storage) {
+			(true, Some(db)) => {
+				let workers = offchain::OffchainWorkers::new(
+					client.clone(),
+					db,
+					inherents_pool.clone(),
+					task_executor.clone(),
+				);
+				// only authorities expose their keys to the offchain workers.
+				let workers = if config.roles == Roles::AUTHORITY {
+					workers.with_key_provider(keystore.clone())
+				} else {
+					workers
+				};
+				Some(Arc::new(workers))
+			},
+			(true, None) => {
+				warn!("Offchain workers disabled, due to lack of offchain storage support in backend.");
+				None
+			},
+			_ => None,
 		};
 
 		{
@@ -237,9 +282,18 @@ impl<Components: components::Components> Service<Components> {
 						).map_err(|e| warn!("Pool error processing new block: {:?}", e))?;
 					}
 
-					if let (Some(txpool), Some(offchain)) = (txpool.upgrade(), offchain.as_ref().and_then(|o| o.upgrade())) {
+					// the workers only run on top of the chain, not for blocks imported while catching up.
+					let is_major_syncing = network.upgrade().map_or(false, |network| network.is_major_syncing());
+					let offchain = if notification.is_new_best && !is_major_syncing {
+						offchain.as_ref().and_then(|o| o.upgrade())
+					} else {
+						None
+					};
+					if let (Some(txpool), Some(offchain)) = (txpool.upgrade(), offchain) {
+						// the workers run on their own thread, only the block is handed over here.
 						Components::RuntimeServices::offchain_workers(
 							&number,
+							notification.hash,
 							&offchain,
 							&txpool,
 						).map_err(|e| warn!("Offchain workers error processing new block: {:?}", e))?;
@@ -322,18 +376,28 @@ impl<Components: components::Components> Service<Components> {
 			impl_version: config.impl_version.into(),
 			properties: config.chain_spec.properties(),
 		};
+		let rpc_extensions = Components::build_rpc_extensions(
+			client.clone(), transaction_pool.clone(), network.clone(),
+		)?;
 		let rpc = Components::RuntimeServices::start_rpc(
 			client.clone(), network.clone(), has_bootnodes, system_info, config.rpc_http,
-			config.rpc_ws, config.rpc_cors.clone(), task_executor.clone(), transaction_pool.clone(),
+			config.rpc_ws, config.rpc_cors.clone(), config.rpc_methods, rpc_extensions,
+			task_executor.clone(), transaction_pool.clone(), keystore.clone(), authority_key.clone(),
 		)?;
 
+		// Prometheus
+		if let Some(address) = config.prometheus_endpoint {
+			let server = metrics::init_prometheus(address)?;
+			task_executor.spawn(server.select(exit.clone()).then(|_| Ok(())));
+		}
+
 		let telemetry_connection_sinks: Arc<Mutex<Vec<mpsc::UnboundedSender<()>>>> = Default::default();
 
 		// Telemetry
 		let telemetry = config.telemetry_endpoints.clone().map(|endpoints| {
 			let is_authority = config.roles == Roles::AUTHORITY;
 			let network_id = network.local_peer_id().to_base58();
-			let pubkey = format!("{}", public_key);
+			let pubkey = public_key.as_ref().map(|public_key| format!("{}", public_key)).unwrap_or_default();
 			let name = config.name.clone();
 			let impl_name = config.impl_name.to_owned();
 			let version = version.clone();
@@ -368,6 +432,7 @@ impl<Components: components::Components> Service<Components> {
 			inherents_pool,
 			signal: Some(signal),
 			keystore,
+			authority_key,
 			config,
 			exit,
 			_rpc: Box::new(rpc),
@@ -378,16 +443,11 @@ impl<Components: components::Components> Service<Components> {
 	}
 
 	/// give the authority key, if we are an authority and have a key
-	pub fn authority_key(&self) -> Option<primitives::ed25519::Pair> {
-		if self.config.roles != Roles::AUTHORITY { return None }
-		let keystore = &self.keystore;
-		if let Ok(Some(Ok(key))) =  keystore.contents().map(|keys| keys.get(0)
-				.map(|k| keystore.load(k, "")))
-		{
-			Some(key)
-		} else {
-			None
-		}
+	///
+	/// The handle is updated when the keys are rotated through the RPC, so
+	/// consensus engines should read it whenever they need to sign.
+	pub fn authority_key(&self) -> SharedKey<ed25519::Pair> {
+		self.authority_key.clone()
 	}
 
 	/// return a shared instance of Telemetry (if enabled)
@@ -538,12 +598,15 @@ impl<C: Components> network::TransactionPool<ComponentExHash<C>, ComponentBlock<
 /// In these block it is required to write a closure that takes the same number of arguments,
 /// the corresponding function in the `ServiceFactory` trait provides.
 ///
+/// The optional `FullRpcExtensions` and `LightRpcExtensions` parameters may be given last to
+/// register custom RPC methods, their closures get the client, the transaction pool and the network.
+///
 /// # Example
 ///
 /// ```
 /// # use substrate_service::{
 /// # 	construct_service_factory, Service, FullBackend, FullExecutor, LightBackend, LightExecutor,
-/// # 	FullComponents, LightComponents, FactoryFullConfiguration, FullClient, TaskExecutor
+/// # 	FullComponents, LightComponents, FactoryFullConfiguration, FullClient, TaskExecutor, SharedKey,
 /// # };
 /// # use transaction_pool::{self, txpool::{Pool as TransactionPool}};
 /// # use network::construct_simple_protocol;
@@ -595,7 +658,7 @@ impl<C: Components> network::TransactionPool<ComponentExHash<C>, ComponentBlock<
 /// 			{ |config, executor| <FullComponents<Factory>>::new(config, executor) },
 /// 		// Setup as Consensus Authority (if the role and key are given)
 /// 		AuthoritySetup = {
-/// 			|service: Self::FullService, executor: TaskExecutor, key: Option<Arc<ed25519::Pair>>| {
+/// 			|service: Self::FullService, executor: TaskExecutor, key: SharedKey<ed25519::Pair>| {
 /// 				Ok(service)
 /// 			}},
 /// 		LightService = LightComponents<Self>
@@ -611,6 +674,8 @@ impl<C: Components> network::TransactionPool<ComponentExHash<C>, ComponentBlock<
 /// 		FinalityProofProvider = { |client: Arc<FullClient<Self>>| {
 /// 				Ok(Some(Arc::new(grandpa::FinalityProofProvider::new(client.clone(), client)) as _))
 /// 			}},
+/// 		// Register custom RPC methods (optional)
+/// 		FullRpcExtensions = { |client, transaction_pool, network| Ok(Vec::new()) },
 /// 	}
 /// }
 /// ```
@@ -637,6 +702,8 @@ macro_rules! construct_service_factory {
 			SelectChain = $select_chain:ty
 				{ $( $select_chain_init:tt )* },
 			FinalityProofProvider = { $( $finality_proof_provider_init:tt )* },
+			$( FullRpcExtensions = { $( $full_rpc_extensions_init:tt )* }, )?
+			$( LightRpcExtensions = { $( $light_rpc_extensions_init:tt )* }, )?
 		}
 	) => {
 		$( #[$attr] )*
@@ -708,6 +775,26 @@ macro_rules! construct_service_factory {
 				( $( $finality_proof_provider_init )* ) (client)
 			}
 
+			$(
+				fn build_full_rpc_extensions(
+					client: $crate::Arc<$crate::FullClient<Self>>,
+					transaction_pool: $crate::Arc<$crate::TransactionPool<Self::FullTransactionPoolApi>>,
+					network: $crate::Arc<$crate::SyncProvider<Self::Block>>,
+				) -> $crate::Result<Vec<Box<dyn $crate::RpcExtension>>, $crate::Error> {
+					( $( $full_rpc_extensions_init )* ) (client, transaction_pool, network)
+				}
+			)?
+
+			$(
+				fn build_light_rpc_extensions(
+					client: $crate::Arc<$crate::LightClient<Self>>,
+					transaction_pool: $crate::Arc<$crate::TransactionPool<Self::LightTransactionPoolApi>>,
+					network: $crate::Arc<$crate::SyncProvider<Self::Block>>,
+				) -> $crate::Result<Vec<Box<dyn $crate::RpcExtension>>, $crate::Error> {
+					( $( $light_rpc_extensions_init )* ) (client, transaction_pool, network)
+				}
+			)?
+
 			fn new_light(
 				config: $crate::FactoryFullConfiguration<Self>,
 				executor: $crate::TaskExecutor
@@ -722,7 +809,7 @@ macro_rules! construct_service_factory {
 			) -> Result<Self::FullService, $crate::Error>
 			{
 				( $( $full_service_init )* ) (config, executor.clone()).and_then(|service| {
-					let key = (&service).authority_key().map(Arc::new);
+					let key = (&service).authority_key();
 					($( $authority_setup )*)(service, executor, key)
 				})
 			}
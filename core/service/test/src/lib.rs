@@ -110,6 +110,7 @@ fn node_config<F: ServiceFactory> (
 		transaction_pool: Default::default(),
 		network: network_config,
 		keystore_path: root.join("key").to_str().unwrap().into(),
+		keystore_password: None,
 		database_path: root.join("db").to_str().unwrap().into(),
 		database_cache_size: None,
 		state_cache_size: 16777216,
@@ -122,6 +123,8 @@ fn node_config<F: ServiceFactory> (
 		rpc_http: None,
 		rpc_ws: None,
 		rpc_cors: None,
+		rpc_methods: Default::default(),
+		prometheus_endpoint: None,
 		telemetry_endpoints: None,
 		default_heap_pages: None,
 		offchain_worker: false,
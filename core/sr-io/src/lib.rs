@@ -33,6 +33,9 @@ use rstd::vec::Vec;
 pub use codec;
 
 pub use primitives::Blake2Hasher;
+pub use primitives::offchain::{
+	Timestamp, HttpRequestId, HttpRequestStatus, HttpError, CryptoKind, SIGNING_CONTEXT,
+};
 
 /// Error verifying ECDSA signature
 pub enum EcdsaVerifyError {
@@ -232,6 +235,117 @@ export_api! {
 		/// 1. scheduled to be included in the next produced block (inherent)
 		/// 2. added to the pool and propagated (transaction)
 		fn submit_extrinsic<T: codec::Encode>(data: &T);
+
+		/// Sets a value in the local storage.
+		///
+		/// Note this storage is not part of the consensus, it's only accessible by
+		/// offchain worker tasks running on the same machine. It IS persisted between runs.
+		fn local_storage_set(key: &[u8], value: &[u8]);
+
+		/// Sets a value in the local storage if it matches current value.
+		///
+		/// Since multiple offchain workers may be running concurrently, to prevent
+		/// data races use CAS to coordinate between them.
+		///
+		/// Returns `true` if the value has been set, `false` otherwise.
+		fn local_storage_compare_and_set(key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool;
+
+		/// Gets a value from the local storage.
+		///
+		/// If the value does not exist in the storage `None` will be returned.
+		fn local_storage_get(key: &[u8]) -> Option<Vec<u8>>;
+
+		/// Initiaties a http request given HTTP verb and the URL.
+		///
+		/// Meta is a future-reserved field containing additional, parity-codec encoded parameters.
+		/// Returns the id of newly started request.
+		fn http_request_start(
+			method: &str,
+			uri: &str,
+			meta: &[u8]
+		) -> Result<HttpRequestId, ()>;
+
+		/// Append header to the request.
+		fn http_request_add_header(
+			request_id: HttpRequestId,
+			name: &str,
+			value: &str
+		) -> Result<(), ()>;
+
+		/// Write a chunk of request body.
+		///
+		/// Writing an empty chunks finalises the request.
+		/// Passing `None` as deadline blocks forever.
+		///
+		/// Returns an error in case deadline is reached or the chunk couldn't be written.
+		fn http_request_write_body(
+			request_id: HttpRequestId,
+			chunk: &[u8],
+			deadline: Option<Timestamp>
+		) -> Result<(), HttpError>;
+
+		/// Block and wait for the responses for given requests.
+		///
+		/// Returns a vector of request statuses (the len is the same as ids).
+		/// Note that if deadline is not provided the method will block indefinitely,
+		/// otherwise unready responses will produce `DeadlineReached` status.
+		///
+		/// Passing `None` as deadline blocks forever.
+		fn http_response_wait(
+			ids: &[HttpRequestId],
+			deadline: Option<Timestamp>
+		) -> Vec<HttpRequestStatus>;
+
+		/// Read all response headers.
+		///
+		/// Returns a vector of pairs `(HeaderKey, HeaderValue)`.
+		/// NOTE response headers have to be read before response body.
+		fn http_response_headers(
+			request_id: HttpRequestId
+		) -> Vec<(Vec<u8>, Vec<u8>)>;
+
+		/// Read a chunk of body response to given buffer.
+		///
+		/// Returns the number of bytes written or an error in case a deadline
+		/// is reached or server closed the connection.
+		/// If `0` is returned it means that the response has been fully consumed
+		/// and the `request_id` is now invalid.
+		/// NOTE this implies that response headers must be read before draining the body.
+		/// Passing `None` as a deadline blocks forever.
+		fn http_response_read_body(
+			request_id: HttpRequestId,
+			buffer: &mut [u8],
+			deadline: Option<Timestamp>
+		) -> Result<usize, HttpError>;
+
+		/// Returns the raw public keys of the local authority keys of given crypto kind.
+		///
+		/// The list is empty if the node is not an authority.
+		fn authority_pubkeys(kind: CryptoKind) -> Vec<Vec<u8>>;
+
+		/// Sign a message with the local authority key matching given public key.
+		///
+		/// The signature covers `SIGNING_CONTEXT` followed by `data`.
+		/// Returns `None` if the key is not available.
+		fn sign(kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>>;
+
+		/// Verifies a signature of a message made with given public key.
+		///
+		/// Like with `sign`, the message is prefixed with `SIGNING_CONTEXT`.
+		/// Returns `true` if the signature is valid, `false` otherwise.
+		fn verify(kind: CryptoKind, public: &[u8], msg: &[u8], signature: &[u8]) -> bool;
+
+		/// Returns current UNIX timestamp (in millis)
+		fn timestamp() -> Timestamp;
+
+		/// Pause the execution until `deadline` is reached.
+		fn sleep_until(deadline: Timestamp);
+
+		/// Returns a random seed.
+		///
+		/// This is a truly random non deterministic seed generated by host environment.
+		/// Obviously fine in the off-chain worker context.
+		fn random_seed() -> [u8; 32];
 	}
 }
 
@@ -28,7 +28,7 @@ pub use substrate_state_machine::{
 };
 
 use environmental::environmental;
-use primitives::{hexdisplay::HexDisplay, H256};
+use primitives::{hexdisplay::HexDisplay, H256, OffchainExt};
 
 #[cfg(feature = "std")]
 use std::collections::HashMap;
@@ -258,6 +258,127 @@ impl OffchainApi for () {
 			.expect("submit_extrinsic can be called only in offchain worker context")
 		).expect("submit_extrinsic cannot be called outside of an Externalities-provided environment.")
 	}
+
+	fn local_storage_set(key: &[u8], value: &[u8]) {
+		with_offchain(|ext| {
+			ext.local_storage_set(key, value)
+		}, "local_storage_set can be called only in the offchain worker context")
+	}
+
+	fn local_storage_compare_and_set(key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool {
+		with_offchain(|ext| {
+			ext.local_storage_compare_and_set(key, old_value, new_value)
+		}, "local_storage_compare_and_set can be called only in the offchain worker context")
+	}
+
+	fn local_storage_get(key: &[u8]) -> Option<Vec<u8>> {
+		with_offchain(|ext| {
+			ext.local_storage_get(key)
+		}, "local_storage_get can be called only in the offchain worker context")
+	}
+
+	fn http_request_start(
+		method: &str,
+		uri: &str,
+		meta: &[u8]
+	) -> Result<HttpRequestId, ()> {
+		with_offchain(|ext| {
+			ext.http_request_start(method, uri, meta)
+		}, "http_request_start can be called only in the offchain worker context")
+	}
+
+	fn http_request_add_header(
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str
+	) -> Result<(), ()> {
+		with_offchain(|ext| {
+			ext.http_request_add_header(request_id, name, value)
+		}, "http_request_add_header can be called only in the offchain worker context")
+	}
+
+	fn http_request_write_body(
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), HttpError> {
+		with_offchain(|ext| {
+			ext.http_request_write_body(request_id, chunk, deadline)
+		}, "http_request_write_body can be called only in the offchain worker context")
+	}
+
+	fn http_response_wait(
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>
+	) -> Vec<HttpRequestStatus> {
+		with_offchain(|ext| {
+			ext.http_response_wait(ids, deadline)
+		}, "http_response_wait can be called only in the offchain worker context")
+	}
+
+	fn http_response_headers(
+		request_id: HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		with_offchain(|ext| {
+			ext.http_response_headers(request_id)
+		}, "http_response_headers can be called only in the offchain worker context")
+	}
+
+	fn http_response_read_body(
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>
+	) -> Result<usize, HttpError> {
+		with_offchain(|ext| {
+			ext.http_response_read_body(request_id, buffer, deadline)
+		}, "http_response_read_body can be called only in the offchain worker context")
+	}
+
+	fn authority_pubkeys(kind: CryptoKind) -> Vec<Vec<u8>> {
+		with_offchain(|ext| {
+			ext.authority_pubkeys(kind)
+		}, "authority_pubkeys can be called only in the offchain worker context")
+	}
+
+	fn sign(kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		with_offchain(|ext| {
+			ext.sign(kind, public, data)
+		}, "sign can be called only in the offchain worker context")
+	}
+
+	fn verify(kind: CryptoKind, public: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+		with_offchain(|ext| {
+			ext.verify(kind, public, msg, signature)
+		}, "verify can be called only in the offchain worker context")
+	}
+
+	fn timestamp() -> Timestamp {
+		with_offchain(|ext| {
+			ext.timestamp()
+		}, "timestamp can be called only in the offchain worker context")
+	}
+
+	fn sleep_until(deadline: Timestamp) {
+		with_offchain(|ext| {
+			ext.sleep_until(deadline)
+		}, "sleep_until can be called only in the offchain worker context")
+	}
+
+	fn random_seed() -> [u8; 32] {
+		with_offchain(|ext| {
+			ext.random_seed()
+		}, "random_seed can be called only in the offchain worker context")
+	}
+}
+
+/// Execute the given closure with the offchain externalities, panicking with `msg`
+/// if they are not available.
+fn with_offchain<R, F: FnOnce(&mut dyn OffchainExt) -> R>(f: F, msg: &'static str) -> R {
+	ext::with(|ext| ext
+		.offchain()
+		.map(|ext| f(ext))
+		.expect(msg)
+	).expect("offchain-worker functions cannot be called outside of an Externalities-provided environment.")
 }
 
 impl Api for () {}
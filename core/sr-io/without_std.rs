@@ -333,6 +333,188 @@ pub mod ext {
 
 		/// Submit extrinsic.
 		fn ext_submit_extrinsic(data: *const u8, len: u32);
+
+		/// Write a key value pair to the local storage.
+		fn ext_local_storage_set(key: *const u8, key_len: u32, value: *const u8, value_len: u32);
+
+		/// Read a value from local storage.
+		///
+		/// # Returns
+		///
+		/// - `0` if the value has not been found, the `value_len` is set to `u32::max_value`.
+		/// - Otherwise, pointer to the value in memory. `value_len` contains the length of the value.
+		fn ext_local_storage_get(key: *const u8, key_len: u32, value_len: *mut u32) -> *mut u8;
+
+		/// Set the value in local storage if it matches current value.
+		///
+		/// `old_value_len` set to `u32::max_value` means that the value must not be present.
+		///
+		/// # Returns
+		///
+		/// - `0` in case the value has been set
+		/// - `1` if the `old_value` didn't match
+		fn ext_local_storage_compare_and_set(
+			key: *const u8,
+			key_len: u32,
+			old_value: *const u8,
+			old_value_len: u32,
+			new_value: *const u8,
+			new_value_len: u32
+		) -> u32;
+
+		/// Initiaties a http request.
+		///
+		/// `meta` is parity-codec encoded additional parameters to the request (like redirection policy,
+		/// timeouts, certificates policy, etc). The format is not yet specified and the field is currently
+		/// only reserved for future use.
+		///
+		/// # Returns
+		///
+		/// `RequestId(u16)` of initiated request, any value beyond `u16::max_value`
+		/// signifies an error.
+		fn ext_http_request_start(
+			method: *const u8,
+			method_len: u32,
+			url: *const u8,
+			url_len: u32,
+			meta: *const u8,
+			meta_len: u32
+		) -> u32;
+
+		/// Add a header to the request.
+		///
+		/// # Returns
+		///
+		/// - `0` if successful (and the request id exists)
+		/// - nonzero otherwise
+		fn ext_http_request_add_header(
+			request_id: u32,
+			name: *const u8,
+			name_len: u32,
+			value: *const u8,
+			value_len: u32
+		) -> u32;
+
+		/// Write a chunk of request body.
+		///
+		/// Writing an empty chunks finalises the request.
+		/// Passing `0` as `has_deadline` blocks forever, `deadline` is ignored then.
+		///
+		/// # Returns
+		///
+		/// - `0` if successful,
+		/// - nonzero otherwise (see HttpError for the codes)
+		fn ext_http_request_write_body(
+			request_id: u32,
+			chunk: *const u8,
+			chunk_len: u32,
+			has_deadline: u32,
+			deadline: u64
+		) -> u32;
+
+		/// Block and wait for the responses for given requests.
+		///
+		/// Note that if `has_deadline` is 0 the method will block indefinitely,
+		/// otherwise unready responses will produce `DeadlineReached` status.
+		/// (see #primitives::offchain::HttpRequestStatus)
+		///
+		/// Make sure that `statuses` have the same length as ids.
+		fn ext_http_response_wait(
+			ids: *const u32,
+			ids_len: u32,
+			statuses: *mut u32,
+			has_deadline: u32,
+			deadline: u64
+		);
+
+		/// Read all response headers.
+		///
+		/// Note the headers are only available before response body is fully consumed.
+		///
+		/// # Returns
+		///
+		/// - A pointer to parity-codec encoded vector of pairs `(HeaderKey, HeaderValue)`.
+		/// - In case invalid `id` is passed it returns a pointer to parity-encoded empty vector.
+		fn ext_http_response_headers(
+			request_id: u32,
+			written_out: *mut u32
+		) -> *mut u8;
+
+		/// Read a chunk of body response to given buffer.
+		///
+		/// Passing `0` as `has_deadline` blocks forever, `deadline` is ignored then.
+		///
+		/// # Returns
+		///
+		/// The number of bytes written if successful,
+		/// - if it's `0` it means response has been fully consumed,
+		/// - if it's greater than `u32::max_value() - 255` it means reading body failed.
+		///
+		/// In case of failure, the error code should be mapped to `HttpError`
+		/// in a following manner:
+		/// - `u32::max_value()` HttpError code 1 (DeadlineReached)
+		/// - `u32::max_value() - 1` HttpError code 2 (IoError)
+		/// The rest is reserved for potential future errors.
+		fn ext_http_response_read_body(
+			request_id: u32,
+			buffer: *mut u8,
+			buffer_len: u32,
+			has_deadline: u32,
+			deadline: u64
+		) -> u32;
+
+		/// Get the local authority keys of given crypto kind.
+		///
+		/// # Returns
+		///
+		/// A pointer to parity-codec encoded vector of raw public keys.
+		/// `written_out` contains the length of the encoded vector.
+		fn ext_authority_pubkeys(kind: u32, written_out: *mut u32) -> *mut u8;
+
+		/// Sign a message with the local authority key matching given public key.
+		///
+		/// # Returns
+		///
+		/// - `0` if the key is not available, the `signature_len` is set to `u32::max_value`.
+		/// - Otherwise, pointer to the signature in memory. `signature_len` contains the length of the signature.
+		fn ext_sign(
+			kind: u32,
+			public: *const u8,
+			public_len: u32,
+			data: *const u8,
+			data_len: u32,
+			signature_len: *mut u32
+		) -> *mut u8;
+
+		/// Verify a signature of a message made with given public key.
+		///
+		/// # Returns
+		///
+		/// - `0` if the signature is valid
+		/// - nonzero otherwise
+		fn ext_verify(
+			kind: u32,
+			public: *const u8,
+			public_len: u32,
+			msg: *const u8,
+			msg_len: u32,
+			signature: *const u8,
+			signature_len: u32
+		) -> u32;
+
+		/// Returns current UNIX timestamp (milliseconds)
+		fn ext_timestamp() -> u64;
+
+		/// Pause execution until given timestamp (milliseconds; `deadline`) is reached.
+		///
+		/// The deadline is obtained by querying the current timestamp via `ext_timestamp`
+		/// and then adding some time to it.
+		fn ext_sleep_until(deadline: u64);
+
+		/// Generate a random seed
+		///
+		/// `data` has to be a pointer to a slice of 32 bytes.
+		fn ext_random_seed(data: *mut u8);
 	}
 }
 
@@ -630,6 +812,255 @@ impl OffchainApi for () {
 			ext_submit_extrinsic.get()(encoded_data.as_ptr(), encoded_data.len() as u32)
 		}
 	}
+
+	fn local_storage_set(key: &[u8], value: &[u8]) {
+		unsafe {
+			ext_local_storage_set.get()(
+				key.as_ptr(),
+				key.len() as u32,
+				value.as_ptr(),
+				value.len() as u32,
+			);
+		}
+	}
+
+	fn local_storage_compare_and_set(key: &[u8], old_value: Option<&[u8]>, new_value: &[u8]) -> bool {
+		let (old_value_ptr, old_value_len) = match old_value {
+			Some(old_value) => (old_value.as_ptr(), old_value.len() as u32),
+			None => (core::ptr::null(), u32::max_value()),
+		};
+		unsafe {
+			ext_local_storage_compare_and_set.get()(
+				key.as_ptr(),
+				key.len() as u32,
+				old_value_ptr,
+				old_value_len,
+				new_value.as_ptr(),
+				new_value.len() as u32,
+			) == 0
+		}
+	}
+
+	fn local_storage_get(key: &[u8]) -> Option<Vec<u8>> {
+		let mut length: u32 = 0;
+		unsafe {
+			let ptr = ext_local_storage_get.get()(
+				key.as_ptr(),
+				key.len() as u32,
+				&mut length,
+			);
+			if length == u32::max_value() {
+				None
+			} else {
+				// Invariants required by Vec::from_raw_parts are not formally fulfilled.
+				// We don't allocate via String/Vec<T>, but use a custom allocator instead.
+				// See #300 for more details.
+				Some(<Vec<u8>>::from_raw_parts(ptr, length as usize, length as usize))
+			}
+		}
+	}
+
+	fn http_request_start(method: &str, url: &str, meta: &[u8]) -> Result<HttpRequestId, ()> {
+		let method = method.as_bytes();
+		let url = url.as_bytes();
+
+		let result = unsafe {
+			ext_http_request_start.get()(
+				method.as_ptr(),
+				method.len() as u32,
+				url.as_ptr(),
+				url.len() as u32,
+				meta.as_ptr(),
+				meta.len() as u32,
+			)
+		};
+
+		if result > u16::max_value() as u32 {
+			Err(())
+		} else {
+			Ok(HttpRequestId(result as u16))
+		}
+	}
+
+	fn http_request_add_header(request_id: HttpRequestId, name: &str, value: &str) -> Result<(), ()> {
+		let name = name.as_bytes();
+		let value = value.as_bytes();
+
+		let result = unsafe {
+			ext_http_request_add_header.get()(
+				request_id.0 as u32,
+				name.as_ptr(),
+				name.len() as u32,
+				value.as_ptr(),
+				value.len() as u32,
+			)
+		};
+
+		if result == 0 {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	fn http_request_write_body(
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>
+	) -> Result<(), HttpError> {
+		let result = unsafe {
+			ext_http_request_write_body.get()(
+				request_id.0 as u32,
+				chunk.as_ptr(),
+				chunk.len() as u32,
+				deadline.is_some() as u32,
+				deadline.map_or(0, |x| x.unix_millis()),
+			)
+		};
+
+		if result == 0 {
+			Ok(())
+		} else {
+			Err(HttpError::from_code(result).unwrap_or(HttpError::IoError))
+		}
+	}
+
+	fn http_response_wait(
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>
+	) -> Vec<HttpRequestStatus> {
+		let ids = ids.iter().map(|x| x.0 as u32).collect::<Vec<_>>();
+		let mut statuses = Vec::new();
+		statuses.resize(ids.len(), 0u32);
+
+		unsafe {
+			ext_http_response_wait.get()(
+				ids.as_ptr(),
+				ids.len() as u32,
+				statuses.as_mut_ptr(),
+				deadline.is_some() as u32,
+				deadline.map_or(0, |x| x.unix_millis()),
+			)
+		}
+
+		statuses
+			.into_iter()
+			.map(|status| HttpRequestStatus::from_code(status).unwrap_or(HttpRequestStatus::Invalid))
+			.collect()
+	}
+
+	fn http_response_headers(
+		request_id: HttpRequestId,
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut len = 0u32;
+		let raw_result = unsafe {
+			let ptr = ext_http_response_headers.get()(
+				request_id.0 as u32,
+				&mut len,
+			);
+			// Invariants required by Vec::from_raw_parts are not formally fulfilled.
+			// We don't allocate via String/Vec<T>, but use a custom allocator instead.
+			// See #300 for more details.
+			<Vec<u8>>::from_raw_parts(ptr, len as usize, len as usize)
+		};
+
+		codec::Decode::decode(&mut &*raw_result).unwrap_or_default()
+	}
+
+	fn http_response_read_body(
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		let result = unsafe {
+			ext_http_response_read_body.get()(
+				request_id.0 as u32,
+				buffer.as_mut_ptr(),
+				buffer.len() as u32,
+				deadline.is_some() as u32,
+				deadline.map_or(0, |x| x.unix_millis()),
+			)
+		};
+
+		if result > u32::max_value() - 255 {
+			let code = (u32::max_value() - result) + 1;
+			Err(HttpError::from_code(code).unwrap_or(HttpError::IoError))
+		} else {
+			Ok(result as usize)
+		}
+	}
+
+	fn authority_pubkeys(kind: CryptoKind) -> Vec<Vec<u8>> {
+		let mut len = 0u32;
+		let raw_result = unsafe {
+			let ptr = ext_authority_pubkeys.get()(
+				kind.into(),
+				&mut len,
+			);
+			// Invariants required by Vec::from_raw_parts are not formally fulfilled.
+			// We don't allocate via String/Vec<T>, but use a custom allocator instead.
+			// See #300 for more details.
+			<Vec<u8>>::from_raw_parts(ptr, len as usize, len as usize)
+		};
+
+		codec::Decode::decode(&mut &*raw_result).unwrap_or_default()
+	}
+
+	fn sign(kind: CryptoKind, public: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+		let mut length: u32 = 0;
+		unsafe {
+			let ptr = ext_sign.get()(
+				kind.into(),
+				public.as_ptr(),
+				public.len() as u32,
+				data.as_ptr(),
+				data.len() as u32,
+				&mut length,
+			);
+			if length == u32::max_value() {
+				None
+			} else {
+				// Invariants required by Vec::from_raw_parts are not formally fulfilled.
+				// We don't allocate via String/Vec<T>, but use a custom allocator instead.
+				// See #300 for more details.
+				Some(<Vec<u8>>::from_raw_parts(ptr, length as usize, length as usize))
+			}
+		}
+	}
+
+	fn verify(kind: CryptoKind, public: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+		unsafe {
+			ext_verify.get()(
+				kind.into(),
+				public.as_ptr(),
+				public.len() as u32,
+				msg.as_ptr(),
+				msg.len() as u32,
+				signature.as_ptr(),
+				signature.len() as u32,
+			) == 0
+		}
+	}
+
+	fn timestamp() -> Timestamp {
+		unsafe {
+			Timestamp::from_unix_millis(ext_timestamp.get()())
+		}
+	}
+
+	fn sleep_until(deadline: Timestamp) {
+		unsafe {
+			ext_sleep_until.get()(deadline.unix_millis())
+		}
+	}
+
+	fn random_seed() -> [u8; 32] {
+		let mut result = [0_u8; 32];
+		unsafe {
+			ext_random_seed.get()(result.as_mut_ptr())
+		}
+		result
+	}
 }
 
 impl Api for () {}
@@ -18,6 +18,7 @@
 //! stage.
 
 use crate::traits::{self, Member, SimpleArithmetic, MaybeDisplay};
+use crate::weights::{GetDispatchInfo, DispatchInfo};
 
 /// Definition of something that the external world might want to say; its
 /// existence implies that it has been checked and is good, particularly with
@@ -55,3 +56,12 @@ where
 		(self.function, self.signed.map(|x| x.0))
 	}
 }
+
+impl<AccountId, Index, Call> GetDispatchInfo for CheckedExtrinsic<AccountId, Index, Call>
+where
+	Call: GetDispatchInfo,
+{
+	fn get_dispatch_info(&self) -> DispatchInfo {
+		self.function.get_dispatch_info()
+	}
+}
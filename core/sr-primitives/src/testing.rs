@@ -21,6 +21,7 @@ use std::{fmt::Debug, ops::Deref, fmt};
 use crate::codec::{Codec, Encode, Decode};
 use crate::traits::{self, Checkable, Applyable, BlakeTwo256, Convert};
 use crate::generic::DigestItem as GenDigestItem;
+use crate::weights::{GetDispatchInfo, DispatchInfo};
 pub use substrate_primitives::H256;
 use substrate_primitives::U256;
 use substrate_primitives::sr25519::{Public as AuthorityId, Signature as AuthoritySignature};
@@ -240,3 +241,8 @@ impl<Call> Applyable for TestXt<Call> where
 		(self.2, self.0)
 	}
 }
+impl<Call: GetDispatchInfo> GetDispatchInfo for TestXt<Call> {
+	fn get_dispatch_info(&self) -> DispatchInfo {
+		self.2.get_dispatch_info()
+	}
+}
@@ -287,6 +287,16 @@ pub trait OffchainWorker<BlockNumber> {
 
 impl<N> OffchainWorker<N> for () {}
 
+/// The runtime upgrade trait. Implementing this lets you migrate the storage of your module
+/// when the runtime is upgraded to a new `spec_version`, at the beginning of the first block
+/// executed by the new runtime.
+pub trait OnRuntimeUpgrade {
+	/// The runtime has been upgraded. Implement to have something happen.
+	fn on_runtime_upgrade() {}
+}
+
+impl OnRuntimeUpgrade for () {}
+
 macro_rules! tuple_impl {
 	($first:ident, $($rest:ident,)+) => {
 		tuple_impl!([$first] [$first] [$($rest)+]);
@@ -316,6 +326,13 @@ macro_rules! tuple_impl {
 				$($direct::generate_extrinsics(n);)+
 			}
 		}
+		impl<
+			$($direct: OnRuntimeUpgrade),+
+		> OnRuntimeUpgrade for ($($direct),+,) {
+			fn on_runtime_upgrade() {
+				$($direct::on_runtime_upgrade();)+
+			}
+		}
 	};
 	([$($direct:ident)+] [$($reverse:ident)+] [$first:ident $($rest:ident)*]) => {
 		tuple_impl!([$($direct)+] [$($reverse)+] []);
@@ -22,6 +22,12 @@ use crate::codec::{Encode, Decode};
 /// Priority for a transaction. Additive. Higher is better.
 pub type TransactionPriority = u64;
 
+/// The lowest priority of operational transactions.
+///
+/// Normal transactions get a lower priority, which lets block authors keep including operational
+/// ones after the share of the block available to normal transactions is used up.
+pub const OPERATIONAL_TRANSACTION_PRIORITY: TransactionPriority = 1 << 32;
+
 /// Minimum number of blocks a transaction will remain valid for.
 /// `TransactionLongevity::max_value()` means "forever".
 pub type TransactionLongevity = u64;
@@ -0,0 +1,139 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Primitives for transaction weighting.
+//!
+//! Every dispatchable function declared with `decl_module!` has a weight, an estimation of
+//! the resources its execution consumes. It is given by the `#[weight = $x]` attribute of the
+//! function, where `$x` implements `WeighData` for the tuple of references to the function
+//! arguments. `$x` also gives the class of the dispatch through `ClassifyDispatch`: block space
+//! is reserved for operational dispatches, such as governance and emergency calls, so that
+//! normal transactions cannot crowd them out. `SimpleDispatchInfo` is a fixed weight and class
+//! that can be used for any function.
+
+use crate::codec::{Encode, Decode};
+
+/// Numeric range of a transaction weight.
+pub type Weight = u32;
+
+/// Execution time that one unit of weight stands for, in nanoseconds on reference hardware.
+pub const WEIGHT_UNIT_NANOS: Weight = 10;
+
+/// Means of weighing some particular kind of data (`T`).
+pub trait WeighData<T> {
+	/// Weigh the data `T` given by `target`.
+	fn weigh_data(&self, target: T) -> Weight;
+}
+
+/// Means of classifying a dispatchable function.
+pub trait ClassifyDispatch<T> {
+	/// Classify the dispatch function based on the data `T` given by `target`.
+	fn classify_dispatch(&self, target: T) -> DispatchClass;
+}
+
+/// Class of a dispatch, which determines the share of the block it can use.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DispatchClass {
+	/// A normal dispatch, limited to a share of the block.
+	Normal,
+	/// An operational dispatch, which can use the whole block.
+	Operational,
+}
+
+impl Default for DispatchClass {
+	fn default() -> Self {
+		DispatchClass::Normal
+	}
+}
+
+/// Static information of a dispatch, collected from its `#[weight = $x]` attribute.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DispatchInfo {
+	/// Weight of this dispatch.
+	pub weight: Weight,
+	/// Class of this dispatch.
+	pub class: DispatchClass,
+}
+
+/// Something that can return the dispatch info of itself, typically a call.
+pub trait GetDispatchInfo {
+	/// Return the `DispatchInfo` of this call.
+	fn get_dispatch_info(&self) -> DispatchInfo;
+}
+
+/// Weight and class of a dispatch which do not depend on its arguments.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum SimpleDispatchInfo {
+	/// A normal dispatch of given fixed weight.
+	FixedNormal(Weight),
+	/// A normal dispatch of no weight, for calls which are paid by other means.
+	FreeNormal,
+	/// An operational dispatch of given fixed weight.
+	FixedOperational(Weight),
+	/// An operational dispatch of no weight.
+	FreeOperational,
+}
+
+impl<T> WeighData<T> for SimpleDispatchInfo {
+	fn weigh_data(&self, _: T) -> Weight {
+		match self {
+			SimpleDispatchInfo::FixedNormal(weight) => *weight,
+			SimpleDispatchInfo::FixedOperational(weight) => *weight,
+			SimpleDispatchInfo::FreeNormal | SimpleDispatchInfo::FreeOperational => 0,
+		}
+	}
+}
+
+impl<T> ClassifyDispatch<T> for SimpleDispatchInfo {
+	fn classify_dispatch(&self, _: T) -> DispatchClass {
+		match self {
+			SimpleDispatchInfo::FixedNormal(_) | SimpleDispatchInfo::FreeNormal => DispatchClass::Normal,
+			SimpleDispatchInfo::FixedOperational(_) | SimpleDispatchInfo::FreeOperational => DispatchClass::Operational,
+		}
+	}
+}
+
+impl Default for SimpleDispatchInfo {
+	fn default() -> Self {
+		// Weight of the functions which are not annotated, roughly a balance transfer which
+		// takes 100 microseconds.
+		SimpleDispatchInfo::FixedNormal(10_000)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn simple_dispatch_info_ignores_arguments() {
+		assert_eq!(SimpleDispatchInfo::FixedNormal(5).weigh_data((&1u32, &vec![1u8; 100])), 5);
+		assert_eq!(SimpleDispatchInfo::FreeNormal.weigh_data(()), 0);
+		assert_eq!(SimpleDispatchInfo::default().weigh_data(()), 10_000);
+	}
+
+	#[test]
+	fn simple_dispatch_info_classifies_dispatch() {
+		assert_eq!(SimpleDispatchInfo::default().classify_dispatch(()), DispatchClass::Normal);
+		assert_eq!(SimpleDispatchInfo::FreeNormal.classify_dispatch(()), DispatchClass::Normal);
+		assert_eq!(SimpleDispatchInfo::FixedOperational(5).classify_dispatch(()), DispatchClass::Operational);
+		assert_eq!(SimpleDispatchInfo::FixedOperational(5).weigh_data(()), 5);
+		assert_eq!(SimpleDispatchInfo::FreeOperational.weigh_data(()), 0);
+	}
+}
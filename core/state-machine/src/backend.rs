@@ -68,6 +68,16 @@ pub trait Backend<H: Hasher> {
 	/// call `f` for each of those keys.
 	fn for_keys_with_prefix<F: FnMut(&[u8])>(&self, prefix: &[u8], f: F);
 
+	/// Retrieve all entries keys of which start with the given prefix, in lexicographic order
+	/// of keys beginning at `start_at` (inclusive) if given, and call `f` for each key/value pair.
+	/// The iteration stops as soon as `f` returns `false`.
+	fn for_key_values_with_prefix<F: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		f: F,
+	);
+
 	/// Calculate the storage root, with given delta over what is already stored in
 	/// the backend, and produce a "transaction" that can be used to commit.
 	/// Does not include child storage updates.
@@ -281,6 +291,25 @@ impl<H: Hasher> Backend<H> for InMemory<H> {
 		self.inner.get(&Some(storage_key.to_vec())).map(|map| map.keys().for_each(|k| f(&k)));
 	}
 
+	fn for_key_values_with_prefix<F: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		mut f: F,
+	) {
+		let mut pairs: Vec<_> = self.inner.get(&None).into_iter()
+			.flat_map(|map| map.iter())
+			.filter(|(k, _)| k.starts_with(prefix) && start_at.map_or(true, |start| &k[..] >= start))
+			.collect();
+		pairs.sort();
+
+		for (key, value) in pairs {
+			if !f(key, value) {
+				break;
+			}
+		}
+	}
+
 	fn storage_root<I>(&self, delta: I) -> (H::Out, Self::Transaction)
 	where
 		I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)>,
@@ -20,7 +20,7 @@ use std::collections::HashMap;
 use std::iter::FromIterator;
 use hash_db::Hasher;
 use trie::trie_root;
-use primitives::storage::well_known_keys::{CHANGES_TRIE_CONFIG, CODE, HEAP_PAGES};
+use primitives::{OffchainExt, storage::well_known_keys::{CHANGES_TRIE_CONFIG, CODE, HEAP_PAGES}};
 use parity_codec::Encode;
 use super::{ChildStorageKey, Externalities, OverlayedChanges};
 use log::warn;
@@ -159,6 +159,11 @@ impl<H: Hasher> Externalities<H> for BasicExternalities where H::Out: Ord {
 		warn!("Call to submit_extrinsic without offchain externalities set.");
 		Err(())
 	}
+
+	fn offchain(&mut self) -> Option<&mut dyn OffchainExt> {
+		warn!("Call to offchain without offchain externalities set.");
+		None
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,95 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Function that traverses changes tries and returns all keys with given
+//! prefix which have been changed at given blocks range.
+
+use std::collections::BTreeSet;
+use parity_codec::Decode;
+use hash_db::Hasher;
+use crate::changes_trie::{AnchorBlockId, Storage};
+use crate::changes_trie::input::{ExtrinsicIndex, InputKey};
+use crate::changes_trie::storage::TrieBackendAdapter;
+use crate::trie_backend_essence::TrieBackendEssence;
+
+/// Return keys starting with `prefix` which have been changed at blocks range
+/// `begin..=end.number`, in lexicographic order.
+///
+/// Only the extrinsics-level entries of every block's changes trie are read, digest
+/// entries do not tell which keys have been changed at a given block.
+pub fn changed_keys<S: Storage<H>, H: Hasher>(
+	storage: &S,
+	begin: u64,
+	end: &AnchorBlockId<H::Out>,
+	prefix: &[u8],
+) -> Result<BTreeSet<Vec<u8>>, String> {
+	let mut keys = BTreeSet::new();
+	for block in begin..end.number + 1 {
+		let root = storage.root(end, block)?
+			.ok_or_else(|| format!("No changes trie root for block {}", block))?;
+		let trie_storage = TrieBackendEssence::<_, H>::new(TrieBackendAdapter::new(storage), root);
+
+		let extrinsic_prefix = ExtrinsicIndex::key_neutral_prefix(block);
+		trie_storage.for_keys_with_prefix(&extrinsic_prefix, |key|
+			if let Some(InputKey::ExtrinsicIndex(trie_key)) = Decode::decode(&mut &key[..]) {
+				if trie_key.key.starts_with(prefix) {
+					keys.insert(trie_key.key);
+				}
+			});
+	}
+
+	Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+	use primitives::Blake2Hasher;
+	use crate::changes_trie::input::{DigestIndex, InputPair};
+	use crate::changes_trie::storage::InMemoryStorage;
+	use super::*;
+
+	#[test]
+	fn changed_keys_works() {
+		let storage = InMemoryStorage::<Blake2Hasher>::with_inputs(vec![
+			(1, vec![
+				InputPair::ExtrinsicIndex(ExtrinsicIndex { block: 1, key: vec![1, 1] }, vec![0]),
+				InputPair::ExtrinsicIndex(ExtrinsicIndex { block: 1, key: vec![2, 1] }, vec![0]),
+			]),
+			(2, vec![
+				InputPair::ExtrinsicIndex(ExtrinsicIndex { block: 2, key: vec![1, 2] }, vec![1]),
+				InputPair::DigestIndex(DigestIndex { block: 2, key: vec![1, 1] }, vec![1]),
+			]),
+			(3, vec![
+				InputPair::ExtrinsicIndex(ExtrinsicIndex { block: 3, key: vec![1, 3] }, vec![0]),
+			]),
+		]);
+		let anchor = |number| AnchorBlockId { hash: Default::default(), number };
+
+		assert_eq!(
+			changed_keys(&storage, 1, &anchor(3), &[1]),
+			Ok(vec![vec![1, 1], vec![1, 2], vec![1, 3]].into_iter().collect()),
+		);
+		assert_eq!(
+			changed_keys(&storage, 2, &anchor(2), &[1]),
+			Ok(vec![vec![1, 2]].into_iter().collect()),
+		);
+		assert_eq!(
+			changed_keys(&storage, 1, &anchor(3), &[]),
+			Ok(vec![vec![1, 1], vec![1, 2], vec![1, 3], vec![2, 1]].into_iter().collect()),
+		);
+		assert!(changed_keys(&storage, 1, &anchor(4), &[1]).is_err());
+	}
+}
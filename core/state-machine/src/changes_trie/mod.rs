@@ -37,12 +37,14 @@
 
 mod build;
 mod build_iterator;
+mod changed_keys;
 mod changes_iterator;
 mod input;
 mod prune;
 mod storage;
 
 pub use self::storage::InMemoryStorage;
+pub use self::changed_keys::changed_keys;
 pub use self::changes_iterator::{key_changes, key_changes_proof, key_changes_proof_check};
 pub use self::prune::{prune, oldest_non_pruned_trie};
 
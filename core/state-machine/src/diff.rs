@@ -0,0 +1,283 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Difference between the storage of two tries.
+
+use std::rc::Rc;
+use hash_db::Hasher;
+use trie::NodeCodec;
+use trie_db::{NibbleSlice, NodeCodec as NodeCodecT, node::Node};
+use crate::trie_backend::TrieBackend;
+use crate::trie_backend_essence::TrieBackendStorage;
+
+/// Returns the keys starting with `prefix` whose value differs between the `from` and `to`
+/// tries, in lexicographic order, along with their value in `to` (`None` if removed).
+///
+/// Both tries are walked node by node from their roots, and subtrees referenced by the same
+/// hash in both tries are skipped. The cost of the walk is thus proportional to the number
+/// of changed entries rather than to the number of entries under the prefix.
+pub fn storage_diff<S, H>(
+	from: &TrieBackend<S, H>,
+	to: &TrieBackend<S, H>,
+	prefix: &[u8],
+) -> Result<Vec<(Vec<u8>, Option<Vec<u8>>)>, String> where
+	S: TrieBackendStorage<H>,
+	H: Hasher,
+{
+	let prefix = prefix.iter().flat_map(|byte| vec![byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+	let mut diff = Vec::new();
+	diff_subtries::<S, H>(
+		from.backend_storage(),
+		root_cursor::<H>(from.root()),
+		to.backend_storage(),
+		root_cursor::<H>(to.root()),
+		&mut Vec::new(),
+		&prefix,
+		&mut diff,
+	)?;
+	Ok(diff)
+}
+
+/// A trie node decoded from its encoding, with partial keys unpacked to nibbles.
+enum OwnedNode {
+	Leaf(Vec<u8>, Vec<u8>),
+	Extension(Vec<u8>, Vec<u8>),
+	Branch(Vec<Option<Vec<u8>>>, Option<Vec<u8>>),
+}
+
+/// Position in a trie at a given nibble path.
+enum Cursor {
+	/// Root of a subtrie, referenced by its hash or by its inline encoding.
+	Ref(Vec<u8>),
+	/// Inside the partial key of a leaf or extension node, after the given number of nibbles.
+	Partial(Rc<OwnedNode>, usize),
+}
+
+fn root_cursor<H: Hasher>(root: &H::Out) -> Option<Cursor> {
+	if *root == NodeCodec::<H>::hashed_null_node() {
+		None
+	} else {
+		Some(Cursor::Ref(root.as_ref().to_vec()))
+	}
+}
+
+/// Compares the subtries of `from` and `to` at nibble `path`, pushing the changed entries to `diff`.
+fn diff_subtries<S: TrieBackendStorage<H>, H: Hasher>(
+	from: &S,
+	from_cursor: Option<Cursor>,
+	to: &S,
+	to_cursor: Option<Cursor>,
+	path: &mut Vec<u8>,
+	prefix: &[u8],
+	diff: &mut Vec<(Vec<u8>, Option<Vec<u8>>)>,
+) -> Result<(), String> {
+	if let (Some(Cursor::Ref(from_ref)), Some(Cursor::Ref(to_ref))) = (&from_cursor, &to_cursor) {
+		if from_ref == to_ref {
+			return Ok(());
+		}
+	}
+
+	let (old_value, old_children) = expand::<S, H>(from, from_cursor, path)?;
+	let (new_value, new_children) = expand::<S, H>(to, to_cursor, path)?;
+
+	// only paths leading to the prefix are walked, so any longer path starts with it
+	if path.len() >= prefix.len() && old_value != new_value {
+		diff.push((nibbles_to_bytes(path), new_value));
+	}
+
+	for (nibble, (old, new)) in old_children.into_iter().zip(new_children).enumerate() {
+		if old.is_none() && new.is_none() {
+			continue;
+		}
+		if path.len() < prefix.len() && prefix[path.len()] as usize != nibble {
+			continue;
+		}
+
+		path.push(nibble as u8);
+		diff_subtries::<S, H>(from, old, to, new, path, prefix, diff)?;
+		path.pop();
+	}
+
+	Ok(())
+}
+
+/// Returns the value stored at nibble `path` and the subtries following each of the 16 nibbles.
+fn expand<S: TrieBackendStorage<H>, H: Hasher>(
+	storage: &S,
+	cursor: Option<Cursor>,
+	path: &[u8],
+) -> Result<(Option<Vec<u8>>, Vec<Option<Cursor>>), String> {
+	let mut children = (0..16).map(|_| None).collect::<Vec<_>>();
+	let (node, walked) = match cursor {
+		None => return Ok((None, children)),
+		Some(Cursor::Partial(node, walked)) => (node, walked),
+		Some(Cursor::Ref(node_ref)) => match load::<S, H>(storage, &node_ref, path)? {
+			Some(node) => (node, 0),
+			None => return Ok((None, children)),
+		},
+	};
+
+	let value = match &*node {
+		OwnedNode::Leaf(partial, value) if walked == partial.len() => Some(value.clone()),
+		OwnedNode::Leaf(partial, _) => {
+			children[partial[walked] as usize] = Some(Cursor::Partial(node.clone(), walked + 1));
+			None
+		},
+		OwnedNode::Extension(partial, child) if walked == partial.len() =>
+			return expand::<S, H>(storage, Some(Cursor::Ref(child.clone())), path),
+		OwnedNode::Extension(partial, child) => {
+			children[partial[walked] as usize] = Some(if walked + 1 == partial.len() {
+				Cursor::Ref(child.clone())
+			} else {
+				Cursor::Partial(node.clone(), walked + 1)
+			});
+			None
+		},
+		OwnedNode::Branch(branch_children, value) => {
+			for (child, node_ref) in children.iter_mut().zip(branch_children) {
+				*child = node_ref.clone().map(Cursor::Ref);
+			}
+			value.clone()
+		},
+	};
+
+	Ok((value, children))
+}
+
+/// Decodes the node referenced by `node_ref` at nibble `path`, reading it from `storage` if
+/// it is referenced by hash. Returns `None` for the empty node.
+fn load<S: TrieBackendStorage<H>, H: Hasher>(
+	storage: &S,
+	node_ref: &[u8],
+	path: &[u8],
+) -> Result<Option<Rc<OwnedNode>>, String> {
+	let stored;
+	let encoded = match NodeCodec::<H>::try_decode_hash(node_ref) {
+		Some(hash) => {
+			stored = storage.get(&hash, &node_prefix(path))?
+				.ok_or_else(|| format!("Trie node {:?} is missing from the database", hash))?;
+			&stored[..]
+		},
+		None => node_ref,
+	};
+
+	let node = match NodeCodec::<H>::decode(encoded).map_err(|e| format!("Invalid trie node: {}", e))? {
+		Node::Empty => return Ok(None),
+		Node::Leaf(partial, value) => OwnedNode::Leaf(nibbles(&partial), value.to_vec()),
+		Node::Extension(partial, child) => OwnedNode::Extension(nibbles(&partial), child.to_vec()),
+		Node::Branch(children, value) => OwnedNode::Branch(
+			children.iter().map(|child| child.map(|child| child.to_vec())).collect(),
+			value.map(|value| value.to_vec()),
+		),
+	};
+	Ok(Some(Rc::new(node)))
+}
+
+fn nibbles(slice: &NibbleSlice) -> Vec<u8> {
+	(0..slice.len()).map(|i| slice.at(i)).collect()
+}
+
+fn nibbles_to_bytes(nibbles: &[u8]) -> Vec<u8> {
+	nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair.get(1).cloned().unwrap_or(0)).collect()
+}
+
+/// Database prefix of the node at nibble `path`: the path in hex-prefix notation, as used by
+/// `TrieDBMut` when storing nodes. The root node is stored without prefix.
+fn node_prefix(path: &[u8]) -> Vec<u8> {
+	if path.is_empty() {
+		return Vec::new();
+	}
+
+	let odd = path.len() % 2;
+	let mut prefix = vec![if odd == 1 { 0x10 + path[0] } else { 0 }];
+	prefix.extend(nibbles_to_bytes(&path[odd..]));
+	prefix
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::{Blake2Hasher, H256};
+	use trie::{TrieMut, TrieDBMut, PrefixedMemoryDB};
+
+	fn trie(pairs: Vec<(Vec<u8>, Vec<u8>)>) -> TrieBackend<PrefixedMemoryDB<Blake2Hasher>, Blake2Hasher> {
+		let mut root = H256::default();
+		let mut mdb = PrefixedMemoryDB::<Blake2Hasher>::default();
+		{
+			let mut trie = TrieDBMut::new(&mut mdb, &mut root);
+			for (key, value) in pairs {
+				trie.insert(&key, &value).expect("insert failed");
+			}
+		}
+		TrieBackend::new(mdb, root)
+	}
+
+	fn pairs(pairs: Vec<(&str, &str)>) -> Vec<(Vec<u8>, Vec<u8>)> {
+		pairs.into_iter()
+			.map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+			.collect()
+	}
+
+	#[test]
+	fn storage_diff_works() {
+		let from = trie(pairs(vec![
+			("a1", "1"), ("a2", "2"), ("a4", "4"), ("a6", "6"), ("b1", "1"),
+		]));
+		let to = trie(pairs(vec![
+			("a2", "2"), ("a3", "3"), ("a4", "5"), ("b1", "2"),
+		]));
+
+		assert_eq!(storage_diff(&from, &to, b"a"), Ok(vec![
+			(b"a1".to_vec(), None),
+			(b"a3".to_vec(), Some(b"3".to_vec())),
+			(b"a4".to_vec(), Some(b"5".to_vec())),
+			(b"a6".to_vec(), None),
+		]));
+		assert_eq!(storage_diff(&from, &to, b"b"), Ok(vec![(b"b1".to_vec(), Some(b"2".to_vec()))]));
+		assert_eq!(storage_diff(&from, &to, b"c"), Ok(Vec::new()));
+		assert_eq!(storage_diff(&from, &from, b""), Ok(Vec::new()));
+	}
+
+	#[test]
+	fn storage_diff_handles_nested_keys_and_empty_tries() {
+		let empty = trie(Vec::new());
+		let from = trie(pairs(vec![("key", "1"), ("key_long", "2"), ("other", "3")]));
+		let to = trie(pairs(vec![("key", "1"), ("key_longer", "2"), ("other", "4")]));
+
+		assert_eq!(storage_diff(&from, &to, b""), Ok(vec![
+			(b"key_long".to_vec(), None),
+			(b"key_longer".to_vec(), Some(b"2".to_vec())),
+			(b"other".to_vec(), Some(b"4".to_vec())),
+		]));
+		assert_eq!(storage_diff(&empty, &to, b"key"), Ok(vec![
+			(b"key".to_vec(), Some(b"1".to_vec())),
+			(b"key_longer".to_vec(), Some(b"2".to_vec())),
+		]));
+		assert_eq!(storage_diff(&from, &empty, b"o"), Ok(vec![(b"other".to_vec(), None)]));
+	}
+
+	#[test]
+	fn storage_diff_finds_changes_in_large_tries() {
+		let entries = |changed: u32| (0u32..1000)
+			.map(|i| (i.to_be_bytes().to_vec(), if i == changed { vec![0] } else { vec![1; 40] }))
+			.collect::<Vec<_>>();
+		let from = trie(entries(1000));
+		let to = trie(entries(517));
+
+		assert_eq!(storage_diff(&from, &to, &[]), Ok(vec![(517u32.to_be_bytes().to_vec(), Some(vec![0]))]));
+		assert_eq!(storage_diff(&from, &to, &[0, 0, 3]), Ok(Vec::new()));
+	}
+}
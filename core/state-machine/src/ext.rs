@@ -16,11 +16,11 @@
 
 //! Conrete externalities implementation.
 
-use std::{error, fmt, cmp::Ord};
+use std::{error, fmt, cmp::Ord, collections::BTreeSet};
 use log::warn;
 use crate::backend::Backend;
 use crate::changes_trie::{AnchorBlockId, Storage as ChangesTrieStorage, compute_changes_trie_root};
-use crate::{Externalities, OverlayedChanges, OffchainExt, ChildStorageKey};
+use crate::{Externalities, EnumerableExternalities, OverlayedChanges, OffchainExt, ChildStorageKey};
 use hash_db::Hasher;
 use primitives::storage::well_known_keys::is_child_storage_key;
 use trie::{MemoryDB, TrieDBMut, TrieMut, default_child_trie_root};
@@ -348,6 +348,57 @@ where
 			Err(())
 		}
 	}
+
+	fn offchain(&mut self) -> Option<&mut dyn OffchainExt> {
+		self.offchain_externalities.as_mut().map(|x| &mut **x as _)
+	}
+}
+
+impl<'a, B, T, H, O> EnumerableExternalities<H> for Ext<'a, H, B, T, O>
+where
+	H: Hasher,
+	B: 'a + Backend<H>,
+	T: 'a + ChangesTrieStorage<H>,
+	O: 'a + OffchainExt,
+	H::Out: Ord,
+{
+	fn storage_pairs_with_prefix(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let _guard = panic_handler::AbortGuard::new(true);
+		let mut keys = BTreeSet::new();
+		self.backend.for_keys_with_prefix(prefix, |key| {
+			keys.insert(key.to_vec());
+		});
+		keys.extend(self.overlay.committed.top.keys()
+			.chain(self.overlay.prospective.top.keys())
+			.filter(|key| key.starts_with(prefix))
+			.cloned());
+
+		keys.into_iter()
+			.filter_map(|key| self.storage(&key).map(|value| (key, value)))
+			.collect()
+	}
+
+	fn child_storage_pairs(&self, storage_key: ChildStorageKey<H>) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let _guard = panic_handler::AbortGuard::new(true);
+		let storage_key = storage_key.as_ref();
+		let mut keys = BTreeSet::new();
+		self.backend.for_keys_in_child_storage(storage_key, |key| {
+			keys.insert(key.to_vec());
+		});
+		for changes in &[&self.overlay.committed, &self.overlay.prospective] {
+			if let Some((_, map)) = changes.children.get(storage_key) {
+				keys.extend(map.keys().cloned());
+			}
+		}
+
+		keys.into_iter()
+			.filter_map(|key| {
+				let value = self.overlay.child_storage(storage_key, &key).map(|x| x.map(|x| x.to_vec()))
+					.unwrap_or_else(|| self.backend.child_storage(storage_key, &key).expect(EXT_NOT_ALLOWED_TO_FAIL));
+				value.map(|value| (key, value))
+			})
+			.collect()
+	}
 }
 
 #[cfg(test)]
@@ -24,16 +24,18 @@ use log::warn;
 use hash_db::Hasher;
 use parity_codec::{Decode, Encode};
 use primitives::{
-	storage::well_known_keys, NativeOrEncoded, NeverNativeValue, OffchainExt
+	storage::well_known_keys, NativeOrEncoded, NeverNativeValue, OffchainExt, offchain,
 };
 
 pub mod backend;
 mod changes_trie;
+mod diff;
 mod ext;
 mod testing;
 mod basic;
 mod overlayed_changes;
 mod proving_backend;
+mod tracing;
 mod trie_backend;
 mod trie_backend_essence;
 
@@ -49,14 +51,17 @@ pub use changes_trie::{
 	RootsStorage as ChangesTrieRootsStorage,
 	InMemoryStorage as InMemoryChangesTrieStorage,
 	key_changes, key_changes_proof, key_changes_proof_check,
+	changed_keys as changed_keys_in_changes_tries,
 	prune as prune_changes_tries,
 	oldest_non_pruned_trie as oldest_non_pruned_changes_trie
 };
+pub use diff::storage_diff;
 pub use overlayed_changes::OverlayedChanges;
 pub use proving_backend::{
 	create_proof_check_backend, create_proof_check_backend_storage,
 	Recorder as ProofRecorder, ProvingBackend,
 };
+pub use tracing::{EnumerableExternalities, StorageAccess, TracingExt};
 pub use trie_backend_essence::{TrieBackendStorage, Storage};
 pub use trie_backend::TrieBackend;
 
@@ -225,6 +230,9 @@ pub trait Externalities<H: Hasher> {
 	///
 	/// Returns an error in case the API is not available.
 	fn submit_extrinsic(&mut self, extrinsic: Vec<u8>) -> Result<(), ()>;
+
+	/// Returns offchain externalities extension if present.
+	fn offchain(&mut self) -> Option<&mut dyn OffchainExt>;
 }
 
 /// An implementation of offchain extensions that should never be triggered.
@@ -239,6 +247,90 @@ impl NeverOffchainExt {
 
 impl OffchainExt for NeverOffchainExt {
 	fn submit_extrinsic(&mut self, _extrinsic: Vec<u8>) { unreachable!() }
+
+	fn local_storage_set(&mut self, _key: &[u8], _value: &[u8]) { unreachable!() }
+
+	fn local_storage_compare_and_set(
+		&mut self,
+		_key: &[u8],
+		_old_value: Option<&[u8]>,
+		_new_value: &[u8],
+	) -> bool { unreachable!() }
+
+	fn local_storage_get(&mut self, _key: &[u8]) -> Option<Vec<u8>> { unreachable!() }
+
+	fn http_request_start(
+		&mut self,
+		_method: &str,
+		_uri: &str,
+		_meta: &[u8]
+	) -> Result<offchain::HttpRequestId, ()> {
+		unreachable!()
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		_request_id: offchain::HttpRequestId,
+		_name: &str,
+		_value: &str
+	) -> Result<(), ()> {
+		unreachable!()
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		_request_id: offchain::HttpRequestId,
+		_chunk: &[u8],
+		_deadline: Option<offchain::Timestamp>
+	) -> Result<(), offchain::HttpError> {
+		unreachable!()
+	}
+
+	fn http_response_wait(
+		&mut self,
+		_ids: &[offchain::HttpRequestId],
+		_deadline: Option<offchain::Timestamp>
+	) -> Vec<offchain::HttpRequestStatus> {
+		unreachable!()
+	}
+
+	fn http_response_headers(
+		&mut self,
+		_request_id: offchain::HttpRequestId
+	) -> Vec<(Vec<u8>, Vec<u8>)> {
+		unreachable!()
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		_request_id: offchain::HttpRequestId,
+		_buffer: &mut [u8],
+		_deadline: Option<offchain::Timestamp>
+	) -> Result<usize, offchain::HttpError> {
+		unreachable!()
+	}
+
+	fn authority_pubkeys(&mut self, _kind: offchain::CryptoKind) -> Vec<Vec<u8>> { unreachable!() }
+
+	fn sign(&mut self, _kind: offchain::CryptoKind, _public: &[u8], _data: &[u8]) -> Option<Vec<u8>> {
+		unreachable!()
+	}
+
+	fn verify(
+		&mut self,
+		_kind: offchain::CryptoKind,
+		_public: &[u8],
+		_msg: &[u8],
+		_signature: &[u8]
+	) -> bool {
+		unreachable!()
+	}
+
+	fn timestamp(&mut self) -> offchain::Timestamp { unreachable!() }
+
+	fn sleep_until(&mut self, _deadline: offchain::Timestamp) { unreachable!() }
+
+	fn random_seed(&mut self) -> [u8; 32] { unreachable!() }
 }
 
 /// Code execution engine.
@@ -699,6 +791,23 @@ where
 }
 
 
+/// Generate storage read proof of several keys at once.
+pub fn prove_read_keys<B, H, I>(
+	backend: B,
+	keys: I,
+) -> Result<Vec<Vec<u8>>, Box<Error>>
+where
+	B: Backend<H>,
+	H: Hasher,
+	H::Out: Ord,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let trie_backend = backend.try_into_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<Error>)?;
+	prove_read_keys_on_trie_backend(&trie_backend, keys)
+}
+
 /// Generate storage read proof on pre-created trie backend.
 pub fn prove_read_on_trie_backend<S, H>(
 	trie_backend: &TrieBackend<S, H>,
@@ -731,6 +840,25 @@ where
 	Ok((result, proving_backend.extract_proof()))
 }
 
+/// Generate storage read proof of several keys at once on pre-created trie backend.
+pub fn prove_read_keys_on_trie_backend<S, H, I>(
+	trie_backend: &TrieBackend<S, H>,
+	keys: I,
+) -> Result<Vec<Vec<u8>>, Box<Error>>
+where
+	S: trie_backend_essence::TrieBackendStorage<H>,
+	H: Hasher,
+	H::Out: Ord,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+	for key in keys.into_iter() {
+		proving_backend.storage(key.as_ref()).map_err(|e| Box::new(e) as Box<Error>)?;
+	}
+	Ok(proving_backend.extract_proof())
+}
+
 /// Check storage read proof, generated by `prove_read` call.
 pub fn read_proof_check<H>(
 	root: H::Out,
@@ -1102,6 +1230,18 @@ mod tests {
 		assert_eq!(local_result2, None);
 	}
 
+	#[test]
+	fn prove_read_keys_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let remote_proof = prove_read_keys(remote_backend, &[&b"value1"[..], &b"value2"[..]]).unwrap();
+
+		let check = |key: &[u8]| read_proof_check::<Blake2Hasher>(remote_root, remote_proof.clone(), key);
+		assert_eq!(check(b"value1").unwrap(), Some(vec![42]));
+		assert_eq!(check(b"value2").unwrap(), Some(vec![24]));
+		assert!(check(&[0xff]).is_err());
+	}
+
 	#[test]
 	fn cannot_change_changes_trie_config() {
 		assert!(new(
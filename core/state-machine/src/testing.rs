@@ -16,15 +16,15 @@
 
 //! Test implementation for Externalities.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::iter::FromIterator;
 use hash_db::Hasher;
 use trie::trie_root;
 use crate::backend::InMemory;
 use crate::changes_trie::{compute_changes_trie_root, InMemoryStorage as ChangesTrieInMemoryStorage, AnchorBlockId};
-use primitives::storage::well_known_keys::{CHANGES_TRIE_CONFIG, CODE, HEAP_PAGES};
+use primitives::{OffchainExt, storage::well_known_keys::{CHANGES_TRIE_CONFIG, CODE, HEAP_PAGES}};
 use parity_codec::Encode;
-use super::{ChildStorageKey, Externalities, OverlayedChanges};
+use super::{ChildStorageKey, EnumerableExternalities, Externalities, OverlayedChanges};
 
 /// Simple HashMap-based Externalities impl.
 pub struct TestExternalities<H: Hasher> {
@@ -32,6 +32,7 @@ pub struct TestExternalities<H: Hasher> {
 	changes_trie_storage: ChangesTrieInMemoryStorage<H>,
 	changes: OverlayedChanges,
 	code: Option<Vec<u8>>,
+	offchain: Option<Box<dyn OffchainExt + Send>>,
 }
 
 impl<H: Hasher> TestExternalities<H> {
@@ -56,6 +57,7 @@ impl<H: Hasher> TestExternalities<H> {
 			changes_trie_storage: ChangesTrieInMemoryStorage::new(),
 			changes: overlay,
 			code: Some(code.to_vec()),
+			offchain: None,
 		}
 	}
 
@@ -63,6 +65,11 @@ impl<H: Hasher> TestExternalities<H> {
 	pub fn insert(&mut self, k: Vec<u8>, v: Vec<u8>) -> Option<Vec<u8>> {
 		self.inner.insert(k, v)
 	}
+
+	/// Set offchain externalities.
+	pub fn set_offchain_externalities(&mut self, offchain: impl OffchainExt + Send + 'static) {
+		self.offchain = Some(Box::new(offchain));
+	}
 }
 
 impl<H: Hasher> ::std::fmt::Debug for TestExternalities<H> {
@@ -102,6 +109,7 @@ impl<H: Hasher> From< HashMap<Vec<u8>, Vec<u8>> > for TestExternalities<H> {
 			changes_trie_storage: ChangesTrieInMemoryStorage::new(),
 			changes: Default::default(),
 			code: None,
+			offchain: None,
 		}
 	}
 }
@@ -170,8 +178,41 @@ impl<H: Hasher> Externalities<H> for TestExternalities<H> where H::Out: Ord {
 		).map(|(root, _)| root.clone())
 	}
 
-	fn submit_extrinsic(&mut self, _extrinsic: Vec<u8>) -> Result<(), ()> {
-		unimplemented!()
+	fn submit_extrinsic(&mut self, extrinsic: Vec<u8>) -> Result<(), ()> {
+		match self.offchain.as_mut() {
+			Some(offchain) => Ok(offchain.submit_extrinsic(extrinsic)),
+			None => Err(()),
+		}
+	}
+
+	fn offchain(&mut self) -> Option<&mut dyn OffchainExt> {
+		self.offchain.as_mut().map(|x| &mut **x as _)
+	}
+}
+
+impl<H: Hasher> EnumerableExternalities<H> for TestExternalities<H> where H::Out: Ord {
+	fn storage_pairs_with_prefix(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let mut pairs = self.inner.iter()
+			.filter(|(key, _)| key.starts_with(prefix))
+			.map(|(key, value)| (key.clone(), value.clone()))
+			.collect::<Vec<_>>();
+		pairs.sort();
+		pairs
+	}
+
+	fn child_storage_pairs(&self, storage_key: ChildStorageKey<H>) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let storage_key = storage_key.as_ref();
+		let keys = [&self.changes.committed, &self.changes.prospective].iter()
+			.filter_map(|changes| changes.children.get(storage_key))
+			.flat_map(|(_, map)| map.keys().cloned())
+			.collect::<BTreeSet<_>>();
+
+		keys.into_iter()
+			.filter_map(|key| {
+				let value = self.changes.child_storage(storage_key, &key)??.to_vec();
+				Some((key, value))
+			})
+			.collect()
 	}
 }
 
@@ -0,0 +1,241 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Externalities wrapper recording the storage accesses of a call.
+
+use std::{cell::RefCell, marker::PhantomData};
+use hash_db::Hasher;
+use primitives::{OffchainExt, storage::well_known_keys::is_child_storage_key};
+use crate::{ChildStorageKey, Externalities};
+
+/// Storage access made through the externalities.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageAccess {
+	/// Entry was read.
+	Read {
+		/// Key of the entry.
+		key: Vec<u8>,
+		/// Value read, `None` if the entry does not exist.
+		value: Option<Vec<u8>>,
+	},
+	/// Entry was set or cleared.
+	Write {
+		/// Key of the entry.
+		key: Vec<u8>,
+		/// Value before the write.
+		old: Option<Vec<u8>>,
+		/// Value after the write, `None` if the entry was cleared.
+		new: Option<Vec<u8>>,
+	},
+	/// Entry of a child storage was read.
+	ChildRead {
+		/// Key of the child storage.
+		storage_key: Vec<u8>,
+		/// Key of the entry.
+		key: Vec<u8>,
+		/// Value read, `None` if the entry does not exist.
+		value: Option<Vec<u8>>,
+	},
+	/// Entry of a child storage was set or cleared.
+	ChildWrite {
+		/// Key of the child storage.
+		storage_key: Vec<u8>,
+		/// Key of the entry.
+		key: Vec<u8>,
+		/// Value before the write.
+		old: Option<Vec<u8>>,
+		/// Value after the write, `None` if the entry was cleared.
+		new: Option<Vec<u8>>,
+	},
+	/// All entries with keys starting with the prefix were cleared.
+	ClearPrefix {
+		/// Prefix of the cleared keys.
+		prefix: Vec<u8>,
+		/// Entries before the clear, in order of keys.
+		old: Vec<(Vec<u8>, Vec<u8>)>,
+	},
+	/// Child storage was cleared.
+	KillChild {
+		/// Key of the child storage.
+		storage_key: Vec<u8>,
+		/// Entries before the clear, in order of keys.
+		old: Vec<(Vec<u8>, Vec<u8>)>,
+	},
+}
+
+/// Externalities able to list the entries they hold, so that the values removed by
+/// prefix and child storage clears can be recorded.
+pub trait EnumerableExternalities<H: Hasher>: Externalities<H> {
+	/// Entries with keys starting with the prefix, in order of keys.
+	fn storage_pairs_with_prefix(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)>;
+
+	/// Entries of the child storage, in order of keys.
+	fn child_storage_pairs(&self, storage_key: ChildStorageKey<H>) -> Vec<(Vec<u8>, Vec<u8>)>;
+}
+
+/// Externalities recording every storage access made through them, in order.
+pub struct TracingExt<'a, H, E: 'a> {
+	inner: &'a mut E,
+	accesses: RefCell<Vec<StorageAccess>>,
+	_hasher: PhantomData<H>,
+}
+
+impl<'a, H: Hasher, E: Externalities<H>> TracingExt<'a, H, E> {
+	/// Wrap given externalities.
+	pub fn new(inner: &'a mut E) -> Self {
+		TracingExt {
+			inner,
+			accesses: RefCell::new(Vec::new()),
+			_hasher: PhantomData,
+		}
+	}
+
+	/// Consume the wrapper, returning the recorded accesses.
+	pub fn into_accesses(self) -> Vec<StorageAccess> {
+		self.accesses.into_inner()
+	}
+
+	fn record(&self, access: StorageAccess) {
+		self.accesses.borrow_mut().push(access);
+	}
+}
+
+impl<'a, H: Hasher, E: EnumerableExternalities<H>> Externalities<H> for TracingExt<'a, H, E> {
+	fn storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		let value = self.inner.storage(key);
+		self.record(StorageAccess::Read { key: key.to_vec(), value: value.clone() });
+		value
+	}
+
+	fn original_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.inner.original_storage(key)
+	}
+
+	fn child_storage(&self, storage_key: ChildStorageKey<H>, key: &[u8]) -> Option<Vec<u8>> {
+		let raw_storage_key = storage_key.as_ref().to_vec();
+		let value = self.inner.child_storage(storage_key, key);
+		self.record(StorageAccess::ChildRead {
+			storage_key: raw_storage_key,
+			key: key.to_vec(),
+			value: value.clone(),
+		});
+		value
+	}
+
+	fn kill_child_storage(&mut self, storage_key: ChildStorageKey<H>) {
+		let raw_storage_key = storage_key.as_ref().to_vec();
+		let old = ChildStorageKey::<H>::from_slice(&raw_storage_key)
+			.map(|storage_key| self.inner.child_storage_pairs(storage_key))
+			.unwrap_or_default();
+		self.record(StorageAccess::KillChild { storage_key: raw_storage_key, old });
+		self.inner.kill_child_storage(storage_key)
+	}
+
+	fn clear_prefix(&mut self, prefix: &[u8]) {
+		// clearing child storages by prefix is refused, nothing is removed then
+		let old = if is_child_storage_key(prefix) {
+			Vec::new()
+		} else {
+			self.inner.storage_pairs_with_prefix(prefix)
+		};
+		self.record(StorageAccess::ClearPrefix { prefix: prefix.to_vec(), old });
+		self.inner.clear_prefix(prefix)
+	}
+
+	fn place_storage(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+		let old = self.inner.storage(&key);
+		self.record(StorageAccess::Write { key: key.clone(), old, new: value.clone() });
+		self.inner.place_storage(key, value)
+	}
+
+	fn place_child_storage(&mut self, storage_key: ChildStorageKey<H>, key: Vec<u8>, value: Option<Vec<u8>>) {
+		let raw_storage_key = storage_key.as_ref().to_vec();
+		let old = ChildStorageKey::<H>::from_slice(&raw_storage_key)
+			.and_then(|storage_key| self.inner.child_storage(storage_key, &key));
+		self.record(StorageAccess::ChildWrite {
+			storage_key: raw_storage_key,
+			key: key.clone(),
+			old,
+			new: value.clone(),
+		});
+		self.inner.place_child_storage(storage_key, key, value)
+	}
+
+	fn chain_id(&self) -> u64 {
+		self.inner.chain_id()
+	}
+
+	fn storage_root(&mut self) -> H::Out where H::Out: Ord {
+		self.inner.storage_root()
+	}
+
+	fn child_storage_root(&mut self, storage_key: ChildStorageKey<H>) -> Vec<u8> {
+		self.inner.child_storage_root(storage_key)
+	}
+
+	fn storage_changes_root(&mut self, parent: H::Out, parent_num: u64) -> Option<H::Out> where H::Out: Ord {
+		self.inner.storage_changes_root(parent, parent_num)
+	}
+
+	fn submit_extrinsic(&mut self, extrinsic: Vec<u8>) -> Result<(), ()> {
+		self.inner.submit_extrinsic(extrinsic)
+	}
+
+	fn offchain(&mut self) -> Option<&mut dyn OffchainExt> {
+		self.inner.offchain()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::Blake2Hasher;
+	use crate::TestExternalities;
+
+	#[test]
+	fn should_record_storage_accesses() {
+		let child_key = b":child_storage:default:child".to_vec();
+		let child = || ChildStorageKey::<Blake2Hasher>::from_slice(&child_key).unwrap();
+		let mut ext = TestExternalities::<Blake2Hasher>::default();
+		ext.set_storage(b"key".to_vec(), b"old".to_vec());
+		ext.set_storage(b"prefix2".to_vec(), b"2".to_vec());
+		ext.set_storage(b"prefix1".to_vec(), b"1".to_vec());
+		ext.set_child_storage(child(), b"child_key".to_vec(), b"child".to_vec());
+
+		let mut tracing = TracingExt::new(&mut ext);
+		assert_eq!(tracing.storage(b"key"), Some(b"old".to_vec()));
+		tracing.set_storage(b"key".to_vec(), b"new".to_vec());
+		tracing.clear_storage(b"other");
+		tracing.clear_prefix(b"prefix");
+		tracing.kill_child_storage(child());
+
+		assert_eq!(tracing.into_accesses(), vec![
+			StorageAccess::Read { key: b"key".to_vec(), value: Some(b"old".to_vec()) },
+			StorageAccess::Write { key: b"key".to_vec(), old: Some(b"old".to_vec()), new: Some(b"new".to_vec()) },
+			StorageAccess::Write { key: b"other".to_vec(), old: None, new: None },
+			StorageAccess::ClearPrefix {
+				prefix: b"prefix".to_vec(),
+				old: vec![(b"prefix1".to_vec(), b"1".to_vec()), (b"prefix2".to_vec(), b"2".to_vec())],
+			},
+			StorageAccess::KillChild {
+				storage_key: child_key.clone(),
+				old: vec![(b"child_key".to_vec(), b"child".to_vec())],
+			},
+		]);
+		assert_eq!(ext.storage(b"key"), Some(b"new".to_vec()));
+		assert_eq!(ext.storage(b"prefix1"), None);
+	}
+}
@@ -81,6 +81,15 @@ impl<S: TrieBackendStorage<H>, H: Hasher> Backend<H> for TrieBackend<S, H> where
 		self.essence.for_keys_in_child_storage(storage_key, f)
 	}
 
+	fn for_key_values_with_prefix<F: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		f: F,
+	) {
+		self.essence.for_key_values_with_prefix(prefix, start_at, f)
+	}
+
 	fn pairs(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
 		let mut read_overlay = S::Overlay::default();
 		let eph = Ephemeral::new(self.essence.backend_storage(), &mut read_overlay);
@@ -245,6 +254,23 @@ pub mod tests {
 		).pairs().is_empty());
 	}
 
+	#[test]
+	fn key_values_with_prefix_are_iterated_in_order() {
+		let collect = |start_at: Option<&[u8]>, limit: usize| {
+			let mut pairs = Vec::new();
+			test_trie().for_key_values_with_prefix(b"value", start_at, |key, value| {
+				pairs.push((key.to_vec(), value.to_vec()));
+				pairs.len() < limit
+			});
+			pairs
+		};
+
+		assert_eq!(collect(None, 10), vec![(b"value1".to_vec(), vec![42]), (b"value2".to_vec(), vec![24])]);
+		assert_eq!(collect(None, 1), vec![(b"value1".to_vec(), vec![42])]);
+		assert_eq!(collect(Some(&b"value2"[..]), 10), vec![(b"value2".to_vec(), vec![24])]);
+		assert_eq!(collect(Some(&b"value3"[..]), 10), vec![]);
+	}
+
 	#[test]
 	fn storage_root_is_non_default() {
 		assert!(test_trie().storage_root(::std::iter::empty()).0 != H256::repeat_byte(0));
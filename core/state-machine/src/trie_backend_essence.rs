@@ -141,6 +141,45 @@ impl<S: TrieBackendStorage<H>, H: Hasher> TrieBackendEssence<S, H> {
 			debug!(target: "trie", "Error while iterating by prefix: {}", e);
 		}
 	}
+
+	/// Execute given closure for all key/value pairs with keys starting with prefix, in order,
+	/// beginning at `start_at` if given, until the closure returns `false`.
+	pub fn for_key_values_with_prefix<F: FnMut(&[u8], &[u8]) -> bool>(
+		&self,
+		prefix: &[u8],
+		start_at: Option<&[u8]>,
+		mut f: F,
+	) {
+		let mut read_overlay = S::Overlay::default();
+		let eph = Ephemeral {
+			storage: &self.storage,
+			overlay: &mut read_overlay,
+		};
+
+		let mut iter = move || -> Result<(), Box<TrieError<H::Out>>> {
+			let trie = TrieDB::<H>::new(&eph, &self.root)?;
+			let mut iter = trie.iter()?;
+
+			match start_at {
+				Some(start) if start > prefix => iter.seek(start)?,
+				_ => iter.seek(prefix)?,
+			}
+
+			for x in iter {
+				let (key, value) = x?;
+
+				if !key.starts_with(prefix) || !f(&key, &value) {
+					break;
+				}
+			}
+
+			Ok(())
+		};
+
+		if let Err(e) = iter() {
+			debug!(target: "trie", "Error while iterating by prefix: {}", e);
+		}
+	}
 }
 
 pub(crate) struct Ephemeral<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> {
@@ -20,18 +20,26 @@
 //! in order to send real-time logging information to the telemetry
 //! server (if there is one). We use the async drain adapter of `slog`
 //! so that the logging thread doesn't get held up at all.
+//!
+//! Besides WebSocket servers, records may be written locally as newline-delimited
+//! JSON, by using a `file:///path/to/file` (rotated once it grows large) or a
+//! `stdout://` URL as an endpoint.
 
 use std::{io, time, thread};
 use std::sync::Arc;
 use parking_lot::Mutex;
 use slog::{Drain, o, OwnedKVList, Record};
-use log::trace;
+use log::{trace, warn};
 use rand::{thread_rng, Rng};
 pub use slog_scope::with_logger;
 pub use slog;
 use serde::{Serialize, Deserialize};
 use core::result;
 
+mod local;
+
+use local::LocalEndpoint;
+
 /// Configuration for telemetry.
 pub struct TelemetryConfig {
 	/// Collection of telemetry WebSocket servers with a corresponding verbosity level.
@@ -94,17 +102,37 @@ impl<D: Drain> Drain for Multiply<D> {
 /// Initialize telemetry.
 pub fn init_telemetry(config: TelemetryConfig) -> slog_scope::GlobalLoggerGuard {
 	let mut endpoint_drains: Vec<Box<slog::Filter<_, _>>> = Vec::new();
-	let mut out_syncs = Vec::new();
+	let mut remote_endpoints = Vec::new();
+	let mut has_local_endpoints = false;
 
 	// Set up a filter/drain for each endpoint
-	config.endpoints.0.iter().for_each(|(url, verbosity)| {
-		let writer = TelemetryWriter::new(Arc::new(url.to_owned()));
-		let out_sync = writer.out.clone();
-		out_syncs.push(out_sync);
-
-		let until_verbosity = *verbosity;
+	config.endpoints.0.into_iter().for_each(|(url, verbosity)| {
+		let endpoint_url = url.clone();
+		let writer: Box<dyn io::Write + Send> = match LocalEndpoint::parse(&url) {
+			Some(endpoint) => match endpoint.writer() {
+				Ok(writer) => {
+					has_local_endpoints = true;
+					writer
+				},
+				Err(e) => {
+					warn!(target: "telemetry", "Unable to open telemetry endpoint {}: {}", url, e);
+					return;
+				},
+			},
+			None => {
+				let writer = TelemetryWriter::new(Arc::new(url.to_owned()));
+				remote_endpoints.push((url, verbosity, writer.out.clone()));
+				Box::new(writer)
+			},
+		};
+
+		let until_verbosity = verbosity;
+		// a failing endpoint (e.g. a full disk) must not bring the node down.
+		let drain = slog_json::Json::new(writer).add_default_keys().set_flush(true).build()
+			.map_err(move |e| warn!(target: "telemetry", "Unable to write to telemetry endpoint {}: {}", endpoint_url, e))
+			.ignore_res();
 		let filter = slog::Filter(
-			slog_json::Json::default(writer).fuse(),
+			drain,
 			move |rec| {
 				let tag = rec.tag().parse::<u8>()
 					.expect("`telemetry!` macro requires tag.");
@@ -123,14 +151,16 @@ pub fn init_telemetry(config: TelemetryConfig) -> slog_scope::GlobalLoggerGuard
 	);
 	let logger_guard = slog_scope::set_global_logger(root);
 
-	// Spawn a thread for each endpoint
+	// Local endpoints are connected right away
 	let on_connect = Arc::new(config.on_connect);
-	config.endpoints.0.into_iter().for_each(|(url, verbosity)| {
-		let inner_verbosity = Arc::new(verbosity.to_owned());
-		let inner_on_connect = Arc::clone(&on_connect);
+	if has_local_endpoints {
+		(on_connect)();
+	}
 
-		let out_sync = out_syncs.remove(0);
-		let out_sync = Arc::clone(&out_sync);
+	// Spawn a thread for each remote endpoint
+	remote_endpoints.into_iter().for_each(|(url, verbosity, out_sync)| {
+		let inner_verbosity = Arc::new(verbosity);
+		let inner_on_connect = Arc::clone(&on_connect);
 
 		thread::spawn(move || {
 			loop {
@@ -0,0 +1,163 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Telemetry endpoints writing the records locally instead of sending them to a server.
+
+use std::{fs, io::{self, Write}, path::{Path, PathBuf}};
+
+/// Prefix of the URLs of file endpoints.
+const FILE_URL_PREFIX: &str = "file://";
+/// URL of the standard output endpoint.
+const STDOUT_URL: &str = "stdout://";
+/// Size of a telemetry file after which it is rotated.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// Number of rotated telemetry files kept next to the current one.
+const MAX_ROTATED_FILES: usize = 4;
+
+/// Endpoint writing newline-delimited JSON records locally.
+#[derive(Debug, PartialEq)]
+pub enum LocalEndpoint {
+	/// Write to a file, rotated once it grows too large.
+	File(PathBuf),
+	/// Write to the standard output.
+	Stdout,
+}
+
+impl LocalEndpoint {
+	/// Parse the endpoint from a telemetry URL, `None` if it's not a local one.
+	pub fn parse(url: &str) -> Option<Self> {
+		if url == STDOUT_URL {
+			Some(LocalEndpoint::Stdout)
+		} else if url.starts_with(FILE_URL_PREFIX) {
+			Some(LocalEndpoint::File(PathBuf::from(&url[FILE_URL_PREFIX.len()..])))
+		} else {
+			None
+		}
+	}
+
+	/// Open the writer of this endpoint.
+	pub fn writer(&self) -> io::Result<Box<dyn Write + Send>> {
+		Ok(match self {
+			LocalEndpoint::File(path) => Box::new(RotatingFile::open(path.clone(), MAX_FILE_SIZE)?),
+			LocalEndpoint::Stdout => Box::new(io::stdout()),
+		})
+	}
+}
+
+/// File which is moved aside once it exceeds the maximal size.
+///
+/// The rotation happens on flush, so that records are never split across files. The
+/// previous files are kept as `<path>.1` (most recent) up to `<path>.4`.
+pub struct RotatingFile {
+	path: PathBuf,
+	file: io::BufWriter<fs::File>,
+	size: u64,
+	max_size: u64,
+}
+
+impl RotatingFile {
+	/// Open the file at `path` for appending.
+	pub fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let file = Self::open_file(&path)?;
+		let size = file.metadata()?.len();
+
+		Ok(RotatingFile {
+			path,
+			file: io::BufWriter::new(file),
+			size,
+			max_size,
+		})
+	}
+
+	fn open_file(path: &Path) -> io::Result<fs::File> {
+		fs::OpenOptions::new().create(true).append(true).open(path)
+	}
+
+	fn rotated_path(&self, index: usize) -> PathBuf {
+		let mut path = self.path.clone().into_os_string();
+		path.push(format!(".{}", index));
+		path.into()
+	}
+
+	fn rotate(&mut self) -> io::Result<()> {
+		for index in (1..MAX_ROTATED_FILES).rev() {
+			let rotated = self.rotated_path(index);
+			if rotated.exists() {
+				fs::rename(&rotated, self.rotated_path(index + 1))?;
+			}
+		}
+		fs::rename(&self.path, self.rotated_path(1))?;
+
+		self.file = io::BufWriter::new(Self::open_file(&self.path)?);
+		self.size = 0;
+		Ok(())
+	}
+}
+
+impl Write for RotatingFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let written = self.file.write(buf)?;
+		self.size += written as u64;
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()?;
+		if self.size >= self.max_size {
+			self.rotate()?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempdir::TempDir;
+
+	#[test]
+	fn should_parse_local_endpoints() {
+		assert_eq!(LocalEndpoint::parse("stdout://"), Some(LocalEndpoint::Stdout));
+		assert_eq!(
+			LocalEndpoint::parse("file:///tmp/telemetry.json"),
+			Some(LocalEndpoint::File("/tmp/telemetry.json".into()))
+		);
+		assert_eq!(LocalEndpoint::parse("wss://telemetry.polkadot.io/submit/"), None);
+	}
+
+	#[test]
+	fn should_rotate_file_on_flush() {
+		let temp_dir = TempDir::new("telemetry").unwrap();
+		let path = temp_dir.path().join("logs").join("telemetry.json");
+		let mut file = RotatingFile::open(path.clone(), 8).unwrap();
+
+		file.write_all(b"{\"a\":1}\n").unwrap();
+		file.write_all(b"{\"b\":2}\n").unwrap();
+		file.flush().unwrap();
+		file.write_all(b"{\"c\":3}\n").unwrap();
+		file.flush().unwrap();
+		file.write_all(b"{}\n").unwrap();
+		file.flush().unwrap();
+
+		let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+		assert_eq!(read(path.clone()), "{}\n");
+		assert_eq!(read(file.rotated_path(1)), "{\"c\":3}\n");
+		assert_eq!(read(file.rotated_path(2)), "{\"a\":1}\n{\"b\":2}\n");
+	}
+}
@@ -365,6 +365,33 @@ cfg_if! {
 				}
 			}
 
+			impl client_api::DryRunApi<Block> for Runtime {
+				fn dry_run(extrinsic: <Block as BlockT>::Extrinsic) -> (ApplyResult, Vec<u8>) {
+					// the test runtime doesn't deposit any events
+					(system::execute_transaction(extrinsic), Vec::new())
+				}
+			}
+
+			impl client_api::TransactionPaymentApi<Block, u64> for Runtime {
+				fn query_fee(extrinsic: <Block as BlockT>::Extrinsic) -> u64 {
+					// the test runtime doesn't charge fees, pretend it's one unit per byte
+					extrinsic.encode().len() as u64
+				}
+			}
+
+			impl client_api::AccountNonceApi<Block, AccountId, u64> for Runtime {
+				fn account_nonce(account: AccountId) -> u64 {
+					system::nonce_of(account)
+				}
+			}
+
+			impl client_api::EventsApi<Block> for Runtime {
+				fn block_events() -> Vec<client_api::BlockEvent> {
+					// The test runtime does not deposit any events.
+					Vec::new()
+				}
+			}
+
 			impl block_builder_api::BlockBuilder<Block> for Runtime {
 				fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyResult {
 					system::execute_transaction(extrinsic)
@@ -503,6 +530,33 @@ cfg_if! {
 				}
 			}
 
+			impl client_api::DryRunApi<Block> for Runtime {
+				fn dry_run(extrinsic: <Block as BlockT>::Extrinsic) -> (ApplyResult, Vec<u8>) {
+					// the test runtime doesn't deposit any events
+					(system::execute_transaction(extrinsic), Vec::new())
+				}
+			}
+
+			impl client_api::TransactionPaymentApi<Block, u64> for Runtime {
+				fn query_fee(extrinsic: <Block as BlockT>::Extrinsic) -> u64 {
+					// the test runtime doesn't charge fees, pretend it's one unit per byte
+					extrinsic.encode().len() as u64
+				}
+			}
+
+			impl client_api::AccountNonceApi<Block, AccountId, u64> for Runtime {
+				fn account_nonce(account: AccountId) -> u64 {
+					system::nonce_of(account)
+				}
+			}
+
+			impl client_api::EventsApi<Block> for Runtime {
+				fn block_events() -> Vec<client_api::BlockEvent> {
+					// The test runtime does not deposit any events.
+					Vec::new()
+				}
+			}
+
 			impl block_builder_api::BlockBuilder<Block> for Runtime {
 				fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyResult {
 					system::execute_transaction(extrinsic)
@@ -18,7 +18,7 @@
 //! and depositing logs.
 
 use rstd::prelude::*;
-use runtime_io::{storage_root, enumerated_trie_root, storage_changes_root, twox_128, blake2_256};
+use runtime_io::{storage_root, enumerated_trie_root, storage_changes_root, blake2_256};
 use runtime_support::storage::{self, StorageValue, StorageMap};
 use runtime_support::storage_items;
 use runtime_primitives::traits::{Hash as HashT, BlakeTwo256, Digest as DigestT};
@@ -168,9 +168,8 @@ pub fn validate_transaction(utx: Extrinsic) -> TransactionValidity {
 		return TransactionValidity::Unknown(ApplyError::Future as i8);
 	}
 
-	let hash = |from: &AccountId, nonce: u64| {
-		twox_128(&nonce.to_keyed_vec(&from.encode())).to_vec()
-	};
+	// same tags as the ones provided by `srml-executive`
+	let hash = |from: &AccountId, nonce: u64| (from, nonce).encode();
 	let requires = if tx.nonce != expected_nonce && tx.nonce > 0 {
 		let mut deps = Vec::new();
 		deps.push(hash(&tx.from, tx.nonce - 1));
@@ -407,16 +407,45 @@ impl<B: ChainApi> Pool<B> {
 		invalid
 	}
 
+	/// Remove the transactions providing any of the given tags from the pool.
+	///
+	/// Unlike `prune_tags`, the tags are not considered provided by the chain, so the
+	/// transactions requiring them are not promoted. Like with `remove_invalid`, all removed
+	/// transactions are returned, but only the ones providing the tags are temporarily banned.
+	pub fn remove_by_tags(&self, tags: &[Tag]) -> Vec<TransactionFor<B>> {
+		let hashes = {
+			let pool = self.pool.read();
+			let provides_tag = |tx: &TransactionFor<B>| tx.provides.iter().any(|tag| tags.contains(tag));
+			pool.ready()
+				.filter(|tx| provides_tag(tx))
+				.map(|tx| tx.hash.clone())
+				.chain(pool.futures().filter(|tx| provides_tag(tx)).map(|tx| tx.hash.clone()))
+				.collect::<Vec<_>>()
+		};
+
+		self.remove_invalid(&hashes)
+	}
+
 	/// Get an iterator for ready transactions ordered by priority
 	pub fn ready(&self) -> impl Iterator<Item=TransactionFor<B>> {
 		self.pool.read().ready()
 	}
 
+	/// Get future transactions, waiting for some of their required tags
+	pub fn futures(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().futures().cloned().collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> base::Status {
 		self.pool.read().status()
 	}
 
+	/// Returns hashes of the temporarily banned transactions.
+	pub fn banned(&self) -> Vec<ExHash<B>> {
+		self.rotator.banned()
+	}
+
 	/// Returns transaction hash
 	#[cfg(test)]
 	fn hash_of(&self, xt: &ExtrinsicFor<B>) -> ExHash<B> {
@@ -549,6 +578,54 @@ mod tests {
 		assert_eq!(pool.ready().map(|v| v.hash).collect::<Vec<_>>(), vec![hash]);
 	}
 
+	#[test]
+	fn should_return_future_transactions() {
+		// given
+		let pool = pool();
+
+		// when
+		let hash = pool.submit_one(&BlockId::Number(0), uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 1,
+		})).unwrap();
+
+		// then
+		assert_eq!(pool.ready().count(), 0);
+		assert_eq!(pool.futures().into_iter().map(|v| v.hash).collect::<Vec<_>>(), vec![hash]);
+	}
+
+	#[test]
+	fn should_remove_transactions_by_provided_tags() {
+		// given
+		let pool = pool();
+		let transfer = |nonce| uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		});
+		let hash0 = pool.submit_one(&BlockId::Number(0), transfer(0)).unwrap();
+		let hash1 = pool.submit_one(&BlockId::Number(0), transfer(1)).unwrap();
+		let hash3 = pool.submit_one(&BlockId::Number(0), transfer(3)).unwrap();
+		assert_eq!(pool.status().ready, 2);
+		assert_eq!(pool.status().future, 1);
+
+		// when
+		let removed = pool.remove_by_tags(&[vec![1], vec![3]]);
+
+		// then
+		let mut removed = removed.into_iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![hash1, hash3]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<Vec<_>>(), vec![hash0]);
+		assert_eq!(pool.status().future, 0);
+		let mut banned = pool.banned();
+		banned.sort();
+		assert_eq!(banned, vec![hash1, hash3]);
+	}
+
 	#[test]
 	fn should_reject_if_temporarily_banned() {
 		// given
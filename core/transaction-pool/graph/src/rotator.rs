@@ -58,6 +58,16 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 		self.banned_until.read().contains_key(hash)
 	}
 
+	/// Returns the hashes of all currently banned extrinsics.
+	pub fn banned(&self) -> Vec<Hash> {
+		let now = Instant::now();
+		self.banned_until.read()
+			.iter()
+			.filter(|(_, until)| **until >= now)
+			.map(|(hash, _)| hash.clone())
+			.collect()
+	}
+
 	/// Bans given set of hashes.
 	pub fn ban(&self, now: &Instant, hashes: impl IntoIterator<Item=Hash>) {
 		let mut banned = self.banned_until.write();
@@ -155,6 +165,20 @@ mod tests {
 		assert!(rotator.is_banned(&hash));
 	}
 
+	#[test]
+	fn should_not_list_expired_bans() {
+		// given
+		let (hash, tx) = tx();
+		let rotator = rotator();
+		let past = Instant::now() - rotator.ban_time - rotator.ban_time;
+
+		// when
+		assert!(rotator.ban_if_stale(&past, 1, &tx));
+
+		// then
+		assert!(rotator.is_banned(&hash));
+		assert!(rotator.banned().is_empty());
+	}
 
 	#[test]
 	fn should_clear_banned() {
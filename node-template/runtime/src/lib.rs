@@ -13,13 +13,15 @@ use primitives::bytes;
 use primitives::{ed25519, sr25519, OpaqueMetadata};
 use runtime_primitives::{
 	ApplyResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
-	traits::{self, NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify}
+	traits::{self, NumberFor, BlakeTwo256, Block as BlockT, StaticLookup, Verify},
+	weights::GetDispatchInfo,
 };
 use client::{
 	block_builder::api::{CheckInherentsResult, InherentData, self as block_builder_api},
 	runtime_api, impl_runtime_apis
 };
 use version::RuntimeVersion;
+use support::parameter_types;
 #[cfg(feature = "std")]
 use version::NativeVersion;
 
@@ -54,6 +56,9 @@ pub type BlockNumber = u64;
 /// Index of an account's extrinsic in the chain.
 pub type Nonce = u64;
 
+/// Balance of an account.
+pub type Balance = u128;
+
 /// Used for the module template in `./template.rs`
 mod template;
 
@@ -108,6 +113,11 @@ pub fn native_version() -> NativeVersion {
 	}
 }
 
+parameter_types! {
+	// Used by the executive to detect runtime upgrades.
+	pub const SpecVersion: u32 = VERSION.spec_version;
+}
+
 impl system::Trait for Runtime {
 	/// The identifier used to distinguish between accounts.
 	type AccountId = AccountId;
@@ -143,6 +153,9 @@ impl consensus::Trait for Runtime {
 	// The aura module handles offline-reports internally
 	// rather than using an explicit report system.
 	type InherentOfflineReport = ();
+	// Misbehavior reports are not handled.
+	type MisbehaviorReport = ();
+	type OnMisbehaviorReport = ();
 	/// The ubiquitous log type.
 	type Log = Log;
 }
@@ -167,7 +180,7 @@ impl timestamp::Trait for Runtime {
 
 impl balances::Trait for Runtime {
 	/// The type for recording an account's balance.
-	type Balance = u128;
+	type Balance = Balance;
 	/// What to do if an account's free balance gets zeroed.
 	type OnFreeBalanceZero = ();
 	/// What to do if a new account is created.
@@ -224,7 +237,7 @@ pub type UncheckedExtrinsic = generic::UncheckedMortalCompactExtrinsic<Address,
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Nonce, Call>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules>;
+pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules, SpecVersion>;
 
 // Implement our runtime API endpoints. This is just a bunch of proxying.
 impl_runtime_apis! {
@@ -280,6 +293,38 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_api::DryRunApi<Block> for Runtime {
+		fn dry_run(extrinsic: <Block as BlockT>::Extrinsic) -> (ApplyResult, Vec<u8>) {
+			let previous_events = System::events().len();
+			let result = Executive::apply_extrinsic(extrinsic);
+			(result, System::events()[previous_events..].encode())
+		}
+	}
+
+	impl runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
+		fn query_fee(extrinsic: <Block as BlockT>::Extrinsic) -> Balance {
+			Balances::compute_fee(extrinsic.encode().len(), extrinsic.function.get_dispatch_info().weight)
+		}
+	}
+
+	impl runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
+		fn account_nonce(account: AccountId) -> Nonce {
+			System::account_nonce(account)
+		}
+	}
+
+	impl runtime_api::EventsApi<Block> for Runtime {
+		fn block_events() -> Vec<runtime_api::BlockEvent> {
+			System::events().into_iter().map(|record| runtime_api::BlockEvent {
+				extrinsic_index: match record.phase {
+					system::Phase::ApplyExtrinsic(index) => Some(index),
+					system::Phase::Finalization => None,
+				},
+				event: record.event.encode(),
+			}).collect()
+		}
+	}
+
 	impl consensus_aura::AuraApi<Block> for Runtime {
 		fn slot_duration() -> u64 {
 			Aura::slot_duration()
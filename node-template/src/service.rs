@@ -5,11 +5,12 @@
 use std::sync::Arc;
 use log::info;
 use transaction_pool::{self, txpool::{Pool as TransactionPool}};
-use node_template_runtime::{self, GenesisConfig, opaque::Block, RuntimeApi};
+use node_template_runtime::{self, AccountId, Balance, GenesisConfig, Nonce, opaque::Block, RuntimeApi};
+use substrate_rpc::{payment::{Payment, PaymentApi}, system::{Account, AccountApi}};
 use substrate_service::{
 	FactoryFullConfiguration, LightComponents, FullComponents, FullBackend,
 	FullClient, LightClient, LightBackend, FullExecutor, LightExecutor,
-	TaskExecutor,
+	TaskExecutor, RpcExtension, RpcHandler, Roles, SharedKey,
 	error::{Error as ServiceError, ErrorKind as ServiceErrorKind},
 };
 use basic_authorship::ProposerFactory;
@@ -57,9 +58,11 @@ construct_service_factory! {
 				FullComponents::<Factory>::new(config, executor)
 			},
 		AuthoritySetup = {
-			|service: Self::FullService, executor: TaskExecutor, key: Option<Arc<Pair>>| {
-				if let Some(key) = key {
-					info!("Using authority key {}", key.public());
+			|service: Self::FullService, executor: TaskExecutor, key: SharedKey<Pair>| {
+				if service.config.roles == Roles::AUTHORITY {
+					if let Some(key) = key.get() {
+						info!("Using authority key {}", key.public());
+					}
 					let proposer = Arc::new(ProposerFactory {
 						client: service.client(),
 						transaction_pool: service.transaction_pool(),
@@ -91,7 +94,7 @@ construct_service_factory! {
 			Self::Block,
 		>
 			{ |config: &mut FactoryFullConfiguration<Self> , client: Arc<FullClient<Self>>, _select_chain: Self::SelectChain| {
-					import_queue::<_, _, _, Pair>(
+					import_queue::<_, _, _, Pair, _>(
 						SlotDuration::get_or_compute(&*client)?,
 						client.clone(),
 						None,
@@ -100,6 +103,7 @@ construct_service_factory! {
 						client,
 						NothingExtra,
 						config.custom.inherent_data_providers.clone(),
+						(),
 					).map_err(Into::into)
 				}
 			},
@@ -107,7 +111,7 @@ construct_service_factory! {
 			Self::Block,
 		>
 			{ |config: &mut FactoryFullConfiguration<Self>, client: Arc<LightClient<Self>>| {
-					import_queue::<_, _, _, Pair>(
+					import_queue::<_, _, _, Pair, _>(
 						SlotDuration::get_or_compute(&*client)?,
 						client.clone(),
 						None,
@@ -116,6 +120,7 @@ construct_service_factory! {
 						client,
 						NothingExtra,
 						config.custom.inherent_data_providers.clone(),
+						(),
 					).map_err(Into::into)
 				}
 			},
@@ -130,5 +135,20 @@ construct_service_factory! {
 		FinalityProofProvider = { |_client: Arc<FullClient<Self>>| {
 			Ok(None)
 		}},
+		FullRpcExtensions = { |client: Arc<FullClient<Self>>, transaction_pool, _network| {
+			let payment = {
+				let client = client.clone();
+				move |io: &mut RpcHandler| {
+					io.extend_with(Payment::<_, _, _, _, Balance>::new(client.clone()).to_delegate())
+				}
+			};
+			let account = move |io: &mut RpcHandler| {
+				io.extend_with(Account::<_, _, _, _, AccountId, Nonce>::new(
+					client.clone(),
+					transaction_pool.clone(),
+				).to_delegate())
+			};
+			Ok(vec![Box::new(payment) as Box<dyn RpcExtension>, Box::new(account) as Box<dyn RpcExtension>])
+		}},
 	}
 }
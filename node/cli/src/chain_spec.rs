@@ -20,7 +20,7 @@ use primitives::{ed25519::Public as AuthorityId, ed25519, sr25519, Pair, crypto:
 use node_primitives::AccountId;
 use node_runtime::{ConsensusConfig, CouncilSeatsConfig, CouncilVotingConfig, DemocracyConfig,
 	SessionConfig, StakingConfig, StakerStatus, TimestampConfig, BalancesConfig, TreasuryConfig,
-	SudoConfig, ContractConfig, GrandpaConfig, IndicesConfig, Permill, Perbill};
+	SudoConfig, ContractConfig, GrandpaConfig, IndicesConfig, SystemConfig, Permill, Perbill, VERSION};
 pub use node_runtime::GenesisConfig;
 use substrate_service;
 use hex_literal::hex;
@@ -83,10 +83,14 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 			code: include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.compact.wasm").to_vec(),    // FIXME change once we have #1252
 			authorities: initial_authorities.iter().map(|x| x.2.clone()).collect(),
 		}),
-		system: None,
+		system: Some(SystemConfig {
+			runtime_spec_version: Some(VERSION.spec_version),
+			..Default::default()
+		}),
 		balances: Some(BalancesConfig {
 			transaction_base_fee: 1 * CENTS,
 			transaction_byte_fee: 10 * MILLICENTS,
+			transaction_weight_fee: MILLICENTS / 10,
 			balances: endowed_accounts.iter().cloned()
 				.map(|k| (k, ENDOWMENT))
 				.chain(initial_authorities.iter().map(|x| (x.0.clone(), STASH)))
@@ -109,6 +113,7 @@ fn staging_testnet_config_genesis() -> GenesisConfig {
 		staking: Some(StakingConfig {
 			current_era: 0,
 			offline_slash: Perbill::from_billionths(1_000_000),
+			equivocation_slash: Perbill::from_percent(10),
 			session_reward: Perbill::from_billionths(2_065),
 			current_session_reward: 0,
 			validator_count: 7,
@@ -243,7 +248,7 @@ pub fn testnet_genesis(
 	});
 
 	const STASH: u128 = 1 << 20;
-	const ENDOWMENT: u128 = 1 << 20;
+	const ENDOWMENT: u128 = 1 << 60;
 
 	let mut contract_config = ContractConfig {
 		signed_claim_handicap: 2,
@@ -272,13 +277,17 @@ pub fn testnet_genesis(
 			code: include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.compact.wasm").to_vec(),
 			authorities: initial_authorities.iter().map(|x| x.2.clone()).collect(),
 		}),
-		system: None,
+		system: Some(SystemConfig {
+			runtime_spec_version: Some(VERSION.spec_version),
+			..Default::default()
+		}),
 		indices: Some(IndicesConfig {
 			ids: endowed_accounts.clone(),
 		}),
 		balances: Some(BalancesConfig {
 			transaction_base_fee: 1,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 1,
 			existential_deposit: 500,
 			transfer_fee: 0,
 			creation_fee: 0,
@@ -297,6 +306,7 @@ pub fn testnet_genesis(
 			sessions_per_era: 5,
 			bonding_duration: 12,
 			offline_slash: Perbill::zero(),
+			equivocation_slash: Perbill::zero(),
 			session_reward: Perbill::zero(),
 			current_session_reward: 0,
 			offline_slash_grace: 0,
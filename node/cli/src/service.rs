@@ -22,22 +22,31 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use client::{self, LongestChain};
-use consensus::{import_queue, start_aura, AuraImportQueue, SlotDuration, NothingExtra};
+use consensus::{
+	import_queue, start_aura, AuraImportQueue, SlotDuration, NothingExtra, ReportMisbehavior,
+};
+use futures::{Future, Stream, sync::mpsc};
 use grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider};
 use node_executor;
+use parity_codec::{Encode, Decode};
 use primitives::{Pair as PairT, ed25519};
-use node_primitives::Block;
-use node_runtime::{GenesisConfig, RuntimeApi};
+use node_primitives::{
+	AccountId, AuthorityId, AuthoritySignature, Balance, Block, BlockNumber, Hash, Index,
+};
+use node_runtime::{Call, ConsensusCall, GenesisConfig, GrandpaCall, RuntimeApi, UncheckedExtrinsic};
+use sr_primitives::generic::BlockId;
+use substrate_rpc::{payment::{Payment, PaymentApi}, system::{Account, AccountApi}};
 use substrate_service::{
 	FactoryFullConfiguration, LightComponents, FullComponents, FullBackend,
 	FullClient, LightClient, LightBackend, FullExecutor, LightExecutor, TaskExecutor,
+	RpcExtension, RpcHandler, Roles, SharedKey,
 	error::{Error as ServiceError, ErrorKind as ServiceErrorKind},
 };
 use transaction_pool::{self, txpool::{Pool as TransactionPool}};
 use inherents::InherentDataProviders;
 use network::construct_simple_protocol;
 use substrate_service::construct_service_factory;
-use log::info;
+use log::{info, warn};
 use substrate_service::TelemetryOnConnect;
 
 construct_simple_protocol! {
@@ -45,19 +54,52 @@ construct_simple_protocol! {
 	pub struct NodeProtocol where Block = Block { }
 }
 
+/// Sends proofs of misbehavior found by the consensus engines to be reported on-chain.
+///
+/// The reports are unsigned calls, submitted to the transaction pool by the task
+/// reading the other end of the channel.
+#[derive(Clone)]
+pub struct MisbehaviorReporter(mpsc::UnboundedSender<Call>);
+
+impl MisbehaviorReporter {
+	fn report(&self, call: Call) {
+		if self.0.unbounded_send(call).is_err() {
+			warn!("Misbehavior report dropped: reports are not submitted anymore");
+		}
+	}
+}
+
+impl ReportMisbehavior<consensus::EquivocationProof<Hash, AuthorityId, AuthoritySignature>> for MisbehaviorReporter {
+	fn report_misbehavior(&self, proof: consensus::EquivocationProof<Hash, AuthorityId, AuthoritySignature>) {
+		self.report(Call::Consensus(ConsensusCall::report_misbehavior(proof)))
+	}
+}
+
+impl ReportMisbehavior<grandpa::EquivocationProof<Hash, BlockNumber>> for MisbehaviorReporter {
+	fn report_misbehavior(&self, proof: grandpa::EquivocationProof<Hash, BlockNumber>) {
+		self.report(Call::Grandpa(GrandpaCall::report_misbehavior(proof)))
+	}
+}
+
 /// Node specific configuration
 pub struct NodeConfig<F: substrate_service::ServiceFactory> {
 	/// grandpa connection to import block
 	// FIXME #1134 rather than putting this on the config, let's have an actual intermediate setup state
 	pub grandpa_import_setup: Option<(Arc<grandpa::BlockImportForService<F>>, grandpa::LinkHalfForService<F>)>,
 	inherent_data_providers: InherentDataProviders,
+	misbehavior_reporter: MisbehaviorReporter,
+	// the transaction pool is not there yet when the import queue is built.
+	misbehavior_reports: Option<mpsc::UnboundedReceiver<Call>>,
 }
 
 impl<F> Default for NodeConfig<F> where F: substrate_service::ServiceFactory {
 	fn default() -> NodeConfig<F> {
+		let (sender, receiver) = mpsc::unbounded();
 		NodeConfig {
 			grandpa_import_setup: None,
 			inherent_data_providers: InherentDataProviders::new(),
+			misbehavior_reporter: MisbehaviorReporter(sender),
+			misbehavior_reports: Some(receiver),
 		}
 	}
 }
@@ -78,12 +120,36 @@ construct_service_factory! {
 			{ |config: FactoryFullConfiguration<Self>, executor: TaskExecutor|
 				FullComponents::<Factory>::new(config, executor) },
 		AuthoritySetup = {
-			|mut service: Self::FullService, executor: TaskExecutor, local_key: Option<Arc<ed25519::Pair>>| {
+			|mut service: Self::FullService, executor: TaskExecutor, local_key: SharedKey<ed25519::Pair>| {
 				let (block_import, link_half) = service.config.custom.grandpa_import_setup.take()
 					.expect("Link Half and Block Import are present for Full Services or setup failed before. qed");
 
-				if let Some(ref key) = local_key {
-					info!("Using authority key {}", key.public());
+				if let Some(reports) = service.config.custom.misbehavior_reports.take() {
+					let client = service.client();
+					let transaction_pool = service.transaction_pool();
+					let submit_reports = reports.for_each(move |call| {
+						let best_hash = match client.info() {
+							Ok(info) => info.chain.best_hash,
+							Err(e) => {
+								warn!("Unable to submit misbehavior report: {:?}", e);
+								return Ok(())
+							},
+						};
+						let xt = Decode::decode(&mut &UncheckedExtrinsic::new_unsigned(call).encode()[..])
+							.expect("Runtime extrinsics are encoded as opaque extrinsics; qed");
+						if let Err(e) = transaction_pool.submit_one(&BlockId::hash(best_hash), xt) {
+							warn!("Unable to submit misbehavior report: {:?}", e);
+						}
+						Ok(())
+					});
+					executor.spawn(submit_reports.select(service.on_exit()).then(|_| Ok(())));
+				}
+
+				let is_authority = service.config.roles == Roles::AUTHORITY;
+				if is_authority {
+					if let Some(key) = local_key.get() {
+						info!("Using authority key {}", key.public());
+					}
 					let proposer = Arc::new(substrate_basic_authorship::ProposerFactory {
 						client: service.client(),
 						transaction_pool: service.transaction_pool(),
@@ -95,7 +161,7 @@ construct_service_factory! {
 						.ok_or_else(|| ServiceError::from(ServiceErrorKind::SelectChainRequired))?;
 					executor.spawn(start_aura(
 						SlotDuration::get_or_compute(&*client)?,
-						key.clone(),
+						local_key.clone(),
 						client,
 						select_chain,
 						block_import.clone(),
@@ -105,14 +171,14 @@ construct_service_factory! {
 						service.config.custom.inherent_data_providers.clone(),
 						service.config.force_authoring,
 					)?);
-
-					info!("Running Grandpa session as Authority {}", key.public());
 				}
 
-				let local_key = if service.config.disable_grandpa {
-					None
-				} else {
+				let is_voter = is_authority && !service.config.disable_grandpa;
+				let local_key = if is_voter {
+					info!("Running Grandpa session as Authority");
 					local_key
+				} else {
+					SharedKey::default()
 				};
 
 				let config = grandpa::Config {
@@ -123,8 +189,8 @@ construct_service_factory! {
 					name: Some(service.config.name.clone())
 				};
 
-				match config.local_key {
-					None => {
+				match is_voter {
+					false => {
 						executor.spawn(grandpa::run_grandpa_observer(
 							config,
 							link_half,
@@ -132,7 +198,7 @@ construct_service_factory! {
 							service.on_exit(),
 						)?);
 					},
-					Some(_) => {
+					true => {
 						let telemetry_on_connect = TelemetryOnConnect {
 							on_exit: Box::new(service.on_exit()),
 							telemetry_connection_sinks: service.telemetry_on_connect_stream(),
@@ -145,6 +211,7 @@ construct_service_factory! {
 							inherent_data_providers: service.config.custom.inherent_data_providers.clone(),
 							on_exit: service.on_exit(),
 							telemetry_on_connect: Some(telemetry_on_connect),
+							misbehavior_reporter: Some(Arc::new(service.config.custom.misbehavior_reporter.clone())),
 						};
 						executor.spawn(grandpa::run_grandpa_voter(grandpa_config)?);
 					},
@@ -167,7 +234,7 @@ construct_service_factory! {
 
 				config.custom.grandpa_import_setup = Some((block_import.clone(), link_half));
 
-				import_queue::<_, _, _, ed25519::Pair>(
+				import_queue::<_, _, _, ed25519::Pair, _>(
 					slot_duration,
 					block_import,
 					Some(justification_import),
@@ -176,6 +243,7 @@ construct_service_factory! {
 					client,
 					NothingExtra,
 					config.custom.inherent_data_providers.clone(),
+					config.custom.misbehavior_reporter.clone(),
 				).map_err(Into::into)
 			}},
 		LightImportQueue = AuraImportQueue<Self::Block>
@@ -191,7 +259,7 @@ construct_service_factory! {
 				let finality_proof_import = block_import.clone();
 				let finality_proof_request_builder = finality_proof_import.create_finality_proof_request_builder();
 
-				import_queue::<_, _, _, ed25519::Pair>(
+				import_queue::<_, _, _, ed25519::Pair, _>(
 					SlotDuration::get_or_compute(&*client)?,
 					block_import,
 					None,
@@ -200,6 +268,7 @@ construct_service_factory! {
 					client,
 					NothingExtra,
 					config.custom.inherent_data_providers.clone(),
+					(),
 				).map_err(Into::into)
 			}},
 		SelectChain = LongestChain<FullBackend<Self>, Self::Block>
@@ -213,6 +282,21 @@ construct_service_factory! {
 		FinalityProofProvider = { |client: Arc<FullClient<Self>>| {
 			Ok(Some(Arc::new(GrandpaFinalityProofProvider::new(client.clone(), client)) as _))
 		}},
+		FullRpcExtensions = { |client: Arc<FullClient<Self>>, transaction_pool, _network| {
+			let payment = {
+				let client = client.clone();
+				move |io: &mut RpcHandler| {
+					io.extend_with(Payment::<_, _, _, _, Balance>::new(client.clone()).to_delegate())
+				}
+			};
+			let account = move |io: &mut RpcHandler| {
+				io.extend_with(Account::<_, _, _, _, AccountId, Index>::new(
+					client.clone(),
+					transaction_pool.clone(),
+				).to_delegate())
+			};
+			Ok(vec![Box::new(payment) as Box<dyn RpcExtension>, Box::new(account) as Box<dyn RpcExtension>])
+		}},
 	}
 }
 
@@ -273,6 +273,7 @@ mod tests {
 			balances: Some(BalancesConfig {
 				transaction_base_fee: 1,
 				transaction_byte_fee: 0,
+				transaction_weight_fee: 0,
 				balances: vec![
 					(alice(), 111),
 					(bob(), 100),
@@ -307,6 +308,7 @@ mod tests {
 				minimum_validator_count: 0,
 				bonding_duration: 0,
 				offline_slash: Perbill::zero(),
+				equivocation_slash: Perbill::zero(),
 				session_reward: Perbill::zero(),
 				current_session_reward: 0,
 				offline_slash_grace: 0,
@@ -864,6 +866,49 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn dry_run_applies_extrinsic_in_initialized_block() {
+		let mut t = new_test_ext(COMPACT_CODE, false);
+
+		executor().call::<_, NeverNativeValue, fn() -> _>(
+			&mut t,
+			"Core_initialize_block",
+			&vec![].and(&from_block_number(1u64)),
+			true,
+			None,
+		).0.unwrap();
+		let r = executor().call::<_, NeverNativeValue, fn() -> _>(
+			&mut t,
+			"DryRunApi_dry_run",
+			&vec![].and(&xt()),
+			true,
+			None,
+		).0.unwrap();
+		let (result, events) = <(ApplyResult, Vec<u8>)>::decode(&mut &r.as_encoded()[..]).unwrap();
+		assert_eq!(result, Ok(ApplyOutcome::Success));
+		assert_eq!(Vec::<EventRecord<Event, Hash>>::decode(&mut &events[..]).unwrap(), vec![
+			EventRecord {
+				phase: Phase::ApplyExtrinsic(0),
+				event: Event::balances(balances::RawEvent::Transfer(
+					alice().into(),
+					bob().into(),
+					69,
+					0
+				)),
+				topics: vec![],
+			},
+			EventRecord {
+				phase: Phase::ApplyExtrinsic(0),
+				event: Event::system(system::Event::ExtrinsicSuccess),
+				topics: vec![],
+			},
+		]);
+
+		runtime_io::with_externalities(&mut t, || {
+			assert_eq!(System::block_number(), 1);
+		});
+	}
+
 	#[test]
 	fn full_native_block_import_works_with_changes_trie() {
 		let block1 = changes_trie_block();
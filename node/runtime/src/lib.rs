@@ -21,7 +21,8 @@
 #![recursion_limit="256"]
 
 use rstd::prelude::*;
-use support::construct_runtime;
+use parity_codec::Encode;
+use support::{construct_runtime, parameter_types};
 use substrate_primitives::u32_trait::{_2, _4};
 use node_primitives::{
 	AccountId, AccountIndex, Balance, BlockNumber, Hash, Index, AuthorityId, Signature, AuthoritySignature
@@ -33,8 +34,9 @@ use client::{
 };
 use runtime_primitives::{ApplyResult, generic, create_runtime_str};
 use runtime_primitives::transaction_validity::TransactionValidity;
+use runtime_primitives::weights::GetDispatchInfo;
 use runtime_primitives::traits::{
-	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, AuthorityIdFor, Convert,
+	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, AuthorityIdFor, Convert, Identity,
 };
 use version::RuntimeVersion;
 use council::{motions as council_motions, voting as council_voting};
@@ -47,6 +49,7 @@ use substrate_primitives::OpaqueMetadata;
 #[cfg(any(feature = "std", test))]
 pub use runtime_primitives::BuildStorage;
 pub use consensus::Call as ConsensusCall;
+pub use grandpa::Call as GrandpaCall;
 pub use timestamp::Call as TimestampCall;
 pub use balances::Call as BalancesCall;
 pub use runtime_primitives::{Permill, Perbill};
@@ -58,8 +61,8 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node"),
 	impl_name: create_runtime_str!("substrate-node"),
 	authoring_version: 10,
-	spec_version: 80,
-	impl_version: 80,
+	spec_version: 89,
+	impl_version: 89,
 	apis: RUNTIME_API_VERSIONS,
 };
 
@@ -72,6 +75,15 @@ pub fn native_version() -> NativeVersion {
 	}
 }
 
+parameter_types! {
+	// Spec version of this runtime, the module storage is migrated when it changes.
+	pub const SpecVersion: u32 = VERSION.spec_version;
+	// A dollar per open operation and a cent per approval it needs, in the chain spec units.
+	pub const MultisigDepositBase: Balance = 100_000_000_000_000;
+	pub const MultisigDepositFactor: Balance = 1_000_000_000_000;
+	pub const MaxSignatories: u16 = 100;
+}
+
 pub struct CurrencyToVoteHandler;
 
 impl CurrencyToVoteHandler {
@@ -128,6 +140,9 @@ impl consensus::Trait for Runtime {
 	// The Aura module handles offline-reports internally
 	// rather than using an explicit report system.
 	type InherentOfflineReport = ();
+	// Authorities sealing two blocks for the same slot are slashed.
+	type MisbehaviorReport = aura::EquivocationReport<Runtime, Hash, AuthoritySignature>;
+	type OnMisbehaviorReport = Staking;
 }
 
 impl timestamp::Trait for Runtime {
@@ -197,10 +212,21 @@ impl sudo::Trait for Runtime {
 	type Proposal = Call;
 }
 
+impl multisig::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type DepositBase = MultisigDepositBase;
+	type DepositFactor = MultisigDepositFactor;
+	type MaxSignatories = MaxSignatories;
+}
+
 impl grandpa::Trait for Runtime {
 	type SessionKey = AuthorityId;
 	type Log = Log;
 	type Event = Event;
+	type ConvertAuthorityIdToSessionKey = Identity;
+	type OnMisbehaviorReport = Staking;
 }
 
 impl finality_tracker::Trait for Runtime {
@@ -216,7 +242,7 @@ construct_runtime!(
 		System: system::{default, Log(ChangesTrieRoot)},
 		Aura: aura::{Module, Inherent(Timestamp)},
 		Timestamp: timestamp::{Module, Call, Storage, Config<T>, Inherent},
-		Consensus: consensus::{Module, Call, Storage, Config<T>, Log(AuthoritiesChange), Inherent},
+		Consensus: consensus::{Module, Call, Storage, Config<T>, Log(AuthoritiesChange), Inherent, ValidateUnsigned},
 		Indices: indices,
 		Balances: balances,
 		Session: session,
@@ -227,10 +253,11 @@ construct_runtime!(
 		CouncilMotions: council_motions::{Module, Call, Storage, Event<T>, Origin},
 		CouncilSeats: council_seats::{Config<T>},
 		FinalityTracker: finality_tracker::{Module, Call, Inherent},
-		Grandpa: grandpa::{Module, Call, Storage, Config<T>, Log(), Event<T>},
+		Grandpa: grandpa::{Module, Call, Storage, Config<T>, Log(), Event<T>, ValidateUnsigned},
 		Treasury: treasury,
 		Contract: contract::{Module, Call, Storage, Config<T>, Event<T>},
 		Sudo: sudo,
+		Multisig: multisig::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -249,7 +276,7 @@ pub type UncheckedExtrinsic = generic::UncheckedMortalCompactExtrinsic<Address,
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Index, Call>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Balances, Runtime, AllModules>;
+pub type Executive = executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Balances, Runtime, AllModules, SpecVersion>;
 
 impl_runtime_apis! {
 	impl client_api::Core<Block> for Runtime {
@@ -304,6 +331,38 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl client_api::DryRunApi<Block> for Runtime {
+		fn dry_run(extrinsic: <Block as BlockT>::Extrinsic) -> (ApplyResult, Vec<u8>) {
+			let previous_events = System::events().len();
+			let result = Executive::apply_extrinsic(extrinsic);
+			(result, System::events()[previous_events..].encode())
+		}
+	}
+
+	impl client_api::TransactionPaymentApi<Block, Balance> for Runtime {
+		fn query_fee(extrinsic: <Block as BlockT>::Extrinsic) -> Balance {
+			Balances::compute_fee(extrinsic.encode().len(), extrinsic.function.get_dispatch_info().weight)
+		}
+	}
+
+	impl client_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
+		fn account_nonce(account: AccountId) -> Index {
+			System::account_nonce(account)
+		}
+	}
+
+	impl client_api::EventsApi<Block> for Runtime {
+		fn block_events() -> Vec<client_api::BlockEvent> {
+			System::events().into_iter().map(|record| client_api::BlockEvent {
+				extrinsic_index: match record.phase {
+					system::Phase::ApplyExtrinsic(index) => Some(index),
+					system::Phase::Finalization => None,
+				},
+				event: record.event.encode(),
+			}).collect()
+		}
+	}
+
 	impl offchain_primitives::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(number: NumberFor<Block>) {
 			Executive::offchain_worker(number)
@@ -36,8 +36,9 @@
 //!  struct and associated method).
 //! - [Timestamp](../srml_timestamp/index.html): The Timestamp module is used in Aura to track
 //! consensus rounds (via `slots`).
-//! - [Consensus](../srml_consensus/index.html): The Consensus module does not relate directly to Aura,
-//!  but serves to manage offline reporting by implementing `ProvideInherent` in a similar way.
+//! - [Consensus](../srml_consensus/index.html): The Consensus module manages offline reporting by implementing
+//!  `ProvideInherent` in a similar way. It also accepts reports of authorities sealing two headers for the same
+//!  slot, checked by [`EquivocationReport`](./struct.EquivocationReport.html).
 //!
 //! ## References
 //!
@@ -53,15 +54,18 @@ pub use timestamp;
 use rstd::{result, prelude::*};
 use srml_support::storage::StorageValue;
 use srml_support::{decl_storage, decl_module};
-use primitives::traits::{As, Zero};
+use primitives::traits::{As, Zero, Verify};
 use timestamp::OnTimestampSet;
 #[cfg(feature = "std")]
 use timestamp::TimestampInherentData;
 use parity_codec::{Encode, Decode};
 use inherents::{RuntimeString, InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
+use aura_primitives::AURA_ENGINE_ID;
 #[cfg(feature = "std")]
 use inherents::{InherentDataProviders, ProvideInherentData};
 
+pub use aura_primitives::EquivocationProof;
+
 mod mock;
 mod tests;
 
@@ -198,6 +202,11 @@ impl<T: Trait> Module<T> {
 		<timestamp::Module<T>>::minimum_period().as_().saturating_mul(2)
 	}
 
+	/// The slot of the current block, as given by the Timestamp module.
+	fn current_slot() -> u64 {
+		<timestamp::Module<T>>::now().as_() / Self::slot_duration().max(1)
+	}
+
 	fn on_timestamp_set<H: HandleReport>(now: T::Moment, slot_duration: T::Moment) {
 		let last = Self::last();
 		<Self as Store>::LastTimestamp::put(now.clone());
@@ -249,6 +258,33 @@ impl<T: staking::Trait + Trait> HandleReport for StakingSlasher<T> {
 	}
 }
 
+/// Aura equivocation proofs, to be reported through `consensus::Module::report_misbehavior`.
+///
+/// `T` is the runtime, `H` the hash type of the blocks and `S` the signature type of the authorities.
+/// Proofs of equivocations older than `consensus::MISBEHAVIOR_REPORT_WINDOW` slots are rejected.
+pub struct EquivocationReport<T, H, S>(::rstd::marker::PhantomData<(T, H, S)>);
+
+impl<T, H, S, SessionKey> consensus::MisbehaviorReport<SessionKey> for EquivocationReport<T, H, S> where
+	T: Trait,
+	H: srml_support::Parameter,
+	S: srml_support::Parameter + Verify<Signer=SessionKey>,
+	SessionKey: srml_support::Parameter,
+{
+	type Proof = EquivocationProof<H, SessionKey, S>;
+
+	fn check_proof(proof: &Self::Proof) -> result::Result<(SessionKey, Vec<u8>), &'static str> {
+		if !proof.is_valid() {
+			return Err("invalid equivocation proof");
+		}
+		if proof.slot.saturating_add(consensus::MISBEHAVIOR_REPORT_WINDOW) < <Module<T>>::current_slot() {
+			return Err("equivocation proof is too old");
+		}
+		// any two headers of the same author and slot prove the same offence.
+		let offence = (AURA_ENGINE_ID, proof.slot, &proof.offender).encode();
+		Ok((proof.offender.clone(), offence))
+	}
+}
+
 impl<T: Trait> ProvideInherent for Module<T> {
 	type Call = timestamp::Call<T>;
 	type Error = MakeFatalError<RuntimeString>;
@@ -36,6 +36,8 @@ impl consensus::Trait for Test {
 	type Log = DigestItem;
 	type SessionKey = UintAuthorityId;
 	type InherentOfflineReport = ();
+	type MisbehaviorReport = ();
+	type OnMisbehaviorReport = ();
 }
 
 impl system::Trait for Test {
@@ -75,3 +77,4 @@ pub fn new_test_ext(authorities: Vec<u64>) -> runtime_io::TestExternalities<Blak
 
 pub type System = system::Module<Test>;
 pub type Aura = Module<Test>;
+pub type Timestamp = timestamp::Module<Test>;
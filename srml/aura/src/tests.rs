@@ -19,11 +19,14 @@
 #![cfg(test)]
 
 use lazy_static::lazy_static;
-use crate::mock::{System, Aura, new_test_ext};
+use crate::mock::{System, Aura, Timestamp, Test, new_test_ext};
 use primitives::traits::Header;
 use runtime_io::with_externalities;
 use parking_lot::Mutex;
-use crate::{AuraReport, HandleReport};
+use substrate_primitives::{H256, ed25519, crypto::Pair};
+use parity_codec::Encode;
+use consensus::{MisbehaviorReport, MISBEHAVIOR_REPORT_WINDOW};
+use crate::{AuraReport, HandleReport, EquivocationProof, EquivocationReport};
 
 #[test]
 fn aura_report_gets_skipped_correctly() {
@@ -90,3 +93,40 @@ fn aura_reports_offline() {
 		assert_eq!(SLASH_COUNTS.lock().as_slice(), &[0, 0, 1, 1]);
 	});
 }
+
+#[test]
+fn equivocation_report_checks_proof() {
+	type Report = EquivocationReport<Test, H256, ed25519::Signature>;
+
+	let pair = ed25519::Pair::from_seed([1; 32]);
+	let sealed = |slot: u64, hash: H256| (hash, pair.sign(&(slot, hash).encode()));
+	let proof = |first, second| EquivocationProof {
+		slot: 42,
+		offender: pair.public(),
+		first_header: first,
+		second_header: second,
+	};
+
+	with_externalities(&mut new_test_ext(vec![0]), || {
+		let valid = proof(sealed(42, H256::repeat_byte(1)), sealed(42, H256::repeat_byte(2)));
+		let (offender, offence) = Report::check_proof(&valid).unwrap();
+		assert_eq!(offender, pair.public());
+
+		// the same offence whatever the headers.
+		let other = proof(sealed(42, H256::repeat_byte(3)), sealed(42, H256::repeat_byte(1)));
+		assert_eq!(Report::check_proof(&other).unwrap().1, offence);
+
+		let same_header = proof(sealed(42, H256::repeat_byte(1)), sealed(42, H256::repeat_byte(1)));
+		assert!(Report::check_proof(&same_header).is_err());
+
+		let other_slot = proof(sealed(42, H256::repeat_byte(1)), sealed(43, H256::repeat_byte(2)));
+		assert!(Report::check_proof(&other_slot).is_err());
+
+		// proofs are accepted for `MISBEHAVIOR_REPORT_WINDOW` slots.
+		let slot_duration = Aura::slot_duration();
+		Timestamp::set_timestamp((42 + MISBEHAVIOR_REPORT_WINDOW) * slot_duration);
+		assert!(Report::check_proof(&valid).is_ok());
+		Timestamp::set_timestamp((43 + MISBEHAVIOR_REPORT_WINDOW) * slot_duration);
+		assert_eq!(Report::check_proof(&valid), Err("equivocation proof is too old"));
+	});
+}
@@ -22,15 +22,18 @@ pub use timestamp;
 
 use rstd::{result, prelude::*};
 use srml_support::{decl_storage, decl_module};
-use primitives::traits::As;
+use primitives::traits::{As, Verify};
 use timestamp::{OnTimestampSet, Trait};
 #[cfg(feature = "std")]
 use timestamp::TimestampInherentData;
-use parity_codec::Decode;
+use parity_codec::{Encode, Decode};
 use inherents::{RuntimeString, InherentIdentifier, InherentData, ProvideInherent, MakeFatalError};
+use babe_primitives::BABE_ENGINE_ID;
 #[cfg(feature = "std")]
 use inherents::{InherentDataProviders, ProvideInherentData};
 
+pub use babe_primitives::EquivocationProof;
+
 /// The BABE inherent identifier.
 pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"babeslot";
 
@@ -121,12 +124,43 @@ impl<T: Trait> Module<T> {
 		// the majority of their slot.
 		<timestamp::Module<T>>::minimum_period().as_().saturating_mul(2)
 	}
+
+	/// The slot of the current block, as given by the Timestamp module.
+	fn current_slot() -> u64 {
+		<timestamp::Module<T>>::now().as_() / Self::slot_duration().max(1)
+	}
 }
 
 impl<T: Trait> OnTimestampSet<T::Moment> for Module<T> {
 	fn on_timestamp_set(_moment: T::Moment) { }
 }
 
+/// Checks BABE equivocation proofs reported through `consensus::Module::report_misbehavior`.
+///
+/// `T` is the runtime, `H` the hash type of the blocks and `S` the signature type of the authorities.
+/// Proofs of equivocations older than `consensus::MISBEHAVIOR_REPORT_WINDOW` slots are rejected.
+pub struct EquivocationReport<T, H, S>(rstd::marker::PhantomData<(T, H, S)>);
+
+impl<T, H, S, SessionKey> consensus::MisbehaviorReport<SessionKey> for EquivocationReport<T, H, S> where
+	T: Trait,
+	H: srml_support::Parameter,
+	S: srml_support::Parameter + Verify<Signer=SessionKey>,
+	SessionKey: srml_support::Parameter,
+{
+	type Proof = EquivocationProof<H, SessionKey, S>;
+
+	fn check_proof(proof: &Self::Proof) -> result::Result<(SessionKey, Vec<u8>), &'static str> {
+		if !proof.is_valid() {
+			return Err("invalid equivocation proof");
+		}
+		if proof.slot.saturating_add(consensus::MISBEHAVIOR_REPORT_WINDOW) < <Module<T>>::current_slot() {
+			return Err("equivocation proof is too old");
+		}
+		let offence = (BABE_ENGINE_ID, proof.slot, &proof.offender).encode();
+		Ok((proof.offender.clone(), offence))
+	}
+}
+
 impl<T: Trait> ProvideInherent for Module<T> {
 	type Call = timestamp::Call<T>;
 	type Error = MakeFatalError<RuntimeString>;
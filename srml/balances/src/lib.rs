@@ -153,7 +153,7 @@ use srml_support::traits::{
 	WithdrawReason, WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
 	Imbalance, SignedImbalance, ReservableCurrency
 };
-use srml_support::dispatch::Result;
+use srml_support::dispatch::{Result, Weight, SimpleDispatchInfo};
 use primitives::traits::{
 	Zero, SimpleArithmetic, As, StaticLookup, Member, CheckedAdd, CheckedSub,
 	MaybeSerializeDebug, Saturating
@@ -272,6 +272,8 @@ decl_storage! {
 		pub TransactionBaseFee get(transaction_base_fee) config(): T::Balance;
 		/// The fee to be paid for making a transaction; the per-byte portion.
 		pub TransactionByteFee get(transaction_byte_fee) config(): T::Balance;
+		/// The fee to be paid for making a transaction; the per-weight-unit portion.
+		pub TransactionWeightFee get(transaction_weight_fee) config(): T::Balance;
 
 		/// Information regarding the vesting of a given account.
 		pub Vesting get(vesting) build(|config: &GenesisConfig<T, I>| {
@@ -361,6 +363,7 @@ decl_module! {
 		/// and reset the account nonce (`system::AccountNonce`).
 		///
 		/// The dispatch origin for this call is `root`.
+		#[weight = SimpleDispatchInfo::FixedOperational(50_000)]
 		fn set_balance(
 			who: <T::Lookup as StaticLookup>::Source,
 			#[compact] free: T::Balance,
@@ -386,6 +389,15 @@ impl<T: Trait<I>, I: Instance> Module<T, I> {
 		}
 	}
 
+	/// Get the fee paid for an extrinsic of encoded length `encoded_len` bytes and weight `weight`.
+	pub fn compute_fee(encoded_len: usize, weight: Weight) -> T::Balance {
+		let encoded_len = <T::Balance as As<u64>>::sa(encoded_len as u64);
+		let weight = <T::Balance as As<u64>>::sa(weight as u64);
+		Self::transaction_base_fee()
+			+ Self::transaction_byte_fee() * encoded_len
+			+ Self::transaction_weight_fee() * weight
+	}
+
 	// PRIVATE MUTABLES
 
 	/// Set the reserved balance of an account to some new value. Will enforce `ExistentialDeposit`
@@ -1012,9 +1024,8 @@ where
 }
 
 impl<T: Trait<I>, I: Instance> MakePayment<T::AccountId> for Module<T, I> {
-	fn make_payment(transactor: &T::AccountId, encoded_len: usize) -> Result {
-		let encoded_len = <T::Balance as As<u64>>::sa(encoded_len as u64);
-		let transaction_fee = Self::transaction_base_fee() + Self::transaction_byte_fee() * encoded_len;
+	fn make_payment(transactor: &T::AccountId, encoded_len: usize, weight: Weight) -> Result {
+		let transaction_fee = Self::compute_fee(encoded_len, weight);
 		let imbalance = Self::withdraw(
 			transactor,
 			transaction_fee,
@@ -58,6 +58,7 @@ impl Trait for Runtime {
 pub struct ExtBuilder {
 	transaction_base_fee: u64,
 	transaction_byte_fee: u64,
+	transaction_weight_fee: u64,
 	existential_deposit: u64,
 	transfer_fee: u64,
 	creation_fee: u64,
@@ -69,6 +70,7 @@ impl Default for ExtBuilder {
 		Self {
 			transaction_base_fee: 0,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
 			existential_deposit: 0,
 			transfer_fee: 0,
 			creation_fee: 0,
@@ -96,6 +98,10 @@ impl ExtBuilder {
 		self.transaction_byte_fee = byte_fee;
 		self
 	}
+	pub fn transaction_weight_fee(mut self, weight_fee: u64) -> Self {
+		self.transaction_weight_fee = weight_fee;
+		self
+	}
 	pub fn monied(mut self, monied: bool) -> Self {
 		self.monied = monied;
 		if self.existential_deposit == 0 {
@@ -112,6 +118,7 @@ impl ExtBuilder {
 		t.extend(GenesisConfig::<Runtime> {
 			transaction_base_fee: self.transaction_base_fee,
 			transaction_byte_fee: self.transaction_byte_fee,
+			transaction_weight_fee: self.transaction_weight_fee,
 			balances: if self.monied {
 				vec![(1, 10 * self.existential_deposit), (2, 20 * self.existential_deposit), (3, 30 * self.existential_deposit), (4, 40 * self.existential_deposit)]
 			} else {
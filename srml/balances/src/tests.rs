@@ -103,17 +103,17 @@ fn lock_reasons_should_work() {
 		Balances::set_lock(ID_1, &1, 10, u64::max_value(), WithdrawReason::Transfer.into());
 		assert_noop!(<Balances as Currency<_>>::transfer(&1, &2, 1), "account liquidity restrictions prevent withdrawal");
 		assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 1));
-		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1));
+		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1, 0));
 
 		Balances::set_lock(ID_1, &1, 10, u64::max_value(), WithdrawReason::Reserve.into());
 		assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 1));
 		assert_noop!(<Balances as ReservableCurrency<_>>::reserve(&1, 1), "account liquidity restrictions prevent withdrawal");
-		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1));
+		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 1, 0));
 
 		Balances::set_lock(ID_1, &1, 10, u64::max_value(), WithdrawReason::TransactionPayment.into());
 		assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 1));
 		assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 1));
-		assert_noop!(<Balances as MakePayment<_>>::make_payment(&1, 1), "account liquidity restrictions prevent withdrawal");
+		assert_noop!(<Balances as MakePayment<_>>::make_payment(&1, 1, 0), "account liquidity restrictions prevent withdrawal");
 	});
 }
 
@@ -618,3 +618,36 @@ fn extra_balance_should_transfer() {
 		}
 	);
 }
+
+#[test]
+fn compute_fee_should_match_paid_fee() {
+	with_externalities(&mut ExtBuilder::default().existential_deposit(10).monied(true).transaction_fees(10, 2).build(), || {
+		assert_eq!(Balances::compute_fee(0, 0), 10);
+		assert_eq!(Balances::compute_fee(5, 0), 20);
+
+		let free_balance = Balances::free_balance(&1);
+		assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 5, 0));
+		assert_eq!(Balances::free_balance(&1), free_balance - 20);
+	});
+}
+
+#[test]
+fn fee_should_be_proportional_to_weight() {
+	with_externalities(
+		&mut ExtBuilder::default()
+			.existential_deposit(10)
+			.monied(true)
+			.transaction_fees(10, 2)
+			.transaction_weight_fee(3)
+			.build(),
+		|| {
+			assert_eq!(Balances::compute_fee(5, 0), 20);
+			assert_eq!(Balances::compute_fee(5, 4), 32);
+			assert_eq!(Balances::compute_fee(5, 8), 44);
+
+			let free_balance = Balances::free_balance(&1);
+			assert_ok!(<Balances as MakePayment<_>>::make_payment(&1, 5, 8));
+			assert_eq!(Balances::free_balance(&1), free_balance - 44);
+		}
+	);
+}
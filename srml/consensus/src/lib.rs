@@ -29,7 +29,8 @@
 //!
 //! ### Dispatchable Functions
 //!
-//! - `report_misbehavior` - Report some misbehavior. The origin of this call must be signed.
+//! - `report_misbehavior` - Report a proof of misbehavior of an authority, which gets punished. The origin of this
+//!  call must be none, the proof is checked before the report is accepted by the transaction pool.
 //! - `note_offline` - Note that the previous block's validator missed its opportunity to propose a block.
 //!  The origin of this call must be an inherent.
 //! - `remark` - Make some on-chain remark. The origin of this call must be signed.
@@ -132,9 +133,11 @@ use rstd::prelude::*;
 use parity_codec as codec;
 use codec::{Encode, Decode};
 use srml_support::{storage, Parameter, decl_storage, decl_module};
-use srml_support::storage::StorageValue;
+use srml_support::dispatch::SimpleDispatchInfo;
+use srml_support::storage::{StorageValue, StorageMap};
 use srml_support::storage::unhashed::StorageVec;
-use primitives::traits::{MaybeSerializeDebug, Member};
+use primitives::traits::{As, MaybeSerializeDebug, Member, ValidateUnsigned};
+use primitives::transaction_validity::{TransactionValidity, TransactionPriority, TransactionLongevity};
 use substrate_primitives::storage::well_known_keys;
 use system::{ensure_signed, ensure_none};
 use inherents::{
@@ -153,6 +156,12 @@ pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"offlrep0";
 /// The error type used by this inherent.
 pub type InherentError = RuntimeString;
 
+/// Number of blocks for which reported offences are remembered, so that they are punished once.
+///
+/// Implementations of `MisbehaviorReport` must reject proofs of offences committed more than
+/// this many blocks ago.
+pub const MISBEHAVIOR_REPORT_WINDOW: u64 = 14_400;
+
 struct AuthorityStorageVec<S: codec::Codec + Default>(rstd::marker::PhantomData<S>);
 impl<S: codec::Codec + Default> StorageVec for AuthorityStorageVec<S> {
 	type Item = S;
@@ -196,6 +205,35 @@ impl InherentOfflineReport for () {
 	}
 }
 
+/// Describes the proofs of misbehavior accepted by `report_misbehavior`.
+pub trait MisbehaviorReport<SessionKey> {
+	/// The proof of misbehavior.
+	type Proof: Parameter;
+
+	/// Check the proof, returning the misbehaving authority and an identifier of the offence.
+	///
+	/// All proofs of the same offence must give the same identifier, so that it is punished once.
+	/// Proofs of offences older than `MISBEHAVIOR_REPORT_WINDOW` blocks must be rejected.
+	fn check_proof(proof: &Self::Proof) -> Result<(SessionKey, Vec<u8>), &'static str>;
+}
+
+impl<SessionKey> MisbehaviorReport<SessionKey> for () {
+	type Proof = ();
+
+	fn check_proof(_: &()) -> Result<(SessionKey, Vec<u8>), &'static str> {
+		Err("Misbehavior reports not allowed")
+	}
+}
+
+/// Handling misbehaving authorities, once their misbehavior is proven.
+pub trait OnMisbehaviorReport<SessionKey> {
+	fn handle_report(offender: SessionKey);
+}
+
+impl<SessionKey> OnMisbehaviorReport<SessionKey> for () {
+	fn handle_report(_: SessionKey) {}
+}
+
 /// A variant of the `OfflineReport` that is useful for instant-finality blocks.
 ///
 /// This assumes blocks are only finalized.
@@ -263,6 +301,11 @@ pub trait Trait: system::Trait {
 	/// Defines the offline-report type of the trait.
 	/// Set to `()` if offline-reports aren't needed for this runtime.
 	type InherentOfflineReport: InherentOfflineReport;
+	/// Defines the proofs of misbehavior which can be reported.
+	/// Set to `()` if misbehavior reports aren't needed for this runtime.
+	type MisbehaviorReport: MisbehaviorReport<Self::SessionKey>;
+	/// What to do with an authority whose misbehavior is proven.
+	type OnMisbehaviorReport: OnMisbehaviorReport<Self::SessionKey>;
 }
 
 decl_storage! {
@@ -270,6 +313,11 @@ decl_storage! {
 		// Actual authorities set at the block execution start. Is `Some` iff
 		// the set has been changed.
 		OriginalAuthorities: Option<Vec<T::SessionKey>>;
+		/// Identifiers of the offences which have already been punished.
+		pub ReportedOffences get(reported_offence): map Vec<u8> => bool;
+		/// Identifiers of the offences reported at a block, forgotten `MISBEHAVIOR_REPORT_WINDOW`
+		/// blocks later.
+		OffencesReportedAt: map T::BlockNumber => Vec<Vec<u8>>;
 	}
 	add_extra_genesis {
 		config(authorities): Vec<T::SessionKey>;
@@ -291,9 +339,21 @@ decl_storage! {
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		/// Report some misbehavior.
-		fn report_misbehavior(origin, _report: Vec<u8>) {
-			ensure_signed(origin)?;
+		/// Report misbehavior of a current authority, which gets punished.
+		///
+		/// Reports are unsigned, so that authorities can report each other without an
+		/// account. Each offence is punished once.
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn report_misbehavior(
+			origin,
+			proof: <T::MisbehaviorReport as MisbehaviorReport<T::SessionKey>>::Proof
+		) {
+			ensure_none(origin)?;
+
+			let (offender, offence) = Self::check_misbehavior(&proof)?;
+			<ReportedOffences<T>>::insert(&offence, true);
+			<OffencesReportedAt<T>>::mutate(<system::Module<T>>::block_number(), |offences| offences.push(offence));
+			T::OnMisbehaviorReport::handle_report(offender);
 		}
 
 		/// Note that the previous block's validator missed its opportunity to propose a block.
@@ -309,16 +369,19 @@ decl_module! {
 		}
 
 		/// Set the number of pages in the WebAssembly environment's heap.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
 		fn set_heap_pages(pages: u64) {
 			storage::unhashed::put_raw(well_known_keys::HEAP_PAGES, &pages.encode());
 		}
 
 		/// Set the new code.
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
 		pub fn set_code(new: Vec<u8>) {
 			storage::unhashed::put_raw(well_known_keys::CODE, &new);
 		}
 
 		/// Set some items of storage.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
 		fn set_storage(items: Vec<KeyValue>) {
 			for i in &items {
 				storage::unhashed::put_raw(&i.0, &i.1);
@@ -326,19 +389,27 @@ decl_module! {
 		}
 
 		/// Kill some items from storage.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
 		fn kill_storage(keys: Vec<Key>) {
 			for key in &keys {
 				storage::unhashed::kill(&key);
 			}
 		}
 
-		fn on_finalize() {
+		fn on_finalize(n: T::BlockNumber) {
 			if let Some(original_authorities) = <OriginalAuthorities<T>>::take() {
 				let current_authorities = AuthorityStorageVec::<T::SessionKey>::items();
 				if current_authorities != original_authorities {
 					Self::deposit_log(RawLog::AuthoritiesChange(current_authorities));
 				}
 			}
+
+			// offences reported this long ago cannot be reported anymore.
+			if let Some(expired) = n.as_().checked_sub(MISBEHAVIOR_REPORT_WINDOW) {
+				for offence in <OffencesReportedAt<T>>::take(T::BlockNumber::sa(expired)) {
+					<ReportedOffences<T>>::remove(offence);
+				}
+			}
 		}
 	}
 }
@@ -376,6 +447,21 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
+	/// Check a proof of misbehavior, returning the offender and the offence if it is a
+	/// current authority and the offence has not been punished yet.
+	fn check_misbehavior(
+		proof: &<T::MisbehaviorReport as MisbehaviorReport<T::SessionKey>>::Proof,
+	) -> Result<(T::SessionKey, Vec<u8>), &'static str> {
+		let (offender, offence) = T::MisbehaviorReport::check_proof(proof)?;
+		if !Self::authorities().contains(&offender) {
+			return Err("offender is not a current authority");
+		}
+		if Self::reported_offence(&offence) {
+			return Err("offence already reported");
+		}
+		Ok((offender, offence))
+	}
+
 	/// Save original authorities set.
 	fn save_original_authorities(current_authorities: Option<Vec<T::SessionKey>>) {
 		if OriginalAuthorities::<T>::get().is_some() {
@@ -435,3 +521,22 @@ impl<T: Trait> ProvideInherent for Module<T> {
 		).map_err(|e| RuntimeString::from(e).into())
 	}
 }
+
+impl<T: Trait> ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		match call {
+			Call::report_misbehavior(proof) => match Self::check_misbehavior(proof) {
+				Ok((_, offence)) => TransactionValidity::Valid {
+					priority: TransactionPriority::max_value(),
+					requires: Vec::new(),
+					provides: rstd::iter::once(offence).collect(),
+					longevity: TransactionLongevity::max_value(),
+				},
+				Err(_) => TransactionValidity::Invalid(0),
+			},
+			_ => TransactionValidity::Invalid(0),
+		}
+	}
+}
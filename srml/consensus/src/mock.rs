@@ -18,16 +18,44 @@
 
 #![cfg(test)]
 
+use std::cell::RefCell;
 use primitives::{BuildStorage, traits::IdentityLookup, testing::{Digest, DigestItem, Header, UintAuthorityId}};
 use srml_support::impl_outer_origin;
 use runtime_io;
 use substrate_primitives::{H256, Blake2Hasher};
-use crate::{GenesisConfig, Trait, Module};
+use parity_codec::Encode;
+use crate::{GenesisConfig, Trait, Module, MisbehaviorReport, OnMisbehaviorReport};
 
 impl_outer_origin!{
 	pub enum Origin for Test {}
 }
 
+thread_local! {
+	pub static PUNISHED: RefCell<Vec<UintAuthorityId>> = RefCell::new(vec![]);
+}
+
+/// Misbehavior proven by `(offender, slot, is_valid)`.
+pub struct TestMisbehaviorReport;
+impl MisbehaviorReport<UintAuthorityId> for TestMisbehaviorReport {
+	type Proof = (u64, u64, bool);
+
+	fn check_proof(proof: &Self::Proof) -> Result<(UintAuthorityId, Vec<u8>), &'static str> {
+		let &(offender, slot, is_valid) = proof;
+		if is_valid {
+			Ok((UintAuthorityId(offender), (offender, slot).encode()))
+		} else {
+			Err("invalid proof")
+		}
+	}
+}
+
+pub struct TestPunisher;
+impl OnMisbehaviorReport<UintAuthorityId> for TestPunisher {
+	fn handle_report(offender: UintAuthorityId) {
+		PUNISHED.with(|p| p.borrow_mut().push(offender));
+	}
+}
+
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Test;
@@ -35,6 +63,8 @@ impl Trait for Test {
 	type Log = DigestItem;
 	type SessionKey = UintAuthorityId;
 	type InherentOfflineReport = crate::InstantFinalityReportVec<()>;
+	type MisbehaviorReport = TestMisbehaviorReport;
+	type OnMisbehaviorReport = TestPunisher;
 }
 impl system::Trait for Test {
 	type Origin = Origin;
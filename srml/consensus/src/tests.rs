@@ -18,10 +18,14 @@
 
 #![cfg(test)]
 
-use primitives::{generic, testing::{self, UintAuthorityId}, traits::OnFinalize};
+use primitives::{generic, testing::{self, UintAuthorityId}, traits::{OnFinalize, ValidateUnsigned}};
+use primitives::transaction_validity::TransactionValidity;
 use runtime_io::with_externalities;
-use crate::mock::{Consensus, System, new_test_ext};
+use srml_support::{assert_ok, assert_noop};
+use crate::mock::{Consensus, System, Origin, PUNISHED, new_test_ext};
+use crate::{Call, MISBEHAVIOR_REPORT_WINDOW};
 use inherents::{InherentData, ProvideInherent};
+use parity_codec::Encode;
 
 #[test]
 fn authorities_change_logged() {
@@ -129,3 +133,68 @@ fn set_and_kill_storage_work() {
 		);
 	});
 }
+
+#[test]
+fn misbehavior_is_punished_once() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+
+		assert_noop!(
+			Consensus::report_misbehavior(Origin::signed(1), (2, 7, true)),
+			"bad origin: expected to be no origin"
+		);
+		assert_noop!(Consensus::report_misbehavior(Origin::NONE, (2, 7, false)), "invalid proof");
+		assert_noop!(
+			Consensus::report_misbehavior(Origin::NONE, (4, 7, true)),
+			"offender is not a current authority"
+		);
+
+		assert_ok!(Consensus::report_misbehavior(Origin::NONE, (2, 7, true)));
+		assert_eq!(PUNISHED.with(|p| p.borrow().clone()), vec![UintAuthorityId(2)]);
+
+		assert_noop!(Consensus::report_misbehavior(Origin::NONE, (2, 7, true)), "offence already reported");
+		assert_ok!(Consensus::report_misbehavior(Origin::NONE, (2, 8, true)));
+		assert_eq!(PUNISHED.with(|p| p.borrow().len()), 2);
+	});
+}
+
+#[test]
+fn reported_offences_are_forgotten_after_the_window() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+		assert_ok!(Consensus::report_misbehavior(Origin::NONE, (2, 7, true)));
+		let offence = (2u64, 7u64).encode();
+		assert!(Consensus::reported_offence(&offence));
+
+		Consensus::on_finalize(MISBEHAVIOR_REPORT_WINDOW);
+		assert!(Consensus::reported_offence(&offence));
+		Consensus::on_finalize(MISBEHAVIOR_REPORT_WINDOW + 1);
+		assert!(!Consensus::reported_offence(&offence));
+	});
+}
+
+#[test]
+fn misbehavior_reports_are_validated() {
+	with_externalities(&mut new_test_ext(vec![1, 2, 3]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+
+		match Consensus::validate_unsigned(&Call::report_misbehavior((2, 7, true))) {
+			TransactionValidity::Valid { provides, .. } => assert_eq!(provides.len(), 1),
+			other => panic!("Unexpected validity: {:?}", other),
+		}
+		assert_eq!(
+			Consensus::validate_unsigned(&Call::report_misbehavior((2, 7, false))),
+			TransactionValidity::Invalid(0),
+		);
+		assert_eq!(
+			Consensus::validate_unsigned(&Call::remark(vec![])),
+			TransactionValidity::Invalid(0),
+		);
+
+		assert_ok!(Consensus::report_misbehavior(Origin::NONE, (2, 7, true)));
+		assert_eq!(
+			Consensus::validate_unsigned(&Call::report_misbehavior((2, 7, true))),
+			TransactionValidity::Invalid(0),
+		);
+	});
+}
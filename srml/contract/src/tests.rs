@@ -96,6 +96,8 @@ impl consensus::Trait for Test {
 	type Log = DigestItem;
 	type SessionKey = UintAuthorityId;
 	type InherentOfflineReport = ();
+	type MisbehaviorReport = ();
+	type OnMisbehaviorReport = ();
 }
 impl Trait for Test {
 	type Currency = Balances;
@@ -195,6 +197,7 @@ impl ExtBuilder {
 			balances::GenesisConfig::<Test> {
 				transaction_base_fee: 0,
 				transaction_byte_fee: 0,
+				transaction_weight_fee: 0,
 				balances: vec![],
 				existential_deposit: self.existential_deposit,
 				transfer_fee: self.transfer_fee,
@@ -105,6 +105,7 @@ mod tests {
 		t.extend(balances::GenesisConfig::<Test>{
 			transaction_base_fee: 0,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
 			balances: vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50), (6, 60)],
 			existential_deposit: 0,
 			transfer_fee: 0,
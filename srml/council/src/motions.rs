@@ -20,7 +20,7 @@ use rstd::prelude::*;
 use rstd::result;
 use substrate_primitives::u32_trait::Value as U32;
 use primitives::traits::{Hash, EnsureOrigin};
-use srml_support::dispatch::{Dispatchable, Parameter};
+use srml_support::dispatch::{Dispatchable, Parameter, SimpleDispatchInfo};
 use srml_support::{StorageValue, StorageMap, decl_module, decl_event, decl_storage, ensure};
 use super::{Trait as CouncilTrait, Module as Council};
 use system::{self, ensure_signed};
@@ -66,6 +66,7 @@ decl_event!(
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
 		fn deposit_event<T>() = default;
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
 		fn propose(origin, #[compact] threshold: u32, proposal: Box<<T as Trait>::Proposal>) {
 			let who = ensure_signed(origin)?;
 
@@ -89,6 +90,7 @@ decl_module! {
 			}
 		}
 
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
 		fn vote(origin, proposal: T::Hash, #[compact] index: ProposalIndex, approve: bool) {
 			let who = ensure_signed(origin)?;
 
@@ -20,7 +20,7 @@ use rstd::prelude::*;
 use primitives::traits::{Zero, One, As, StaticLookup};
 use runtime_io::print;
 use srml_support::{
-	StorageValue, StorageMap, dispatch::Result, decl_storage, decl_event, ensure,
+	StorageValue, StorageMap, dispatch::{Result, SimpleDispatchInfo}, decl_storage, decl_event, ensure,
 	traits::{Currency, ReservableCurrency, OnUnbalanced}
 };
 use democracy;
@@ -216,6 +216,9 @@ decl_module! {
 		/// Claim that `signed` is one of the top Self::carry_count() + current_vote().1 candidates.
 		/// Only works if the `block_number >= current_vote().0` and `< current_vote().0 + presentation_duration()``
 		/// `signed` should have at least
+		///
+		/// Checking the presentation reads the approvals of every voter, which makes it expensive.
+		#[weight = SimpleDispatchInfo::FixedNormal(1_000_000)]
 		fn present_winner(
 			origin,
 			candidate: <T::Lookup as StaticLookup>::Source,
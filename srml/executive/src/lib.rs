@@ -33,6 +33,7 @@
 //! - Execute a block.
 //! - Finalize a block.
 //! - Start an off-chain worker.
+//! - Run the storage migrations of the modules after a runtime upgrade.
 //!
 //! ### Implementations
 //!
@@ -58,6 +59,7 @@
 //! # pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 //! # pub type Balances = u64;
 //! # pub type AllModules = u64;
+//! # pub type SpecVersion = u64;
 //! # pub enum Runtime {};
 //! # use primitives::transaction_validity::TransactionValidity;
 //! # use primitives::traits::ValidateUnsigned;
@@ -69,7 +71,7 @@
 //! # 	}
 //! # }
 //! /// Executive: handles dispatch to the various modules.
-//! pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules>;
+//! pub type Executive = executive::Executive<Runtime, Block, Context, Balances, Runtime, AllModules, SpecVersion>;
 //! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -80,16 +82,28 @@ use rstd::result;
 use primitives::traits::{
 	self, Header, Zero, One, Checkable, Applyable, CheckEqual, OnFinalize,
 	OnInitialize, Digest, NumberFor, Block as BlockT, OffchainWorker,
-	ValidateUnsigned,
+	ValidateUnsigned, OnRuntimeUpgrade,
 };
-use srml_support::{Dispatchable, traits::MakePayment};
+use primitives::weights::{GetDispatchInfo, DispatchInfo, DispatchClass, Weight};
+use srml_support::{Dispatchable, traits::{MakePayment, Get}};
 use parity_codec::{Codec, Encode};
 use system::extrinsics_root;
 use primitives::{ApplyOutcome, ApplyError};
-use primitives::transaction_validity::{TransactionValidity, TransactionPriority, TransactionLongevity};
+use primitives::transaction_validity::{
+	TransactionValidity, TransactionPriority, TransactionLongevity, OPERATIONAL_TRANSACTION_PRIORITY,
+};
 
 mod internal {
+	use primitives::weights::WEIGHT_UNIT_NANOS;
+
 	pub const MAX_TRANSACTIONS_SIZE: u32 = 4 * 1024 * 1024;
+	/// Time the extrinsics of a block may take to execute: a third of a six second block time,
+	/// leaving the rest for authoring, propagating and importing the block.
+	pub const MAX_TRANSACTIONS_MILLIS: u32 = 2_000;
+	pub const MAX_TRANSACTIONS_WEIGHT: u32 = MAX_TRANSACTIONS_MILLIS * (1_000_000 / WEIGHT_UNIT_NANOS);
+	/// Percentage of the block size and weight that normal extrinsics can use, the rest is
+	/// reserved for operational ones.
+	pub const NORMAL_DISPATCH_RATIO: u32 = 75;
 
 	pub enum ApplyError {
 		BadSignature(&'static str),
@@ -111,8 +125,12 @@ pub trait ExecuteBlock<Block: BlockT> {
 	fn execute_block(block: Block);
 }
 
-pub struct Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>(
-	PhantomData<(System, Block, Context, Payment, UnsignedValidator, AllModules)>
+/// Executes blocks of the runtime.
+///
+/// `SpecVersion` gives the `spec_version` of the runtime, the `on_runtime_upgrade` hooks of
+/// `AllModules` are executed when it changes.
+pub struct Executive<System, Block, Context, Payment, UnsignedValidator, AllModules, SpecVersion>(
+	PhantomData<(System, Block, Context, Payment, UnsignedValidator, AllModules, SpecVersion)>
 );
 
 impl<
@@ -121,17 +139,19 @@ impl<
 	Context: Default,
 	Payment: MakePayment<System::AccountId>,
 	UnsignedValidator,
-	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> ExecuteBlock<Block> for Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>
+	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>
+		+ OnRuntimeUpgrade,
+	SpecVersion: Get<u32>,
+> ExecuteBlock<Block> for Executive<System, Block, Context, Payment, UnsignedValidator, AllModules, SpecVersion>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
-	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId>,
+	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId> + GetDispatchInfo,
 	<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call: Dispatchable,
 	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call>
 {
 	fn execute_block(block: Block) {
-		Executive::<System, Block, Context, Payment, UnsignedValidator, AllModules>::execute_block(block);
+		Executive::<System, Block, Context, Payment, UnsignedValidator, AllModules, SpecVersion>::execute_block(block);
 	}
 }
 
@@ -141,11 +161,13 @@ impl<
 	Context: Default,
 	Payment: MakePayment<System::AccountId>,
 	UnsignedValidator,
-	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>,
-> Executive<System, Block, Context, Payment, UnsignedValidator, AllModules>
+	AllModules: OnInitialize<System::BlockNumber> + OnFinalize<System::BlockNumber> + OffchainWorker<System::BlockNumber>
+		+ OnRuntimeUpgrade,
+	SpecVersion: Get<u32>,
+> Executive<System, Block, Context, Payment, UnsignedValidator, AllModules, SpecVersion>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
-	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId>,
+	<Block::Extrinsic as Checkable<Context>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId> + GetDispatchInfo,
 	<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call: Dispatchable,
 	<<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=<<Block::Extrinsic as Checkable<Context>>::Checked as Applyable>::Call>
@@ -157,9 +179,24 @@ where
 
 	fn initialize_block_impl(block_number: &System::BlockNumber, parent_hash: &System::Hash, extrinsics_root: &System::Hash) {
 		<system::Module<System>>::initialize(block_number, parent_hash, extrinsics_root);
+		Self::on_runtime_upgrade();
 		<AllModules as OnInitialize<System::BlockNumber>>::on_initialize(*block_number);
 	}
 
+	/// Execute the `on_runtime_upgrade` hooks if the `spec_version` of the runtime differs from the
+	/// one of the runtime which last executed them.
+	///
+	/// A chain without any record is upgraded from a runtime which did not track upgrades. Chains
+	/// which start with the current runtime record its `spec_version` at genesis instead.
+	fn on_runtime_upgrade() {
+		let spec_version = SpecVersion::get();
+		if <system::Module<System>>::last_runtime_upgrade() == Some(spec_version) {
+			return;
+		}
+		<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+		<system::Module<System>>::note_runtime_upgrade(spec_version);
+	}
+
 	fn initial_checks(block: &Block) {
 		let header = block.header();
 
@@ -246,8 +283,15 @@ where
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default()).map_err(internal::ApplyError::BadSignature)?;
 
-		// Check the size of the block if that extrinsic is applied.
-		if <system::Module<System>>::all_extrinsics_len() + encoded_len as u32 > internal::MAX_TRANSACTIONS_SIZE {
+		// Check the size and weight of the block if that extrinsic is applied.
+		let dispatch_info = xt.get_dispatch_info();
+		let weight = dispatch_info.weight;
+		if !Self::fits_block(
+			<system::Module<System>>::all_extrinsics_len(),
+			<system::Module<System>>::all_extrinsics_weight(),
+			encoded_len,
+			dispatch_info,
+		) {
 			return Err(internal::ApplyError::FullBlock);
 		}
 
@@ -259,7 +303,7 @@ where
 			) }
 
 			// pay any fees
-			Payment::make_payment(sender, encoded_len).map_err(|_| internal::ApplyError::CantPay)?;
+			Payment::make_payment(sender, encoded_len, weight).map_err(|_| internal::ApplyError::CantPay)?;
 
 			// AUDIT: Under no circumstances may this function panic from here onwards.
 
@@ -276,7 +320,7 @@ where
 		// Decode parameters and dispatch
 		let (f, s) = xt.deconstruct();
 		let r = f.dispatch(s.into());
-		<system::Module<System>>::note_applied_extrinsic(&r, encoded_len as u32);
+		<system::Module<System>>::note_applied_extrinsic(&r, encoded_len as u32, weight);
 
 		r.map(|_| internal::ApplyOutcome::Success).or_else(|e| match e {
 			primitives::BLOCK_FULL => Err(internal::ApplyError::FullBlock),
@@ -284,6 +328,22 @@ where
 		})
 	}
 
+	/// Returns true if an extrinsic of given length and dispatch info can be added to a block of
+	/// `block_len` and `block_weight`. Normal extrinsics only get a share of the block limits.
+	fn fits_block(block_len: u32, block_weight: Weight, encoded_len: usize, info: DispatchInfo) -> bool {
+		let (max_len, max_weight) = match info.class {
+			DispatchClass::Operational => (internal::MAX_TRANSACTIONS_SIZE, internal::MAX_TRANSACTIONS_WEIGHT),
+			DispatchClass::Normal => (
+				internal::MAX_TRANSACTIONS_SIZE / 100 * internal::NORMAL_DISPATCH_RATIO,
+				internal::MAX_TRANSACTIONS_WEIGHT / 100 * internal::NORMAL_DISPATCH_RATIO,
+			),
+		};
+		let fits_len = (encoded_len as u64).checked_add(block_len as u64)
+			.map_or(false, |total| total <= max_len as u64);
+		let fits_weight = block_weight.checked_add(info.weight).map_or(false, |total| total <= max_weight);
+		fits_len && fits_weight
+	}
+
 	fn final_checks(header: &System::Header) {
 		// remove temporaries
 		let new_header = <system::Module<System>>::finalize();
@@ -329,10 +389,17 @@ where
 			Err(_) => return TransactionValidity::Invalid(UNKNOWN_ERROR),
 		};
 
+		// Reject an extrinsic which does not fit even in an empty block.
+		let dispatch_info = xt.get_dispatch_info();
+		let weight = dispatch_info.weight;
+		if !Self::fits_block(0, 0, encoded_len, dispatch_info) {
+			return TransactionValidity::Invalid(ApplyError::FullBlock as i8)
+		}
+
 		match (xt.sender(), xt.index()) {
 			(Some(sender), Some(index)) => {
 				// pay any fees
-				if Payment::make_payment(sender, encoded_len).is_err() {
+				if Payment::make_payment(sender, encoded_len, weight).is_err() {
 					return TransactionValidity::Invalid(ApplyError::CantPay as i8)
 				}
 
@@ -350,8 +417,18 @@ where
 					vec![]
 				};
 
+				// Operational extrinsics are in a band above all normal ones, whose length is
+				// bounded by the block size, so that they are not crowded out by them. Within the
+				// band, they are ordered by what the sender pays fees for.
+				let priority = match dispatch_info.class {
+					DispatchClass::Operational => OPERATIONAL_TRANSACTION_PRIORITY
+						.saturating_add(encoded_len as TransactionPriority)
+						.saturating_add(weight as TransactionPriority),
+					DispatchClass::Normal => encoded_len as TransactionPriority,
+				};
+
 				TransactionValidity::Valid {
-					priority: encoded_len as TransactionPriority,
+					priority,
 					requires,
 					provides,
 					longevity: TransactionLongevity::max_value(),
@@ -378,7 +455,7 @@ mod tests {
 	use primitives::BuildStorage;
 	use primitives::traits::{Header as HeaderT, BlakeTwo256, IdentityLookup};
 	use primitives::testing::{Digest, DigestItem, Header, Block};
-	use srml_support::{traits::Currency, impl_outer_origin, impl_outer_event};
+	use srml_support::{traits::Currency, storage, impl_outer_origin, impl_outer_event, parameter_types};
 	use system;
 	use hex_literal::hex;
 
@@ -435,8 +512,34 @@ mod tests {
 		}
 	}
 
+	const UPGRADES_KEY: &[u8] = b":test:upgrades";
+
+	/// Counts the executions of the runtime upgrade hooks in storage.
+	pub struct UpgradeCounter;
+	impl OnInitialize<u64> for UpgradeCounter {}
+	impl OnFinalize<u64> for UpgradeCounter {}
+	impl OffchainWorker<u64> for UpgradeCounter {}
+	impl OnRuntimeUpgrade for UpgradeCounter {
+		fn on_runtime_upgrade() {
+			let upgrades: u32 = storage::unhashed::get_or_default(UPGRADES_KEY);
+			storage::unhashed::put(UPGRADES_KEY, &(upgrades + 1));
+		}
+	}
+
+	parameter_types! {
+		const SpecVersion: u32 = 2;
+	}
+
 	type TestXt = primitives::testing::TestXt<Call<Runtime>>;
-	type Executive = super::Executive<Runtime, Block<TestXt>, system::ChainContext<Runtime>, balances::Module<Runtime>, Runtime, ()>;
+	type Executive = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		system::ChainContext<Runtime>,
+		balances::Module<Runtime>,
+		Runtime,
+		UpgradeCounter,
+		SpecVersion,
+	>;
 
 	#[test]
 	fn balance_transfer_dispatch_works() {
@@ -444,6 +547,7 @@ mod tests {
 		t.extend(balances::GenesisConfig::<Runtime> {
 			transaction_base_fee: 10,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
 			balances: vec![(1, 111)],
 			existential_deposit: 0,
 			transfer_fee: 0,
@@ -461,8 +565,13 @@ mod tests {
 		});
 	}
 
+	const NORMAL_WEIGHT_LIMIT: Weight = internal::MAX_TRANSACTIONS_WEIGHT / 100 * internal::NORMAL_DISPATCH_RATIO;
+
 	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
-		let mut t = system::GenesisConfig::<Runtime>::default().build_storage().unwrap().0;
+		let mut t = system::GenesisConfig::<Runtime> {
+			runtime_spec_version: Some(SpecVersion::get()),
+			..Default::default()
+		}.build_storage().unwrap().0;
 		t.extend(balances::GenesisConfig::<Runtime>::default().build_storage().unwrap().0);
 		t.into()
 	}
@@ -474,7 +583,7 @@ mod tests {
 				header: Header {
 					parent_hash: [69u8; 32].into(),
 					number: 1,
-					state_root: hex!("ac2840371d51ff2e036c8fc05af7313b7a030f735c38b2f03b94cbe87bfbb7c9").into(),
+					state_root: hex!("bc81568a014cf00a340d746551b1ee9eda14952e2cee071d8f6c037dd98fcd84").into(),
 					extrinsics_root: hex!("03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314").into(),
 					digest: Digest { logs: vec![], },
 				},
@@ -559,6 +668,99 @@ mod tests {
 		run_test(true);
 	}
 
+	#[test]
+	fn block_weight_limit_enforced() {
+		let mut t = new_test_ext();
+		let xt = primitives::testing::TestXt(Some(1), 0, Call::transfer(33, 69));
+		let weight = xt.get_dispatch_info().weight;
+		with_externalities(&mut t, || {
+			Executive::initialize_block(&Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default()));
+			// Fill the share of normal extrinsics up to one unit less than the weight of the transfer.
+			let filled = NORMAL_WEIGHT_LIMIT - weight + 1;
+			<system::Module<Runtime>>::note_applied_extrinsic(&Ok(()), 0, filled);
+			assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(1));
+
+			assert_eq!(Executive::apply_extrinsic(xt), Err(ApplyError::FullBlock));
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), filled);
+			assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(1));
+		});
+	}
+
+	#[test]
+	fn operational_extrinsics_use_reserved_block_space() {
+		let mut t = new_test_ext();
+		let xt = primitives::testing::TestXt(None, 0, Call::set_balance(33, 69, 69));
+		let weight = xt.get_dispatch_info().weight;
+		assert_eq!(xt.get_dispatch_info().class, DispatchClass::Operational);
+		with_externalities(&mut t, || {
+			Executive::initialize_block(&Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default()));
+			<system::Module<Runtime>>::note_applied_extrinsic(&Ok(()), 0, NORMAL_WEIGHT_LIMIT);
+
+			// Fails on the root origin check, but is not rejected for a full block.
+			assert_eq!(Executive::apply_extrinsic(xt.clone()), Ok(ApplyOutcome::Fail));
+			assert_eq!(<system::Module<Runtime>>::all_extrinsics_weight(), NORMAL_WEIGHT_LIMIT + weight);
+
+			<system::Module<Runtime>>::note_applied_extrinsic(&Ok(()), 0, internal::MAX_TRANSACTIONS_WEIGHT - NORMAL_WEIGHT_LIMIT - weight);
+			assert_eq!(Executive::apply_extrinsic(xt), Err(ApplyError::FullBlock));
+		});
+	}
+
+	#[test]
+	fn operational_extrinsics_get_priority() {
+		let mut t = new_test_ext();
+		let transfer = primitives::testing::TestXt(Some(1), 0, Call::transfer(33, 69));
+		let set_balance = primitives::testing::TestXt(Some(1), 0, Call::set_balance(33, 69, 69));
+		with_externalities(&mut t, || {
+			match Executive::validate_transaction(transfer.clone()) {
+				TransactionValidity::Valid { priority, .. } =>
+					assert_eq!(priority, transfer.encode().len() as TransactionPriority),
+				_ => panic!("transfer should be valid"),
+			}
+			match Executive::validate_transaction(set_balance.clone()) {
+				TransactionValidity::Valid { priority, .. } =>
+					assert_eq!(
+						priority,
+						OPERATIONAL_TRANSACTION_PRIORITY + set_balance.encode().len() as TransactionPriority + 50_000,
+					),
+				_ => panic!("set_balance should be valid"),
+			}
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_hooks_run_once_per_spec_version() {
+		let upgrades = || storage::unhashed::get_or_default::<u32>(UPGRADES_KEY);
+		let header = |number| Header::new(number, H256::default(), H256::default(), [69u8; 32].into(), Digest::default());
+		with_externalities(&mut new_test_ext(), || {
+			// The genesis runtime is the current one.
+			Executive::initialize_block(&header(1));
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), Some(SpecVersion::get()));
+			assert_eq!(upgrades(), 0);
+
+			// The previous block was executed by an older runtime.
+			<system::Module<Runtime>>::note_runtime_upgrade(SpecVersion::get() - 1);
+			Executive::initialize_block(&header(2));
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), Some(SpecVersion::get()));
+			assert_eq!(upgrades(), 1);
+
+			Executive::initialize_block(&header(3));
+			assert_eq!(upgrades(), 1);
+		});
+	}
+
+	#[test]
+	fn runtime_upgrade_hooks_run_on_chains_without_record() {
+		let upgrades = || storage::unhashed::get_or_default::<u32>(UPGRADES_KEY);
+		let header = Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default());
+		let t = system::GenesisConfig::<Runtime>::default().build_storage().unwrap().0;
+		with_externalities(&mut runtime_io::TestExternalities::<Blake2Hasher>::new(t), || {
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), None);
+			Executive::initialize_block(&header);
+			assert_eq!(<system::Module<Runtime>>::last_runtime_upgrade(), Some(SpecVersion::get()));
+			assert_eq!(upgrades(), 1);
+		});
+	}
+
 	#[test]
 	fn validate_unsigned() {
 		let xt = primitives::testing::TestXt(None, 0, Call::set_balance(33, 69, 69));
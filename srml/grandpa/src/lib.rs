@@ -19,8 +19,9 @@
 //! This manages the GRANDPA authority set ready for the native code.
 //! These authorities are only for GRANDPA finality, not for consensus overall.
 //!
-//! In the future, it will also handle misbehavior reports, and on-chain
-//! finality notifications.
+//! It also accepts proofs of authorities casting two votes of the same kind
+//! in a round, which are punished through `Trait::OnMisbehaviorReport`. In the
+//! future, it will also handle on-chain finality notifications.
 //!
 //! For full integration with GRANDPA, the `GrandpaApi` should be implemented.
 //! The necessary items are re-exported via the `fg_primitives` crate.
@@ -35,14 +36,15 @@ use serde::Serialize;
 use rstd::prelude::*;
 use parity_codec as codec;
 use codec::{Encode, Decode};
-use fg_primitives::ScheduledChange;
+use fg_primitives::{ScheduledChange, EquivocationProof};
 use srml_support::{Parameter, decl_event, decl_storage, decl_module};
-use srml_support::dispatch::Result;
-use srml_support::storage::StorageValue;
+use srml_support::dispatch::{Result, SimpleDispatchInfo};
+use srml_support::storage::{StorageValue, StorageMap};
 use srml_support::storage::unhashed::StorageVec;
-use primitives::traits::CurrentHeight;
+use primitives::traits::{As, CurrentHeight, Convert, ValidateUnsigned};
+use primitives::transaction_validity::{TransactionValidity, TransactionPriority, TransactionLongevity};
 use substrate_primitives::ed25519;
-use system::ensure_signed;
+use system::ensure_none;
 use primitives::traits::MaybeSerializeDebug;
 use ed25519::Public as AuthorityId;
 
@@ -133,6 +135,12 @@ pub trait Trait: system::Trait {
 
 	/// The event type of this module.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Get the session key of a GRANDPA voter from its id.
+	type ConvertAuthorityIdToSessionKey: Convert<AuthorityId, Self::SessionKey>;
+
+	/// What to do with an authority whose equivocation is proven.
+	type OnMisbehaviorReport: consensus::OnMisbehaviorReport<Self::SessionKey>;
 }
 
 /// A stored pending change, old format.
@@ -189,6 +197,13 @@ decl_storage! {
 		PendingChange get(pending_change): Option<StoredPendingChange<T::BlockNumber, T::SessionKey>>;
 		// next block number where we can force a change.
 		NextForced get(next_forced): Option<T::BlockNumber>;
+		/// The id of the current authority set, incremented each time a change is applied.
+		pub CurrentSetId get(current_set_id): u64;
+		/// Equivocations which have already been punished.
+		pub ReportedEquivocations get(reported_equivocation): map Vec<u8> => bool;
+		/// Equivocations reported at a block, forgotten `consensus::MISBEHAVIOR_REPORT_WINDOW`
+		/// blocks later.
+		EquivocationsReportedAt: map T::BlockNumber => Vec<Vec<u8>>;
 	}
 	add_extra_genesis {
 		config(authorities): Vec<(T::SessionKey, u64)>;
@@ -215,13 +230,33 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event<T>() = default;
 
-		/// Report some misbehavior.
-		fn report_misbehavior(origin, _report: Vec<u8>) {
-			ensure_signed(origin)?;
-			// FIXME: https://github.com/paritytech/substrate/issues/1112
+		/// Report a current authority voting for two different blocks in the same round,
+		/// which gets punished. Each equivocation is punished once.
+		///
+		/// Only proofs from the current authority set are accepted, and at least one of
+		/// the votes must target a block of this chain at most
+		/// `consensus::MISBEHAVIOR_REPORT_WINDOW` blocks old.
+		#[weight = SimpleDispatchInfo::FixedOperational(100_000)]
+		fn report_misbehavior(origin, proof: EquivocationProof<T::Hash, T::BlockNumber>) {
+			ensure_none(origin)?;
+
+			let (offender, equivocation) = Self::check_equivocation(&proof)?;
+			<ReportedEquivocations<T>>::insert(&equivocation, true);
+			<EquivocationsReportedAt<T>>::mutate(
+				<system::Module<T>>::block_number(),
+				|equivocations| equivocations.push(equivocation),
+			);
+			T::OnMisbehaviorReport::handle_report(offender);
 		}
 
 		fn on_finalize(block_number: T::BlockNumber) {
+			// equivocations reported this long ago cannot be reported anymore.
+			if let Some(expired) = block_number.as_().checked_sub(consensus::MISBEHAVIOR_REPORT_WINDOW) {
+				for equivocation in <EquivocationsReportedAt<T>>::take(T::BlockNumber::sa(expired)) {
+					<ReportedEquivocations<T>>::remove(equivocation);
+				}
+			}
+
 			if let Some(pending_change) = <PendingChange<T>>::get() {
 				if block_number == pending_change.scheduled_at {
 					if let Some(median) = pending_change.forced {
@@ -243,6 +278,7 @@ decl_module! {
 						RawEvent::NewAuthorities(pending_change.next_authorities.clone())
 					);
 					<AuthorityStorageVec<T::SessionKey>>::set_items(pending_change.next_authorities);
+					<CurrentSetId<T>>::mutate(|set_id| *set_id += 1);
 					<PendingChange<T>>::kill();
 				}
 			}
@@ -307,6 +343,44 @@ impl<T: Trait> Module<T> {
 	fn deposit_log(log: Log<T>) {
 		<system::Module<T>>::deposit_log(<T as Trait>::Log::from(log).into());
 	}
+
+	/// Check a proof of equivocation, returning the session key of the offender and an
+	/// identifier of the equivocation if it is a current authority not punished for it yet.
+	fn check_equivocation(
+		proof: &EquivocationProof<T::Hash, T::BlockNumber>,
+	) -> rstd::result::Result<(T::SessionKey, Vec<u8>), &'static str> {
+		if !proof.is_valid() {
+			return Err("invalid equivocation proof");
+		}
+
+		// proofs from past sets could otherwise be replayed against their members forever.
+		if proof.set_id != Self::current_set_id() {
+			return Err("equivocation proof is not from the current authority set");
+		}
+
+		// the targets are chosen by the offender, so only those found on chain tell how
+		// old the votes are.
+		let target: u64 = [&proof.first_vote.0, &proof.second_vote.0].iter()
+			.filter(|(hash, number)| <system::Module<T>>::block_hash(number) == *hash)
+			.map(|(_, number)| number.as_())
+			.max()
+			.ok_or("equivocation proof does not target a known block")?;
+		if target.saturating_add(consensus::MISBEHAVIOR_REPORT_WINDOW) < <system::Module<T>>::block_number().as_() {
+			return Err("equivocation proof is too old");
+		}
+
+		// the authorities of the current set, which is the set of the proof.
+		let offender = T::ConvertAuthorityIdToSessionKey::convert(proof.offender.clone());
+		if !Self::grandpa_authorities().iter().any(|(key, _)| *key == offender) {
+			return Err("offender is not a current authority");
+		}
+
+		let equivocation = (proof.set_id, proof.round, proof.kind, &proof.offender).encode();
+		if Self::reported_equivocation(&equivocation) {
+			return Err("equivocation already reported");
+		}
+		Ok((offender, equivocation))
+	}
 }
 
 impl<T: Trait> Module<T> where AuthorityId: core::convert::From<<T as Trait>::SessionKey> {
@@ -325,6 +399,25 @@ impl<T: Trait> Module<T> where AuthorityId: core::convert::From<<T as Trait>::Se
 	}
 }
 
+impl<T: Trait> ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		match call {
+			Call::report_misbehavior(proof) => match Self::check_equivocation(proof) {
+				Ok((_, equivocation)) => TransactionValidity::Valid {
+					priority: TransactionPriority::max_value(),
+					requires: Vec::new(),
+					provides: rstd::iter::once(equivocation).collect(),
+					longevity: TransactionLongevity::max_value(),
+				},
+				Err(_) => TransactionValidity::Invalid(0),
+			},
+			_ => TransactionValidity::Invalid(0),
+		}
+	}
+}
+
 /// Helper for authorities being synchronized with the general session authorities.
 ///
 /// This is not the only way to manage an authority set for GRANDPA, but it is
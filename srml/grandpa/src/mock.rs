@@ -18,13 +18,14 @@
 
 #![cfg(test)]
 
-use primitives::{BuildStorage, traits::IdentityLookup, testing::{Digest, DigestItem, Header}};
+use std::cell::RefCell;
+use primitives::{BuildStorage, traits::{IdentityLookup, Convert}, testing::{Digest, DigestItem, Header}};
 use primitives::generic::DigestItem as GenDigestItem;
 use runtime_io;
 use srml_support::{impl_outer_origin, impl_outer_event};
-use substrate_primitives::{H256, Blake2Hasher};
+use substrate_primitives::{H256, Blake2Hasher, ed25519, crypto::Pair};
 use parity_codec::{Encode, Decode};
-use crate::{GenesisConfig, Trait, Module, RawLog};
+use crate::{GenesisConfig, Trait, Module, RawLog, AuthorityId};
 
 impl_outer_origin!{
 	pub enum Origin for Test {}
@@ -36,6 +37,30 @@ impl From<RawLog<u64, u64>> for DigestItem {
 	}
 }
 
+thread_local! {
+	pub static PUNISHED: RefCell<Vec<u64>> = RefCell::new(vec![]);
+}
+
+/// The key pair of the test authority with session key `key`.
+pub fn authority_pair(key: u64) -> ed25519::Pair {
+	ed25519::Pair::from_seed([key as u8; 32])
+}
+
+/// Maps the ids of the first test authorities to their session keys.
+pub struct ConvertTestAuthorityId;
+impl Convert<AuthorityId, u64> for ConvertTestAuthorityId {
+	fn convert(id: AuthorityId) -> u64 {
+		(0..16).find(|key| authority_pair(*key).public() == id).unwrap_or(u64::max_value())
+	}
+}
+
+pub struct TestPunisher;
+impl consensus::OnMisbehaviorReport<u64> for TestPunisher {
+	fn handle_report(offender: u64) {
+		PUNISHED.with(|p| p.borrow_mut().push(offender));
+	}
+}
+
 // Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 #[derive(Clone, PartialEq, Eq, Debug, Decode, Encode)]
 pub struct Test;
@@ -43,6 +68,8 @@ impl Trait for Test {
 	type Log = DigestItem;
 	type SessionKey = u64;
 	type Event = TestEvent;
+	type ConvertAuthorityIdToSessionKey = ConvertTestAuthorityId;
+	type OnMisbehaviorReport = TestPunisher;
 }
 impl system::Trait for Test {
 	type Origin = Origin;
@@ -20,8 +20,12 @@
 
 use primitives::{testing, traits::OnFinalize};
 use primitives::traits::Header;
+use primitives::transaction_validity::TransactionValidity;
 use runtime_io::with_externalities;
-use crate::mock::{Grandpa, System, new_test_ext};
+use srml_support::{assert_ok, assert_noop};
+use substrate_primitives::{H256, crypto::Pair};
+use fg_primitives::VoteKind;
+use crate::mock::{Grandpa, System, Origin, Test, PUNISHED, authority_pair, new_test_ext};
 use system::{EventRecord, Phase};
 use crate::{RawLog, RawEvent};
 use codec::{Decode, Encode};
@@ -198,3 +202,145 @@ fn dispatch_forced_change() {
 		let _ = header;
 	});
 }
+
+fn equivocation_proof(offender: u64, round: u64, targets: [u8; 2]) -> EquivocationProof<H256, u64> {
+	equivocation_proof_in_set(0, offender, round, targets)
+}
+
+fn equivocation_proof_in_set(
+	set_id: u64,
+	offender: u64,
+	round: u64,
+	targets: [u8; 2],
+) -> EquivocationProof<H256, u64> {
+	let pair = authority_pair(offender);
+	let vote = |target: u8| {
+		let target = (H256::repeat_byte(target), target as u64);
+		let payload = ((VoteKind::Prevote, &target.0, &target.1), round, set_id).encode();
+		let signature = pair.sign(&payload);
+		(target, signature)
+	};
+
+	EquivocationProof {
+		set_id,
+		round,
+		kind: VoteKind::Prevote,
+		offender: pair.public(),
+		first_vote: vote(targets[0]),
+		second_vote: vote(targets[1]),
+	}
+}
+
+// the blocks `equivocation_proof` votes for, up to `last`.
+fn import_vote_targets(last: u8) {
+	for target in 1..=last {
+		<system::BlockHash<Test>>::insert(target as u64, H256::repeat_byte(target));
+	}
+}
+
+#[test]
+fn equivocation_is_punished_once() {
+	with_externalities(&mut new_test_ext(vec![(1, 1), (2, 1), (3, 1)]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+		import_vote_targets(3);
+
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::signed(1), equivocation_proof(2, 1, [1, 2])),
+			"bad origin: expected to be no origin"
+		);
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 1])),
+			"invalid equivocation proof"
+		);
+		let mut forged = equivocation_proof(2, 1, [1, 2]);
+		forged.round = 2;
+		assert_noop!(Grandpa::report_misbehavior(Origin::NONE, forged), "invalid equivocation proof");
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(4, 1, [1, 2])),
+			"offender is not a current authority"
+		);
+
+		assert_ok!(Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 2])));
+		assert_eq!(PUNISHED.with(|p| p.borrow().clone()), vec![2]);
+
+		// other votes of the same round are the same equivocation.
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [2, 3])),
+			"equivocation already reported"
+		);
+		assert_ok!(Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 2, [1, 2])));
+		assert_eq!(PUNISHED.with(|p| p.borrow().clone()), vec![2, 2]);
+	});
+}
+
+#[test]
+fn equivocation_reports_are_validated() {
+	with_externalities(&mut new_test_ext(vec![(1, 1), (2, 1), (3, 1)]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+		import_vote_targets(2);
+
+		let call = Call::report_misbehavior(equivocation_proof(2, 1, [1, 2]));
+		match Grandpa::validate_unsigned(&call) {
+			TransactionValidity::Valid { provides, .. } => assert_eq!(provides.len(), 1),
+			other => panic!("Unexpected validity: {:?}", other),
+		}
+		assert_eq!(
+			Grandpa::validate_unsigned(&Call::report_misbehavior(equivocation_proof(2, 1, [1, 1]))),
+			TransactionValidity::Invalid(0),
+		);
+
+		assert_ok!(Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 2])));
+		assert_eq!(Grandpa::validate_unsigned(&call), TransactionValidity::Invalid(0));
+	});
+}
+
+#[test]
+fn old_equivocations_are_rejected_and_forgotten() {
+	with_externalities(&mut new_test_ext(vec![(1, 1), (2, 1), (3, 1)]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+		import_vote_targets(2);
+		assert_ok!(Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 2])));
+		let equivocation = (0u64, 1u64, VoteKind::Prevote, authority_pair(2).public()).encode();
+		assert!(Grandpa::reported_equivocation(&equivocation));
+
+		Grandpa::on_finalize(consensus::MISBEHAVIOR_REPORT_WINDOW);
+		assert!(Grandpa::reported_equivocation(&equivocation));
+		Grandpa::on_finalize(consensus::MISBEHAVIOR_REPORT_WINDOW + 1);
+		assert!(!Grandpa::reported_equivocation(&equivocation));
+
+		// the votes target block 2 at most, too long ago to be reported again.
+		System::initialize(&(consensus::MISBEHAVIOR_REPORT_WINDOW + 3), &Default::default(), &Default::default());
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 2])),
+			"equivocation proof is too old"
+		);
+	});
+}
+
+#[test]
+fn equivocations_must_be_from_the_current_set_and_chain() {
+	with_externalities(&mut new_test_ext(vec![(1, 1), (2, 1), (3, 1)]), || {
+		System::initialize(&1, &Default::default(), &Default::default());
+		import_vote_targets(2);
+
+		// the targets are signed by the offender but unknown to the chain.
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [3, 4])),
+			"equivocation proof does not target a known block"
+		);
+
+		Grandpa::schedule_change(vec![(1, 1), (2, 1)], 0, None).unwrap();
+		Grandpa::on_finalize(1);
+		assert_eq!(Grandpa::current_set_id(), 1);
+
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof(2, 1, [1, 2])),
+			"equivocation proof is not from the current authority set"
+		);
+		assert_noop!(
+			Grandpa::report_misbehavior(Origin::NONE, equivocation_proof_in_set(1, 3, 1, [1, 2])),
+			"offender is not a current authority"
+		);
+		assert_ok!(Grandpa::report_misbehavior(Origin::NONE, equivocation_proof_in_set(1, 2, 1, [1, 2])));
+	});
+}
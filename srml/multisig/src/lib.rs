@@ -0,0 +1,508 @@
+// Copyright 2019 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Multisig Module
+//!
+//! - [`multisig::Trait`](./trait.Trait.html)
+//! - [`Call`](./enum.Call.html)
+//!
+//! ## Overview
+//!
+//! The Multisig module lets a set of accounts (the "signatories") jointly control a single
+//! account, dispatching calls from it once a given number of them (the "threshold") agreed.
+//!
+//! The multisig account has no key of its own: its id is derived from the hash of the sorted
+//! signatories and the threshold, see `multi_account_id`. Funds can be sent to it like to any
+//! other account, and can only leave it through a call approved by enough signatories.
+//!
+//! A call is identified by its hash. The first signatory approving it opens the operation and
+//! reserves a deposit proportional to the threshold, which is returned once the call is
+//! dispatched or the operation cancelled. The call itself is only needed by the last approval.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `as_multi` - Approve a call and dispatch it from the multisig account if this reaches the
+//!   threshold.
+//! * `approve_as_multi` - Approve a call by its hash, without dispatching it.
+//! * `cancel_as_multi` - Close an operation and return its deposit, callable by the signatory
+//!   which opened it.
+//!
+//! ### Public Functions
+//!
+//! * `multi_account_id` - The account controlled by a set of signatories and a threshold.
+//!
+//! ## Related Modules
+//!
+//! * [Sudo](../srml_sudo/index.html)
+//! * [Balances](../srml_balances/index.html)
+//!
+//! [`Call`]: ./enum.Call.html
+//! [`Trait`]: ./trait.Trait.html
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use rstd::{prelude::*, result};
+use parity_codec::{Encode, Decode};
+use runtime_primitives::traits::{Hash, As, Saturating};
+use srml_support::{
+	StorageMap, Parameter, Dispatchable, decl_module, decl_event, decl_storage, ensure,
+};
+use srml_support::traits::{Currency, ReservableCurrency, Get};
+use srml_support::dispatch::{
+	SimpleDispatchInfo, GetDispatchInfo, WeighData, ClassifyDispatch, DispatchClass, Weight,
+};
+use system::ensure_signed;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// A call dispatched from a multisig account.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo;
+
+	/// The currency in which the deposits are held.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Deposit held for every open operation, whatever its threshold.
+	type DepositBase: Get<BalanceOf<Self>>;
+
+	/// Additional deposit held for every approval the operation needs.
+	type DepositFactor: Get<BalanceOf<Self>>;
+
+	/// Maximum number of signatories of a multisig account.
+	type MaxSignatories: Get<u16>;
+}
+
+/// An operation waiting for enough approvals.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct Multisig<AccountId, Balance> {
+	/// The signatory which opened the operation and holds its deposit.
+	pub depositor: AccountId,
+	/// The amount held in reserve by the depositor.
+	pub deposit: Balance,
+	/// The signatories which approved the operation so far.
+	pub approvals: Vec<AccountId>,
+}
+
+/// Weight of `as_multi` besides the weight of the call it may dispatch.
+const AS_MULTI_BASE_WEIGHT: Weight = 50_000;
+
+/// Weight of `as_multi`, which includes the weight of the call as it may be dispatched.
+struct AsMultiWeight;
+
+impl<'a, 'b, 'c, A, C: GetDispatchInfo> WeighData<(&'a u16, &'b A, &'c Box<C>)> for AsMultiWeight {
+	fn weigh_data(&self, (_, _, call): (&'a u16, &'b A, &'c Box<C>)) -> Weight {
+		call.get_dispatch_info().weight.saturating_add(AS_MULTI_BASE_WEIGHT)
+	}
+}
+
+impl<T> ClassifyDispatch<T> for AsMultiWeight {
+	fn classify_dispatch(&self, _: T) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
+		/// Approve `call` as one of the signatories of the multisig account defined by
+		/// `threshold` and the sender together with `other_signatories`, which must be sorted.
+		///
+		/// If this approval reaches the threshold the call is dispatched with the multisig
+		/// account as signed origin, and the deposit is returned. Otherwise the approval is
+		/// recorded, and a deposit is reserved from the sender if it is the first one.
+		///
+		/// The weight of this call includes the weight of `call`, which is paid for by every
+		/// approval.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[weight = AsMultiWeight]
+		fn as_multi(
+			origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			call: Box<<T as Trait>::Call>
+		) {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::ensure_signatories(threshold, other_signatories, &who)?;
+			let id = Self::multi_account_id(&signatories, threshold);
+			let call_hash = T::Hashing::hash_of(&call);
+
+			let mut multisig = match <Multisigs<T>>::get((id.clone(), call_hash)) {
+				Some(multisig) => multisig,
+				None => Self::open(&who, threshold)?,
+			};
+			let approved = multisig.approvals.contains(&who);
+			if !approved {
+				multisig.approvals.push(who.clone());
+			}
+
+			if multisig.approvals.len() < threshold as usize {
+				ensure!(!approved, "already approved");
+				<Multisigs<T>>::insert((id.clone(), call_hash), multisig);
+				Self::deposit_event(RawEvent::Approval(who, id, call_hash));
+				return Ok(());
+			}
+
+			<Multisigs<T>>::remove((id.clone(), call_hash));
+			let _ = T::Currency::unreserve(&multisig.depositor, multisig.deposit);
+			let ok = call.dispatch(system::RawOrigin::Signed(id.clone()).into()).is_ok();
+			Self::deposit_event(RawEvent::Executed(who, id, call_hash, ok));
+		}
+
+		/// Approve the call with hash `call_hash` as one of the signatories of the multisig
+		/// account defined by `threshold` and the sender together with `other_signatories`.
+		///
+		/// The call is never dispatched by this function: once enough signatories approved it,
+		/// any of them can dispatch it by submitting the call itself through `as_multi`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+		fn approve_as_multi(
+			origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			call_hash: T::Hash
+		) {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::ensure_signatories(threshold, other_signatories, &who)?;
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let mut multisig = match <Multisigs<T>>::get((id.clone(), call_hash)) {
+				Some(multisig) => multisig,
+				None => Self::open(&who, threshold)?,
+			};
+			ensure!(!multisig.approvals.contains(&who), "already approved");
+			multisig.approvals.push(who.clone());
+
+			<Multisigs<T>>::insert((id.clone(), call_hash), multisig);
+			Self::deposit_event(RawEvent::Approval(who, id, call_hash));
+		}
+
+		/// Cancel the operation on the call with hash `call_hash` of the multisig account defined
+		/// by `threshold` and the sender together with `other_signatories`, returning its deposit.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the signatory which opened the
+		/// operation.
+		#[weight = SimpleDispatchInfo::FixedNormal(10_000)]
+		fn cancel_as_multi(
+			origin,
+			threshold: u16,
+			other_signatories: Vec<T::AccountId>,
+			call_hash: T::Hash
+		) {
+			let who = ensure_signed(origin)?;
+			let signatories = Self::ensure_signatories(threshold, other_signatories, &who)?;
+			let id = Self::multi_account_id(&signatories, threshold);
+
+			let multisig = <Multisigs<T>>::get((id.clone(), call_hash)).ok_or("no such operation")?;
+			ensure!(multisig.depositor == who, "only the depositor can cancel an operation");
+
+			<Multisigs<T>>::remove((id.clone(), call_hash));
+			let _ = T::Currency::unreserve(&multisig.depositor, multisig.deposit);
+			Self::deposit_event(RawEvent::Cancelled(who, id, call_hash));
+		}
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where <T as system::Trait>::AccountId, <T as system::Trait>::Hash {
+		/// A signatory (first) approved the call with given hash of a multisig account (second).
+		Approval(AccountId, AccountId, Hash),
+		/// A signatory (first) gave the last approval of a call of a multisig account (second),
+		/// which was dispatched with given success.
+		Executed(AccountId, AccountId, Hash, bool),
+		/// The depositor (first) cancelled the operation on a call of a multisig account (second).
+		Cancelled(AccountId, AccountId, Hash),
+	}
+);
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Multisig {
+		/// Open operations, by multisig account and call hash.
+		pub Multisigs get(multisigs): map (T::AccountId, T::Hash) => Option<Multisig<T::AccountId, BalanceOf<T>>>;
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account controlled by the sorted `signatories` with given `threshold`.
+	pub fn multi_account_id(signatories: &[T::AccountId], threshold: u16) -> T::AccountId {
+		let entropy = (&b"multisig"[..], signatories, threshold).using_encoded(T::Hashing::hash);
+		T::AccountId::decode(&mut entropy.as_ref()).unwrap_or_default()
+	}
+
+	/// The deposit held for an operation needing `threshold` approvals.
+	pub fn deposit(threshold: u16) -> BalanceOf<T> {
+		T::DepositFactor::get()
+			.saturating_mul(<BalanceOf<T> as As<u64>>::sa(threshold as u64))
+			.saturating_add(T::DepositBase::get())
+	}
+
+	/// Check the signatories and threshold of a multisig account, returning all its
+	/// signatories, sorted.
+	fn ensure_signatories(
+		threshold: u16,
+		other_signatories: Vec<T::AccountId>,
+		who: &T::AccountId,
+	) -> result::Result<Vec<T::AccountId>, &'static str> {
+		ensure!(threshold >= 2, "threshold must be at least two");
+		ensure!(
+			other_signatories.len() < T::MaxSignatories::get() as usize,
+			"too many signatories"
+		);
+		ensure!(
+			threshold as usize <= other_signatories.len() + 1,
+			"threshold exceeds the number of signatories"
+		);
+		ensure!(
+			other_signatories.windows(2).all(|w| w[0] < w[1]),
+			"signatories must be sorted and unique"
+		);
+		ensure!(!other_signatories.contains(who), "sender is in the other signatories");
+
+		let mut signatories = other_signatories;
+		let index = signatories.iter().position(|s| s > who).unwrap_or(signatories.len());
+		signatories.insert(index, who.clone());
+		Ok(signatories)
+	}
+
+	/// Open an operation needing `threshold` approvals, reserving its deposit from `depositor`.
+	fn open(
+		depositor: &T::AccountId,
+		threshold: u16,
+	) -> result::Result<Multisig<T::AccountId, BalanceOf<T>>, &'static str> {
+		let deposit = Self::deposit(threshold);
+		T::Currency::reserve(depositor, deposit)
+			.map_err(|_| "not enough free funds for the deposit")?;
+		Ok(Multisig {
+			depositor: depositor.clone(),
+			deposit,
+			approvals: Vec::new(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use runtime_io::with_externalities;
+	use srml_support::{impl_outer_origin, impl_outer_dispatch, assert_ok, assert_noop, parameter_types};
+	use substrate_primitives::{H256, Blake2Hasher};
+	use runtime_primitives::BuildStorage;
+	use runtime_primitives::traits::{BlakeTwo256, IdentityLookup};
+	use runtime_primitives::testing::{Digest, DigestItem, Header};
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call for Test where origin: Origin {
+			balances::Balances,
+			multisig::Multisig,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type Event = ();
+		type Log = DigestItem;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = ();
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+	}
+	parameter_types! {
+		pub const DepositBase: u64 = 1;
+		pub const DepositFactor: u64 = 1;
+		pub const MaxSignatories: u16 = 3;
+	}
+	impl Trait for Test {
+		type Event = ();
+		type Call = Call;
+		type Currency = Balances;
+		type DepositBase = DepositBase;
+		type DepositFactor = DepositFactor;
+		type MaxSignatories = MaxSignatories;
+	}
+	type Balances = balances::Module<Test>;
+	type Multisig = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 10), (2, 10), (3, 10), (4, 10)],
+			transaction_base_fee: 0,
+			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
+			transfer_fee: 0,
+			creation_fee: 0,
+			existential_deposit: 0,
+			vesting: vec![],
+		}.build_storage().unwrap().0);
+		t.into()
+	}
+
+	fn transfer(dest: u64, value: u64) -> Box<Call> {
+		Box::new(Call::Balances(balances::Call::transfer(dest, value)))
+	}
+
+	#[test]
+	fn multi_account_id_depends_on_signatories_and_threshold() {
+		with_externalities(&mut new_test_ext(), || {
+			let id = Multisig::multi_account_id(&[1, 2, 3], 2);
+			assert_eq!(id, Multisig::multi_account_id(&[1, 2, 3], 2));
+			assert!(id != Multisig::multi_account_id(&[1, 2, 3], 3));
+			assert!(id != Multisig::multi_account_id(&[1, 2, 4], 2));
+		});
+	}
+
+	#[test]
+	fn as_multi_weighs_the_call() {
+		let call = transfer(4, 5);
+		let weight = Call::Multisig(super::Call::as_multi(2, vec![2, 3], call.clone())).get_dispatch_info().weight;
+		assert_eq!(weight, call.get_dispatch_info().weight + AS_MULTI_BASE_WEIGHT);
+	}
+
+	#[test]
+	fn call_is_dispatched_at_threshold() {
+		with_externalities(&mut new_test_ext(), || {
+			let id = Multisig::multi_account_id(&[1, 2, 3], 2);
+			assert_ok!(Balances::transfer(Origin::signed(1), id, 5));
+
+			let call = transfer(4, 5);
+			let hash = BlakeTwo256::hash_of(&call);
+			assert_ok!(Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], call.clone()));
+			assert_eq!(Balances::reserved_balance(&1), 3);
+			assert_eq!(Multisig::multisigs((id, hash)).unwrap().approvals, vec![1]);
+			assert_noop!(
+				Multisig::as_multi(Origin::signed(1), 2, vec![2, 3], call.clone()),
+				"already approved"
+			);
+
+			assert_ok!(Multisig::as_multi(Origin::signed(3), 2, vec![1, 2], call));
+			assert_eq!(Balances::free_balance(&id), 0);
+			assert_eq!(Balances::free_balance(&4), 15);
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Multisig::multisigs((id, hash)), None);
+		});
+	}
+
+	#[test]
+	fn approvals_by_hash_need_the_call_to_dispatch() {
+		with_externalities(&mut new_test_ext(), || {
+			let id = Multisig::multi_account_id(&[1, 2, 3], 3);
+			assert_ok!(Balances::transfer(Origin::signed(1), id, 5));
+
+			let call = transfer(4, 5);
+			let hash = BlakeTwo256::hash_of(&call);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(2), 3, vec![1, 3], hash));
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(3), 3, vec![1, 2], hash));
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(3), 3, vec![1, 2], hash),
+				"already approved"
+			);
+			assert_eq!(Balances::reserved_balance(&2), 4);
+			assert_eq!(Balances::free_balance(&id), 5);
+
+			assert_ok!(Multisig::as_multi(Origin::signed(1), 3, vec![2, 3], call));
+			assert_eq!(Balances::free_balance(&4), 15);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+		});
+	}
+
+	#[test]
+	fn only_depositor_can_cancel() {
+		with_externalities(&mut new_test_ext(), || {
+			let hash = BlakeTwo256::hash_of(&transfer(4, 5));
+			assert_noop!(
+				Multisig::cancel_as_multi(Origin::signed(1), 2, vec![2, 3], hash),
+				"no such operation"
+			);
+			assert_ok!(Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], hash));
+			assert_noop!(
+				Multisig::cancel_as_multi(Origin::signed(2), 2, vec![1, 3], hash),
+				"only the depositor can cancel an operation"
+			);
+			assert_ok!(Multisig::cancel_as_multi(Origin::signed(1), 2, vec![2, 3], hash));
+			assert_eq!(Balances::reserved_balance(&1), 0);
+			assert_eq!(Multisig::multisigs((Multisig::multi_account_id(&[1, 2, 3], 2), hash)), None);
+		});
+	}
+
+	#[test]
+	fn signatories_are_checked() {
+		with_externalities(&mut new_test_ext(), || {
+			let hash = BlakeTwo256::hash_of(&transfer(4, 5));
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 1, vec![2], hash),
+				"threshold must be at least two"
+			);
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 3, vec![2], hash),
+				"threshold exceeds the number of signatories"
+			);
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3, 4], hash),
+				"too many signatories"
+			);
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 2, vec![3, 2], hash),
+				"signatories must be sorted and unique"
+			);
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 2, vec![1, 2], hash),
+				"sender is in the other signatories"
+			);
+		});
+	}
+
+	#[test]
+	fn opening_needs_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(Multisig::deposit(2), 3);
+			let hash = BlakeTwo256::hash_of(&transfer(4, 5));
+			assert_ok!(Balances::transfer(Origin::signed(1), 2, 8));
+			assert_noop!(
+				Multisig::approve_as_multi(Origin::signed(1), 2, vec![2, 3], hash),
+				"not enough free funds for the deposit"
+			);
+		});
+	}
+}
@@ -358,6 +358,8 @@ mod tests {
 		type Log = DigestItem;
 		type SessionKey = UintAuthorityId;
 		type InherentOfflineReport = ();
+		type MisbehaviorReport = ();
+		type OnMisbehaviorReport = ();
 	}
 	impl system::Trait for Test {
 		type Origin = Origin;
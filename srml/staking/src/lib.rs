@@ -203,6 +203,10 @@
 //! capped at their total stake (NOTE: This cap should never come into force in a correctly implemented,
 //! non-corrupted, well-configured system).
 //!
+//! A validator proven to have equivocated (see
+//! [`on_equivocating_validator`](./struct.Module.html#method.on_equivocating_validator)) is slashed at once by
+//! [`EquivocationSlash`](./struct.Module.html#method.equivocation_slash) of its `total` `Exposure` and unstaked.
+//!
 //! ### Additional Fund Management Operations
 //!
 //! Any funds already placed into stash can be the target of the following operations:
@@ -239,7 +243,8 @@
 use runtime_io::with_storage;
 use rstd::{prelude::*, result, collections::btree_map::BTreeMap};
 use parity_codec::{HasCompact, Encode, Decode};
-use srml_support::{StorageValue, StorageMap, EnumerableStorageMap, dispatch::Result};
+use srml_support::{StorageValue, StorageMap, EnumerableStorageMap};
+use srml_support::dispatch::{Result, WeighData, ClassifyDispatch, DispatchClass, Weight};
 use srml_support::{decl_module, decl_event, decl_storage, ensure};
 use srml_support::traits::{
 	Currency, OnFreeBalanceZero, OnDilution, LockIdentifier, LockableCurrency, WithdrawReasons,
@@ -261,6 +266,27 @@ use phragmen::{elect, ACCURACY, ExtendedBalance};
 const RECENT_OFFLINE_COUNT: usize = 32;
 const DEFAULT_MINIMUM_VALIDATOR_COUNT: u32 = 4;
 const MAX_NOMINATIONS: usize = 16;
+
+/// Weight of `nominate` besides the weight of its targets.
+const NOMINATE_BASE_WEIGHT: Weight = 20_000;
+/// Weight of every target of `nominate` taken into account.
+const NOMINATE_TARGET_WEIGHT: Weight = 5_000;
+
+/// Weight of `nominate`, which grows with the number of targets up to `MAX_NOMINATIONS`.
+struct NominateWeight;
+
+impl<'a, S> WeighData<(&'a Vec<S>,)> for NominateWeight {
+	fn weigh_data(&self, (targets,): (&'a Vec<S>,)) -> Weight {
+		let targets = targets.len().min(MAX_NOMINATIONS) as Weight;
+		NOMINATE_BASE_WEIGHT.saturating_add(NOMINATE_TARGET_WEIGHT.saturating_mul(targets))
+	}
+}
+
+impl<T> ClassifyDispatch<T> for NominateWeight {
+	fn classify_dispatch(&self, _: T) -> DispatchClass {
+		DispatchClass::Normal
+	}
+}
 const MAX_UNSTAKE_THRESHOLD: u32 = 10;
 
 /// Indicates the initial status of the staker.
@@ -437,6 +463,8 @@ decl_storage! {
 		pub SessionReward get(session_reward) config(): Perbill = Perbill::from_billionths(60);
 		/// Slash, per validator that is taken for the first time they are found to be offline.
 		pub OfflineSlash get(offline_slash) config(): Perbill = Perbill::from_millionths(1000); // Perbill::from_fraction() is only for std, so use from_millionths().
+		/// Slash of the exposure of a validator proven to have equivocated.
+		pub EquivocationSlash get(equivocation_slash) config(): Perbill = Perbill::from_percent(10);
 		/// Number of instances of offline reports before slashing begins for validators.
 		pub OfflineSlashGrace get(offline_slash_grace) config(): u32;
 		/// The length of the bonding duration in eras.
@@ -507,6 +535,9 @@ decl_storage! {
 
 		/// Most recent `RECENT_OFFLINE_COUNT` instances. (Who it was, when it was reported, how many instances they were offline for).
 		pub RecentlyOffline get(recently_offline): Vec<(T::AccountId, T::BlockNumber, u32)>;
+
+		/// Stashes slashed for an equivocation in the current era, which are not slashed again before the next one.
+		pub EquivocationSlashed get(equivocation_slashed): Vec<T::AccountId>;
 	}
 	add_extra_genesis {
 		config(stakers): Vec<(T::AccountId, T::AccountId, BalanceOf<T>, StakerStatus<T::AccountId>)>;
@@ -659,6 +690,9 @@ decl_module! {
 		/// Effects will be felt at the beginning of the next era.
 		///
 		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
+		///
+		/// At most `MAX_NOMINATIONS` targets are taken into account.
+		#[weight = NominateWeight]
 		fn nominate(origin, targets: Vec<<T::Lookup as StaticLookup>::Source>) {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or("not a controller")?;
@@ -758,6 +792,9 @@ decl_event!(
 		OfflineWarning(AccountId, u32),
 		/// One validator (and its nominators) has been slashed by the given amount.
 		OfflineSlash(AccountId, Balance),
+		/// One validator (and its nominators) has been slashed by the given amount for an
+		/// equivocation.
+		EquivocationSlash(AccountId, Balance),
 	}
 );
 
@@ -910,6 +947,7 @@ impl<T: Trait> Module<T> {
 
 		// Increment current era.
 		<CurrentEra<T>>::put(&(<CurrentEra<T>>::get() + One::one()));
+		<EquivocationSlashed<T>>::kill();
 
 		// Enact era length change.
 		if let Some(next_spe) = Self::next_sessions_per_era() {
@@ -1093,6 +1131,34 @@ impl<T: Trait> Module<T> {
 			Self::deposit_event(event);
 		}
 	}
+
+	/// Call when a validator is proven to have equivocated, e.g. by authoring two blocks
+	/// for the same slot or voting twice in the same finality round.
+	///
+	/// The validator is slashed by `EquivocationSlash` of its exposure, with no grace, and
+	/// removed from the validator candidates. It is slashed at most once per era, further
+	/// equivocations until the next era are ignored.
+	pub fn on_equivocating_validator(controller: T::AccountId) {
+		if let Some(l) = Self::ledger(&controller) {
+			let stash = l.stash;
+
+			// Early exit if validator is invulnerable.
+			if Self::invulnerables().contains(&stash) {
+				return
+			}
+			if Self::equivocation_slashed().contains(&stash) {
+				return
+			}
+			<EquivocationSlashed<T>>::mutate(|slashed| slashed.push(stash.clone()));
+
+			let slash = Self::equivocation_slash() * Self::stakers(&stash).total;
+			let _ = Self::slash_validator(&stash, slash);
+			<Validators<T>>::remove(&stash);
+			let _ = Self::apply_force_new_era(false);
+
+			Self::deposit_event(RawEvent::EquivocationSlash(stash, slash));
+		}
+	}
 }
 
 impl<T: Trait> OnSessionChange<T::Moment> for Module<T> {
@@ -1121,3 +1187,13 @@ impl<T: Trait> consensus::OnOfflineReport<Vec<u32>> for Module<T> {
 		}
 	}
 }
+
+impl<T: Trait> consensus::OnMisbehaviorReport<T::SessionKey> for Module<T> {
+	fn handle_report(offender: T::SessionKey) {
+		// the session module sets the authorities in the order of the validators.
+		let index = <consensus::Module<T>>::authorities().iter().position(|a| *a == offender);
+		if let Some(v) = index.and_then(|i| <session::Module<T>>::validators().get(i).cloned()) {
+			Self::on_equivocating_validator(v);
+		}
+	}
+}
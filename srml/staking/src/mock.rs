@@ -50,6 +50,8 @@ impl consensus::Trait for Test {
 	type Log = DigestItem;
 	type SessionKey = UintAuthorityId;
 	type InherentOfflineReport = ();
+	type MisbehaviorReport = ();
+	type OnMisbehaviorReport = ();
 }
 impl system::Trait for Test {
 	type Origin = Origin;
@@ -194,6 +196,7 @@ impl ExtBuilder {
 			],
 			transaction_base_fee: 0,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
 			existential_deposit: self.existential_deposit,
 			transfer_fee: 0,
 			creation_fee: 0,
@@ -225,6 +228,7 @@ impl ExtBuilder {
 			bonding_duration: self.sessions_per_era * self.session_length * 3,
 			session_reward: Perbill::from_millionths((1000000 * self.reward / balance_factor) as u32),
 			offline_slash: Perbill::from_percent(5),
+			equivocation_slash: Perbill::from_percent(10),
 			current_session_reward: self.reward,
 			offline_slash_grace: 0,
 			invulnerables: vec![],
@@ -24,6 +24,8 @@ use phragmen;
 use srml_support::{assert_ok, assert_noop, assert_eq_uvec, EnumerableStorageMap};
 use mock::{Balances, Session, Staking, System, Timestamp, Test, ExtBuilder, Origin};
 use srml_support::traits::{Currency, ReservableCurrency};
+use primitives::testing::UintAuthorityId;
+use consensus::OnMisbehaviorReport;
 
 #[inline]
 fn check_exposure(acc: u64) {
@@ -221,6 +223,46 @@ fn offline_should_slash_and_kick() {
 	});
 }
 
+#[test]
+fn equivocation_should_slash_and_kick() {
+	// Test that a validator proven to have equivocated gets slashed and kicked without grace
+	with_externalities(&mut ExtBuilder::default().build(), || {
+		let _ = Balances::make_free_balance_be(&11, 1000);
+		assert!(<Validators<Test>>::exists(&11));
+		assert_eq!(Staking::equivocation_slash(), Perbill::from_percent(10));
+		// Authorities are in the order of the session validators, i.e. the controllers
+		assert_eq!(Session::validators(), vec![10, 20]);
+		<consensus::Module<Test>>::set_authorities(&[UintAuthorityId(10), UintAuthorityId(20)]);
+
+		// Unknown authorities are ignored
+		Staking::handle_report(UintAuthorityId(30));
+		assert_eq!(Balances::free_balance(&11), 1000);
+		assert!(Staking::forcing_new_era().is_none());
+
+		Staking::handle_report(UintAuthorityId(10));
+		// Slashed by equivocation_slash() * amount_at_stake, offline reports are untouched
+		let slash = Staking::equivocation_slash() * Staking::stakers(11).total;
+		assert_eq!(slash, 112);
+		assert_eq!(Balances::free_balance(&11), 1000 - slash);
+		assert_eq!(Staking::slash_count(&11), 0);
+		// Account 10 has been removed as a validator, account 20 is untouched
+		assert!(!<Validators<Test>>::exists(&11));
+		assert!(<Validators<Test>>::exists(&21));
+		// A new era is forced due to slashing
+		assert!(Staking::forcing_new_era().is_some());
+
+		// Further equivocations are ignored until the next era
+		Staking::handle_report(UintAuthorityId(10));
+		assert_eq!(Balances::free_balance(&11), 1000 - slash);
+		assert_eq!(Staking::equivocation_slashed(), vec![11]);
+
+		System::set_block_number(1);
+		Session::check_rotate_session(System::block_number());
+		assert_eq!(Staking::current_era(), 1);
+		assert!(Staking::equivocation_slashed().is_empty());
+	});
+}
+
 #[test]
 fn offline_grace_should_delay_slashing() {
 	// Tests that with grace, slashing is delayed
@@ -2032,3 +2074,14 @@ fn phragmen_large_scale_test_2() {
 		assert_total_expo(5, nom_budget / 2 + c_budget);
 	})
 }
+
+#[test]
+fn nominate_weight_grows_with_targets() {
+	use srml_support::dispatch::GetDispatchInfo;
+
+	let weight = |targets: usize| Call::<Test>::nominate(vec![11; targets]).get_dispatch_info().weight;
+	assert_eq!(weight(1), NOMINATE_BASE_WEIGHT + NOMINATE_TARGET_WEIGHT);
+	assert!(weight(2) > weight(1));
+	// targets beyond `MAX_NOMINATIONS` are ignored, and so not weighed.
+	assert_eq!(weight(MAX_NOMINATIONS), weight(MAX_NOMINATIONS + 100));
+}
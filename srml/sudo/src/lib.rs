@@ -89,6 +89,7 @@
 use sr_std::prelude::*;
 use sr_primitives::traits::StaticLookup;
 use srml_support::{StorageValue, Parameter, Dispatchable, decl_module, decl_event, decl_storage, ensure};
+use srml_support::dispatch::SimpleDispatchInfo;
 use system::ensure_signed;
 
 pub trait Trait: system::Trait {
@@ -107,6 +108,7 @@ decl_module! {
 		/// Authenticates the sudo key and dispatches a function call with `Root` origin.
 		///
 		/// The dispatch origin for this call must be _Signed_.
+		#[weight = SimpleDispatchInfo::FixedOperational(1_000_000)]
 		fn sudo(origin, proposal: Box<T::Proposal>) {
 			// This is a public call, so we ensure that the origin is some signed account.
 			let sender = ensure_signed(origin)?;
@@ -119,6 +121,7 @@ decl_module! {
 		/// Authenticates the current sudo key and sets the given AccountId (`new`) as the new sudo key.
 		///
 		/// The dispatch origin for this call must be _Signed_.
+		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
 		fn set_key(origin, new: <T::Lookup as StaticLookup>::Source) {
 			// This is a public call, so we ensure that the origin is some signed account.
 			let sender = ensure_signed(origin)?;
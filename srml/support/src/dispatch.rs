@@ -26,6 +26,9 @@ pub use srml_metadata::{
 	FunctionMetadata, DecodeDifferent, DecodeDifferentArray,
 	FunctionArgumentMetadata, OuterDispatchMetadata, OuterDispatchCall
 };
+pub use crate::runtime_primitives::weights::{
+	SimpleDispatchInfo, GetDispatchInfo, DispatchInfo, WeighData, ClassifyDispatch, DispatchClass, Weight,
+};
 
 /// A type that cannot be instantiated.
 pub enum Never {}
@@ -158,6 +161,58 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 /// # fn main() {}
 /// ```
 ///
+/// ### Weight Example
+///
+/// Every function has a weight, an estimation of the resources its execution consumes, which
+/// bounds the number of its calls in a block and is used to compute their fees. It is given by
+/// the `#[weight = $x]` attribute, where `$x` implements [`WeighData`](./dispatch/trait.WeighData.html)
+/// for the tuple of references to the function arguments. Functions without the attribute get
+/// `SimpleDispatchInfo::default()`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate srml_support;
+/// # use srml_support::dispatch::{Result, SimpleDispatchInfo};
+/// # use srml_system::{self as system, Trait, ensure_signed};
+/// decl_module! {
+/// 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+///
+/// 		#[weight = SimpleDispatchInfo::FixedNormal(1_000_000)]
+/// 		fn my_expensive_function(origin) -> Result {
+///				// Your implementation
+/// 			Ok(())
+/// 		}
+///		}
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The same attribute gives the [`DispatchClass`](./dispatch/enum.DispatchClass.html) of the
+/// function, through [`ClassifyDispatch`](./dispatch/trait.ClassifyDispatch.html). Operational
+/// functions, such as governance and emergency calls, can use block space that normal ones
+/// cannot:
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate srml_support;
+/// # use srml_support::dispatch::{Result, SimpleDispatchInfo};
+/// # use srml_system::{self as system, Trait, ensure_root};
+/// decl_module! {
+/// 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+///
+/// 		#[weight = SimpleDispatchInfo::FixedOperational(10_000)]
+/// 		fn my_emergency_function(origin) -> Result {
+/// 			ensure_root(origin)?;
+/// 			Ok(())
+/// 		}
+///		}
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The weight and class of a call are returned by its
+/// [`GetDispatchInfo`](./dispatch/trait.GetDispatchInfo.html) implementation.
+///
 /// ## Multiple Module Instances Example
 ///
 /// A Substrate module can be built such that multiple instances of the same module can be used within a single
@@ -201,6 +256,12 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 /// * `offchain_worker`: Executes at the beginning of a block and produces extrinsics for a future block
 /// upon completion. Using this function will implement the
 /// [`OffchainWorker`](../sr_primitives/traits/trait.OffchainWorker.html) trait.
+///
+/// The reserved function `on_runtime_upgrade` takes no input. It executes once, at the beginning of
+/// the first block after the runtime's `spec_version` changed, and is the place to migrate the module's
+/// storage to a new layout, for example with the `translate` functions of the storage traits. Using this
+/// function will implement the [`OnRuntimeUpgrade`](../sr_primitives/traits/trait.OnRuntimeUpgrade.html)
+/// trait.
 #[macro_export]
 macro_rules! decl_module {
 	// Macro transformations (to convert invocations with incomplete parameters to the canonical
@@ -220,6 +281,7 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -239,6 +301,7 @@ macro_rules! decl_module {
 			{}
 			{}
 			{}
+			{}
 			[]
 			$($t)*
 		);
@@ -252,6 +315,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event $(<$dpeg:ident $(, $dpeg_instance:ident)?>)* () = default;
@@ -265,6 +329,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -277,6 +342,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		$vis:vis fn deposit_event $(<$dpeg:ident $(, $dpeg_instance:ident)?>)* (
@@ -292,6 +358,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -304,6 +371,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_finalize($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -317,6 +385,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ fn on_finalize( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -329,6 +398,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{}
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_finalise($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -346,6 +416,7 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_initialize($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -359,6 +430,7 @@ macro_rules! decl_module {
 			{ fn on_initialize( $( $param_name : $param ),* ) { $( $impl )* } }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -371,6 +443,7 @@ macro_rules! decl_module {
 		{}
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn on_initialise($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -388,6 +461,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
 		fn offchain_worker($($param_name:ident : $param:ty),* ) { $( $impl:tt )* }
@@ -401,6 +475,34 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ fn offchain_worker( $( $param_name : $param ),* ) { $( $impl )* } }
+			{ $( $on_runtime_upgrade )* }
+			[ $($t)* ]
+			$($rest)*
+		);
+	};
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{}
+		[ $($t:tt)* ]
+		$(#[doc = $doc_attr:tt])*
+		fn on_runtime_upgrade() { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, I: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ fn on_runtime_upgrade() { $( $impl )* } }
 			[ $($t)* ]
 			$($rest)*
 		);
@@ -413,8 +515,10 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
+		#[weight = $weight:expr]
 		$fn_vis:vis fn $fn_name:ident(
 			$origin:ident $(, $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -428,9 +532,11 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[
 				$($t)*
 				$(#[doc = $doc_attr])*
+				#[weight = $weight]
 				$fn_vis fn $fn_name(
 					$origin $( , $(#[$codec_attr])* $param_name : $param )*
 				) $( -> $result )* { $( $impl )* }
@@ -447,8 +553,10 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
+		$(#[weight = $weight:expr])?
 		$fn_vis:vis fn $fn_name:ident(
 			$origin:ident : T::Origin $(, $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -468,8 +576,10 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
+		$(#[weight = $weight:expr])?
 		$fn_vis:vis fn $fn_name:ident(
 			origin : $origin:ty $(, $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -489,8 +599,10 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 		$(#[doc = $doc_attr:tt])*
+		#[weight = $weight:expr]
 		$fn_vis:vis fn $fn_name:ident(
 			$( $(#[$codec_attr:ident])* $param_name:ident : $param:ty),*
 		) $( -> $result:ty )* { $( $impl:tt )* }
@@ -504,9 +616,11 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 			[
 				$($t)*
 				$(#[doc = $doc_attr])*
+				#[weight = $weight]
 				$fn_vis fn $fn_name(
 					root $( , $(#[$codec_attr])* $param_name : $param )*
 				) $( -> $result )* { $( $impl )* }
@@ -515,6 +629,39 @@ macro_rules! decl_module {
 			$($rest)*
 		);
 	};
+	// Add the default `#[weight]` to a function declared without it.
+	(@normalize
+		$(#[$attr:meta])*
+		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path $(= $module_default_instance:path)?)?>
+		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident
+		{ $( $deposit_event:tt )* }
+		{ $( $on_initialize:tt )* }
+		{ $( $on_finalize:tt )* }
+		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
+		[ $($t:tt)* ]
+		$(#[doc = $doc_attr:tt])*
+		$fn_vis:vis fn $fn_name:ident(
+			$( $params:tt )*
+		) $( -> $result:ty )* { $( $impl:tt )* }
+		$($rest:tt)*
+	) => {
+		$crate::decl_module!(@normalize
+			$(#[$attr])*
+			pub struct $mod_type<$trait_instance: $trait_name$(<I>, $instance: $instantiable $(= $module_default_instance)?)?>
+			for enum $call_type where origin: $origin_type, system = $system
+			{ $( $deposit_event )* }
+			{ $( $on_initialize )* }
+			{ $( $on_finalize )* }
+			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
+			[ $($t)* ]
+			$(#[doc = $doc_attr])*
+			#[weight = $crate::dispatch::SimpleDispatchInfo::default()]
+			$fn_vis fn $fn_name( $( $params )* ) $( -> $result )* { $( $impl )* }
+			$($rest)*
+		);
+	};
 	(@normalize
 		$(#[$attr:meta])*
 		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, I: $instantiable:path $(= $module_default_instance:path)?)?>
@@ -523,6 +670,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 		[ $($t:tt)* ]
 	) => {
 		$crate::decl_module!(@imp
@@ -535,6 +683,7 @@ macro_rules! decl_module {
 			{ $( $on_initialize )* }
 			{ $( $on_finalize )* }
 			{ $( $offchain )* }
+			{ $( $on_runtime_upgrade )* }
 		);
 	};
 
@@ -689,6 +838,27 @@ macro_rules! decl_module {
 		{}
 	};
 
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		fn on_runtime_upgrade() { $( $impl:tt )* }
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::runtime_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?>
+		{
+			fn on_runtime_upgrade() { $( $impl )* }
+		}
+	};
+
+	(@impl_on_runtime_upgrade
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+	) => {
+		impl<$trait_instance: $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::runtime_primitives::traits::OnRuntimeUpgrade
+			for $module<$trait_instance$(, $instance)?>
+		{}
+	};
+
 	(@impl_function
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
 		$origin_ty:ty;
@@ -866,6 +1036,7 @@ macro_rules! decl_module {
 		for enum $call_type:ident where origin: $origin_type:ty, system = $system:ident {
 			$(
 				$(#[doc = $doc_attr:tt])*
+				#[weight = $weight:expr]
 				$fn_vis:vis fn $fn_name:ident(
 					$from:ident $( , $(#[$codec_attr:ident])* $param_name:ident : $param:ty)*
 				) $( -> $result:ty )* { $( $impl:tt )* }
@@ -876,6 +1047,7 @@ macro_rules! decl_module {
 		{ $( $on_initialize:tt )* }
 		{ $( $on_finalize:tt )* }
 		{ $( $offchain:tt )* }
+		{ $( $on_runtime_upgrade:tt )* }
 	) => {
 		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
 		#[derive(Clone, Copy, PartialEq, Eq)]
@@ -900,6 +1072,12 @@ macro_rules! decl_module {
 			$( $offchain )*
 		}
 
+		$crate::decl_module! {
+			@impl_on_runtime_upgrade
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			$( $on_runtime_upgrade )*
+		}
+
 		$crate::decl_module! {
 			@impl_deposit_event
 			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
@@ -1001,6 +1179,29 @@ macro_rules! decl_module {
 			}
 		}
 
+		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::dispatch::GetDispatchInfo
+			for $call_type<$trait_instance $(, $instance)?>
+		{
+			fn get_dispatch_info(&self) -> $crate::dispatch::DispatchInfo {
+				match *self {
+					$(
+						$call_type::$fn_name( $( ref $param_name ),* ) => {
+							let weight = <dyn $crate::dispatch::WeighData<( $( & $param, )* )>>::weigh_data(
+								&$weight,
+								( $( $param_name, )* )
+							);
+							let class = <dyn $crate::dispatch::ClassifyDispatch<( $( & $param, )* )>>::classify_dispatch(
+								&$weight,
+								( $( $param_name, )* )
+							);
+							$crate::dispatch::DispatchInfo { weight, class }
+						},
+					)*
+					$call_type::__PhantomItem(_, _) => { unreachable!("__PhantomItem should never be used.") },
+				}
+			}
+		}
+
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $crate::dispatch::Dispatchable
 			for $call_type<$trait_instance $(, $instance)?>
 		{
@@ -1074,6 +1275,15 @@ macro_rules! impl_outer_dispatch {
 				}
 			}
 		}
+		impl $crate::dispatch::GetDispatchInfo for $call_type {
+			fn get_dispatch_info(&self) -> $crate::dispatch::DispatchInfo {
+				match self {
+					$(
+						$call_type::$camelcase(call) => call.get_dispatch_info(),
+					)*
+				}
+			}
+		}
 		$(
 			impl $crate::dispatch::IsSubType<$camelcase> for $call_type {
 				fn is_aux_sub_type(&self) -> Option<&<$camelcase as $crate::dispatch::Callable>::Call> {
@@ -1209,7 +1419,7 @@ macro_rules! __function_to_metadata {
 #[allow(dead_code)]
 mod tests {
 	use super::*;
-	use crate::runtime_primitives::traits::{OnInitialize, OnFinalize};
+	use crate::runtime_primitives::traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade};
 
 	pub trait Trait {
 		type Origin;
@@ -1227,16 +1437,20 @@ mod tests {
 	decl_module! {
 		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 			/// Hi, this is a comment.
+			#[weight = SimpleDispatchInfo::FixedNormal(7)]
 			fn aux_0(_origin) -> Result { unreachable!() }
 			fn aux_1(_origin, #[compact] _data: u32) -> Result { unreachable!() }
 			fn aux_2(_origin, _data: i32, _data2: String) -> Result { unreachable!() }
+			#[weight = SimpleDispatchInfo::FreeOperational]
 			fn aux_3() -> Result { unreachable!() }
+			#[weight = SimpleDispatchInfo::FixedNormal(3)]
 			fn aux_4(_data: i32) -> Result { unreachable!() }
 			fn aux_5(_origin, _data: i32, #[compact] _data2: u32) -> Result { unreachable!() }
 
 			fn on_initialize(n: T::BlockNumber) { if n.into() == 42 { panic!("on_initialize") } }
 			fn on_finalize(n: T::BlockNumber) { if n.into() == 42 { panic!("on_finalize") } }
 			fn offchain_worker() {}
+			fn on_runtime_upgrade() { panic!("on_runtime_upgrade") }
 		}
 	}
 
@@ -1344,6 +1558,26 @@ mod tests {
 		assert_eq!(decoded, call);
 	}
 
+	#[test]
+	fn weight_should_attach_to_call_enum() {
+		assert_eq!(
+			Call::<TraitImpl>::aux_0().get_dispatch_info(),
+			DispatchInfo { weight: 7, class: DispatchClass::Normal },
+		);
+		assert_eq!(
+			Call::<TraitImpl>::aux_3().get_dispatch_info(),
+			DispatchInfo { weight: 0, class: DispatchClass::Operational },
+		);
+		assert_eq!(
+			Call::<TraitImpl>::aux_4(1).get_dispatch_info(),
+			DispatchInfo { weight: 3, class: DispatchClass::Normal },
+		);
+		assert_eq!(
+			Call::<TraitImpl>::aux_2(1, "hello".into()).get_dispatch_info(),
+			DispatchInfo { weight: SimpleDispatchInfo::default().weigh_data(()), class: DispatchClass::Normal },
+		);
+	}
+
 	#[test]
 	#[should_panic(expected = "on_initialize")]
 	fn on_initialize_should_work() {
@@ -1355,4 +1589,10 @@ mod tests {
 	fn on_finalize_should_work() {
 		<Module<TraitImpl> as OnFinalize<u32>>::on_finalize(42);
 	}
+
+	#[test]
+	#[should_panic(expected = "on_runtime_upgrade")]
+	fn on_runtime_upgrade_should_work() {
+		<Module<TraitImpl> as OnRuntimeUpgrade>::on_runtime_upgrade();
+	}
 }
@@ -332,6 +332,25 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn linked_map_translate_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			// The values used to be pairs of `u32`, with the same encoded length as the `u64`.
+			Map::insert(17u32, 2u64 | 3u64 << 32);
+			assert_eq!(Map::translate(|_, (a, b): (u32, u32)| a as u64 + b as u64), Ok(()));
+			assert_eq!(Map::enumerate().collect::<Vec<_>>(), vec![(17, 5), (15, 42)]);
+
+			// Linkage is kept.
+			Map::remove(&17);
+			assert_eq!(Map::head(), Some(15));
+			assert_eq!(Map::enumerate().collect::<Vec<_>>(), vec![(15, 42)]);
+
+			// Nothing is written if any of the values cannot be decoded.
+			assert!(Map::translate(|_, old: u128| old as u64).is_err());
+			assert_eq!(Map::enumerate().collect::<Vec<_>>(), vec![(15, 42)]);
+		});
+	}
+
 	#[test]
 	fn double_map_basic_insert_remove_remove_prefix_should_work() {
 		with_externalities(&mut new_test_ext(), || {
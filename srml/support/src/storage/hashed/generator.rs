@@ -205,6 +205,32 @@ pub trait StorageValue<T: codec::Codec> {
 		storage.put_raw(Self::key(), &new_val);
 		Ok(())
 	}
+
+	/// Translate the value from some previous type `O` into the current one, typically in a
+	/// storage migration.
+	///
+	/// `f` is given the old value, if any, and returns the new one; the value is removed if it
+	/// returns `None`. Fails without altering storage if the value cannot be decoded as `O`.
+	fn translate<O: codec::Decode, F: FnOnce(Option<O>) -> Option<T>, S: HashedStorage<Twox128>>(
+		f: F, storage: &S
+	) -> Result<(), &'static str> {
+		let old = match storage.get_raw(Self::key()) {
+			Some(raw) => {
+				let input = &mut &raw[..];
+				let old = O::decode(input).ok_or("Could not decode the old value")?;
+				if !input.is_empty() {
+					return Err("Could not decode the old value");
+				}
+				Some(old)
+			},
+			None => None,
+		};
+		match f(old) {
+			Some(new) => storage.put(Self::key(), &new),
+			None => storage.kill(Self::key()),
+		}
+		Ok(())
+	}
 }
 
 /// A strongly-typed list in storage.
@@ -282,4 +308,44 @@ pub trait EnumerableStorageMap<K: codec::Codec, V: codec::Codec>: StorageMap<K,
 
 	/// Enumerate all elements in the map.
 	fn enumerate<'a, S: HashedStorage<Self::Hasher>>(storage: &'a S) -> Box<dyn Iterator<Item = (K, V)> + 'a> where K: 'a, V: 'a;
+
+	/// Translate the values of all elements from some previous type `O` into the current one,
+	/// typically in a storage migration.
+	///
+	/// The elements are only written once all of them have been translated, so that an
+	/// element whose value cannot be decoded as `O` leaves the whole map in the old format.
+	fn translate<O: codec::Decode, F: FnMut(&K, O) -> V, S: HashedStorage<Self::Hasher>>(
+		mut f: F, storage: &S
+	) -> Result<(), &'static str> {
+		let mut translated = Vec::new();
+		let mut next = Self::head(storage);
+		while let Some(key) = next {
+			let key_for = Self::key_for(&key);
+			let raw = storage.get_raw(&key_for).ok_or("Linked element is missing")?;
+			let input = &mut &raw[..];
+			let old = O::decode(input).ok_or("Could not decode the old value")?;
+
+			// The value is followed by the linkage of the element, its previous and next keys,
+			// which is kept as is.
+			let linkage = *input;
+			let (_previous, following): (Option<K>, Option<K>) = {
+				let input = &mut &linkage[..];
+				let decoded = codec::Decode::decode(input).ok_or("Could not decode the element linkage")?;
+				if !input.is_empty() {
+					return Err("Could not decode the element linkage");
+				}
+				decoded
+			};
+
+			let mut new = codec::Encode::encode(&f(&key, old));
+			new.extend_from_slice(linkage);
+			translated.push((key_for, new));
+			next = following;
+		}
+
+		for (key_for, new) in translated {
+			storage.put_raw(&key_for, &new);
+		}
+		Ok(())
+	}
 }
@@ -163,6 +163,13 @@ pub trait StorageValue<T: Codec> {
 	/// `T` is required to implement `codec::EncodeAppend`.
 	fn append<I: Encode>(items: &[I]) -> Result<(), &'static str>
 		where T: EncodeAppend<Item=I>;
+
+	/// Translate the value from some previous type `O` into the current one, e.g. in the
+	/// `on_runtime_upgrade` hook of a module.
+	///
+	/// `f` is given the old value, if any, and returns the new one, `None` removing the value.
+	/// Fails without altering storage if the value cannot be decoded as `O`.
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<(), &'static str>;
 }
 
 impl<T: Codec, U> StorageValue<T> for U where U: hashed::generator::StorageValue<T> {
@@ -194,6 +201,9 @@ impl<T: Codec, U> StorageValue<T> for U where U: hashed::generator::StorageValue
 	{
 		U::append(items, &RuntimeStorage)
 	}
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<(), &'static str> {
+		U::translate(f, &RuntimeStorage)
+	}
 }
 
 /// A strongly-typed list in storage.
@@ -340,6 +350,13 @@ pub trait EnumerableStorageMap<K: Codec, V: Codec>: StorageMap<K, V> {
 
 	/// Enumerate all elements in the map.
 	fn enumerate() -> Box<dyn Iterator<Item = (K, V)>> where K: 'static, V: 'static;
+
+	/// Translate the values of all elements from some previous type `O` into the current one,
+	/// e.g. in the `on_runtime_upgrade` hook of a module. `f` is given the key and old value of
+	/// every element.
+	///
+	/// Fails without changing any element if the value of one of them cannot be decoded as `O`.
+	fn translate<O: Decode, F: FnMut(&K, O) -> V>(f: F) -> Result<(), &'static str>;
 }
 
 impl<K: Codec, V: Codec, U> EnumerableStorageMap<K, V> for U where U: hashed::generator::EnumerableStorageMap<K, V> {
@@ -350,6 +367,10 @@ impl<K: Codec, V: Codec, U> EnumerableStorageMap<K, V> for U where U: hashed::ge
 	fn enumerate() -> Box<dyn Iterator<Item = (K, V)>> where K: 'static, V: 'static {
 		<U as hashed::generator::EnumerableStorageMap<K, V>>::enumerate(&RuntimeStorage)
 	}
+
+	fn translate<O: Decode, F: FnMut(&K, O) -> V>(f: F) -> Result<(), &'static str> {
+		<U as hashed::generator::EnumerableStorageMap<K, V>>::translate(f, &RuntimeStorage)
+	}
 }
 
 /// An implementation of a map with a two keys.
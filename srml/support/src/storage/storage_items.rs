@@ -407,6 +407,25 @@ mod tests {
 		assert!(Value::get(&storage).is_none());
 	}
 
+	#[test]
+	fn value_translate() {
+		let mut overlay = HashMap::new();
+		let storage = RefCell::new(&mut overlay);
+		// The value used to be a `u16`.
+		HashedStorage::<Twox128>::put(&storage, Value::key(), &7u16);
+		assert_eq!(Value::translate(|old: Option<u16>| old.map(|v| v as u32 * 2), &storage), Ok(()));
+		assert_eq!(Value::get(&storage), Some(14));
+
+		// A `u32` is not a `u16`.
+		assert!(Value::translate(|old: Option<u16>| old.map(Into::into), &storage).is_err());
+		assert_eq!(Value::get(&storage), Some(14));
+
+		assert_eq!(Value::translate(|_: Option<u32>| None, &storage), Ok(()));
+		assert!(Value::get(&storage).is_none());
+		assert_eq!(Value::translate(|old: Option<u16>| old.map(Into::into), &storage), Ok(()));
+		assert!(Value::get(&storage).is_none());
+	}
+
 	#[test]
 	fn list() {
 		let mut overlay = HashMap::new();
@@ -21,6 +21,7 @@ use crate::codec::{Codec, Encode, Decode};
 use crate::runtime_primitives::traits::{
 	MaybeSerializeDebug, SimpleArithmetic
 };
+use crate::runtime_primitives::weights::Weight;
 
 /// New trait for querying a single fixed value from a type.
 pub trait Get<T> {
@@ -105,12 +106,12 @@ pub enum UpdateBalanceOutcome {
 /// It operates over a single generic `AccountId` type.
 pub trait MakePayment<AccountId> {
 	/// Make transaction payment from `who` for an extrinsic of encoded length
-	/// `encoded_len` bytes. Return `Ok` iff the payment was successful.
-	fn make_payment(who: &AccountId, encoded_len: usize) -> Result<(), &'static str>;
+	/// `encoded_len` bytes and weight `weight`. Return `Ok` iff the payment was successful.
+	fn make_payment(who: &AccountId, encoded_len: usize, weight: Weight) -> Result<(), &'static str>;
 }
 
 impl<T> MakePayment<T> for () {
-	fn make_payment(_: &T, _: usize) -> Result<(), &'static str> { Ok(()) }
+	fn make_payment(_: &T, _: usize, _: Weight) -> Result<(), &'static str> { Ok(()) }
 }
 
 /// Handler for when some currency "account" decreased in balance for
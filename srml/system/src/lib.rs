@@ -82,6 +82,7 @@ use primitives::traits::{self, CheckEqual, SimpleArithmetic, SimpleBitOps, One,
 };
 #[cfg(any(feature = "std", test))]
 use primitives::traits::Zero;
+use primitives::weights::Weight;
 use substrate_primitives::storage::well_known_keys;
 use srml_support::{
 	storage, decl_module, decl_event, decl_storage, StorageDoubleMap, StorageValue,
@@ -303,6 +304,8 @@ decl_storage! {
 		ExtrinsicCount: Option<u32>;
 		/// Total length in bytes for all extrinsics put together, for the current block.
 		AllExtrinsicsLen: Option<u32>;
+		/// Total weight of all extrinsics put together, for the current block.
+		AllExtrinsicsWeight: Option<Weight>;
 		/// Map of block numbers to block hashes.
 		pub BlockHash get(block_hash) build(|_| vec![(T::BlockNumber::zero(), hash69())]): map T::BlockNumber => T::Hash;
 		/// Extrinsics data for the current block (maps an extrinsic's index to its data).
@@ -343,9 +346,15 @@ decl_storage! {
 		/// no notification will be triggered thus the event might be lost.
 		EventTopics get(event_topics): double_map hasher(blake2_256) (), blake2_256(T::Hash)
 			=> Vec<(T::BlockNumber, EventIndex)>;
+		/// The `spec_version` of the runtime which last executed the `on_runtime_upgrade` hooks of
+		/// the modules. Unset on chains started before upgrades were tracked.
+		LastRuntimeUpgrade get(last_runtime_upgrade): Option<u32>;
 	}
 	add_extra_genesis {
 		config(changes_trie_config): Option<ChangesTrieConfiguration>;
+		/// The `spec_version` of the genesis runtime, whose storage needs no migration. The
+		/// `on_runtime_upgrade` hooks run on the first block when it is not given.
+		config(runtime_spec_version): Option<u32>;
 
 		build(|storage: &mut primitives::StorageOverlay, _: &mut primitives::ChildrenStorageOverlay, config: &GenesisConfig<T>| {
 			use parity_codec::Encode;
@@ -357,6 +366,10 @@ decl_storage! {
 					well_known_keys::CHANGES_TRIE_CONFIG.to_vec(),
 					changes_trie_config.encode());
 			}
+
+			if let Some(spec_version) = config.runtime_spec_version {
+				runtime_io::with_storage(storage, || <Module<T>>::note_runtime_upgrade(spec_version));
+			}
 		});
 	}
 }
@@ -464,6 +477,11 @@ impl<T: Trait> Module<T> {
 		<AllExtrinsicsLen<T>>::get().unwrap_or_default()
 	}
 
+	/// Gets a total weight of all executed extrinsics.
+	pub fn all_extrinsics_weight() -> Weight {
+		<AllExtrinsicsWeight<T>>::get().unwrap_or_default()
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize(number: &T::BlockNumber, parent_hash: &T::Hash, txs_root: &T::Hash) {
 		// populate environment
@@ -487,6 +505,7 @@ impl<T: Trait> Module<T> {
 	pub fn finalize() -> T::Header {
 		<ExtrinsicCount<T>>::kill();
 		<AllExtrinsicsLen<T>>::kill();
+		<AllExtrinsicsWeight<T>>::kill();
 
 		let number = <Number<T>>::take();
 		let parent_hash = <ParentHash<T>>::take();
@@ -606,6 +625,11 @@ impl<T: Trait> Module<T> {
 		<AccountNonce<T>>::insert(who, Self::account_nonce(who) + T::Index::one());
 	}
 
+	/// Record that the runtime of given `spec_version` executed the `on_runtime_upgrade` hooks.
+	pub fn note_runtime_upgrade(spec_version: u32) {
+		<LastRuntimeUpgrade<T>>::put(spec_version);
+	}
+
 	/// Note what the extrinsic data of the current extrinsic index is. If this is called, then
 	/// ensure `derive_extrinsics` is also called before block-building is completed.
 	///
@@ -616,7 +640,7 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// To be called immediately after an extrinsic has been applied.
-	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, encoded_len: u32) {
+	pub fn note_applied_extrinsic(r: &Result<(), &'static str>, encoded_len: u32, weight: Weight) {
 		Self::deposit_event(match r {
 			Ok(_) => Event::ExtrinsicSuccess,
 			Err(_) => Event::ExtrinsicFailed,
@@ -624,9 +648,11 @@ impl<T: Trait> Module<T> {
 
 		let next_extrinsic_index = Self::extrinsic_index().unwrap_or_default() + 1u32;
 		let total_length = encoded_len.saturating_add(Self::all_extrinsics_len());
+		let total_weight = weight.saturating_add(Self::all_extrinsics_weight());
 
 		storage::unhashed::put(well_known_keys::EXTRINSIC_INDEX, &next_extrinsic_index);
 		<AllExtrinsicsLen<T>>::put(&total_length);
+		<AllExtrinsicsWeight<T>>::put(&total_weight);
 	}
 
 	/// To be called immediately after `note_applied_extrinsic` of the last extrinsic of the block
@@ -739,8 +765,8 @@ mod tests {
 
 			System::initialize(&2, &[0u8; 32].into(), &[0u8; 32].into());
 			System::deposit_event(42u16);
-			System::note_applied_extrinsic(&Ok(()), 0);
-			System::note_applied_extrinsic(&Err(""), 0);
+			System::note_applied_extrinsic(&Ok(()), 0, 0);
+			System::note_applied_extrinsic(&Err(""), 0, 0);
 			System::note_finished_extrinsics();
 			System::deposit_event(3u16);
 			System::finalize();
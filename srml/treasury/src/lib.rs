@@ -359,6 +359,7 @@ mod tests {
 			balances: vec![(0, 100), (1, 99), (2, 1)],
 			transaction_base_fee: 0,
 			transaction_byte_fee: 0,
+			transaction_weight_fee: 0,
 			transfer_fee: 0,
 			creation_fee: 0,
 			existential_deposit: 0,